@@ -4,8 +4,13 @@
 //! including both built-in and custom user-defined commands.
 
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
-use crate::config::{get_custom_commands_path};
+use std::path::Path;
+use sysinfo::Networks;
+use crate::config::{atomic_write, get_custom_commands_path, get_command_variables, get_recent_commands_path, known_placeholders,
+                    get_dangerous_patterns};
 
 /// A command template with name, command string, description, and category
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -14,6 +19,85 @@ pub struct CommandTemplate {
     pub command: String,
     pub description: String,
     pub category: String,
+    /// Send Enter after inserting the command so it runs immediately
+    #[serde(default, alias = "auto_run", skip_serializing_if = "is_false")]
+    pub autorun: bool,
+    /// Optional link to documentation, e.g. a HackTricks page or man page
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reference: String,
+    /// Target operating system the command is meant for
+    #[serde(default, skip_serializing_if = "CommandOs::is_any")]
+    pub os: CommandOs,
+}
+
+/// Operating system a command template applies to
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandOs {
+    #[default]
+    #[serde(alias = "Any")]
+    Any,
+    #[serde(alias = "Linux")]
+    Linux,
+    #[serde(alias = "Windows")]
+    Windows,
+}
+
+impl CommandOs {
+    pub const ALL: [CommandOs; 3] = [Self::Any, Self::Linux, Self::Windows];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Any => "Any",
+            Self::Linux => "Linux",
+            Self::Windows => "Windows",
+        }
+    }
+
+    fn is_any(&self) -> bool {
+        *self == Self::Any
+    }
+
+    /// True if a command tagged with this OS suits the chosen OS; `Any` matches everything
+    pub fn matches(&self, os: CommandOs) -> bool {
+        self.is_any() || os.is_any() || *self == os
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Collapses whitespace and lowercases text so patterns match regardless of spacing
+fn normalize_command(text: &str) -> String {
+    text.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `pattern` occurs in `text` starting at a word boundary
+///
+/// Single-word patterns such as `halt` must end at one too, so `asphalt` and
+/// `reboot_test.py` don't match while `mkfs.ext4` and `rm -rfv` still do.
+fn contains_at_boundary(text: &str, pattern: &str) -> bool {
+    let check_start = pattern.starts_with(is_word_char);
+    let check_end = !pattern.contains(' ') && pattern.ends_with(is_word_char);
+    text.match_indices(pattern).any(|(start, _)| {
+        let end = start + pattern.len();
+        (!check_start || !text[..start].ends_with(is_word_char))
+            && (!check_end || !text[end..].starts_with(is_word_char))
+    })
+}
+
+/// Returns true if the command contains one of the configured dangerous patterns
+pub fn is_dangerous_command(command: &str) -> bool {
+    let normalized = normalize_command(command);
+    get_dangerous_patterns()
+        .iter()
+        .map(|pattern| normalize_command(pattern))
+        .any(|pattern| !pattern.is_empty() && contains_at_boundary(&normalized, &pattern))
 }
 
 /// Container for a list of command templates (for YAML serialization)
@@ -39,17 +123,10 @@ pub fn load_command_templates() -> Vec<CommandTemplate> {
         }
     }
     
-    // Load custom commands
-    let custom_path = get_custom_commands_path();
-    if custom_path.exists() {
-        if let Ok(content) = fs::read_to_string(&custom_path) {
-            match serde_yaml::from_str::<CommandsConfig>(&content) {
-                Ok(config) => commands.extend(config.commands),
-                Err(e) => {
-                    log::warn!("Failed to parse custom_commands.yaml: {}", e);
-                }
-            }
-        }
+    // Load custom commands; a broken file leaves the built-in commands usable
+    match read_custom_commands() {
+        Ok(custom) => commands.extend(custom),
+        Err(e) => log::warn!("{}", e),
     }
     
     commands
@@ -59,15 +136,8 @@ pub fn load_command_templates() -> Vec<CommandTemplate> {
 pub fn save_custom_command(command: CommandTemplate) -> Result<(), String> {
     let custom_path = get_custom_commands_path();
     
-    // Load existing custom commands
-    let mut commands = Vec::new();
-    if custom_path.exists() {
-        if let Ok(content) = fs::read_to_string(&custom_path) {
-            if let Ok(config) = serde_yaml::from_str::<CommandsConfig>(&content) {
-                commands = config.commands;
-            }
-        }
-    }
+    // Load existing custom commands; never overwrite a file that failed to parse
+    let mut commands = read_custom_commands()?;
     
     // Add new command
     commands.push(command);
@@ -75,22 +145,30 @@ pub fn save_custom_command(command: CommandTemplate) -> Result<(), String> {
     // Save back to file
     let config = CommandsConfig { commands };
     let yaml = serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
-    fs::write(&custom_path, yaml).map_err(|e| format!("Failed to write file: {}", e))?;
+    atomic_write(&custom_path, yaml).map_err(|e| format!("Failed to write file: {}", e))?;
     
     Ok(())
 }
 
-/// Loads only custom commands from the config file
-pub fn load_custom_commands() -> Vec<CommandTemplate> {
+/// Reads custom commands, failing with the file name and parse error if the file is malformed
+pub fn read_custom_commands() -> Result<Vec<CommandTemplate>, String> {
     let custom_path = get_custom_commands_path();
-    if custom_path.exists() {
-        if let Ok(content) = fs::read_to_string(&custom_path) {
-            if let Ok(config) = serde_yaml::from_str::<CommandsConfig>(&content) {
-                return config.commands;
-            }
-        }
+    if !custom_path.exists() {
+        return Ok(Vec::new());
     }
-    Vec::new()
+    let content = fs::read_to_string(&custom_path)
+        .map_err(|e| format!("Failed to read {}: {}", custom_path.display(), e))?;
+    serde_yaml::from_str::<CommandsConfig>(&content)
+        .map(|config| config.commands)
+        .map_err(|e| format!("Failed to parse {}: {}", custom_path.display(), e))
+}
+
+/// Loads only custom commands from the config file
+pub fn load_custom_commands() -> Vec<CommandTemplate> {
+    read_custom_commands().unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        Vec::new()
+    })
 }
 
 /// Saves the entire list of custom commands
@@ -98,13 +176,77 @@ pub fn save_custom_commands_list(commands: Vec<CommandTemplate>) -> Result<(), S
     let custom_path = get_custom_commands_path();
     let config = CommandsConfig { commands };
     let yaml = serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
-    fs::write(&custom_path, yaml).map_err(|e| format!("Failed to write file: {}", e))?;
+    atomic_write(&custom_path, yaml).map_err(|e| format!("Failed to write file: {}", e))?;
     Ok(())
 }
 
+/// Merges commands from a YAML command pack into the custom commands,
+/// skipping entries whose name and command already exist.
+///
+/// Returns the number of commands added.
+pub fn import_custom_commands(path: &Path) -> Result<usize, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let pack = serde_yaml::from_str::<CommandsConfig>(&content)
+        .map_err(|e| format!("Failed to parse command pack: {}", e))?;
+
+    let mut commands = read_custom_commands()?;
+    let mut added = 0;
+    for command in pack.commands {
+        let duplicate = commands
+            .iter()
+            .any(|c| c.name == command.name && c.command == command.command);
+        if !duplicate {
+            commands.push(command);
+            added += 1;
+        }
+    }
+
+    if added > 0 {
+        save_custom_commands_list(commands)?;
+    }
+    Ok(added)
+}
+
+/// Writes all custom commands to a YAML command pack, returning how many were exported
+pub fn export_custom_commands(path: &Path) -> Result<usize, String> {
+    let commands = load_custom_commands();
+    let count = commands.len();
+    let config = CommandsConfig { commands };
+    let yaml = serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
+    fs::write(path, yaml).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(count)
+}
+
+/// Maximum number of commands kept in the drawer's Recent section
+pub const MAX_RECENT_COMMANDS: usize = 8;
+
+/// Loads recently used command strings, most recent first
+pub fn load_recent_commands() -> Vec<String> {
+    fs::read_to_string(get_recent_commands_path())
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Moves a command to the front of the recent list and persists it
+pub fn record_recent_command(command: &str) -> Result<(), String> {
+    let entry = command.replace('\n', " ");
+    let mut recent = load_recent_commands();
+    recent.retain(|c| *c != entry);
+    recent.insert(0, entry);
+    recent.truncate(MAX_RECENT_COMMANDS);
+    fs::write(get_recent_commands_path(), recent.join("\n"))
+        .map_err(|e| format!("Failed to write recent commands: {}", e))
+}
+
 /// Deletes a custom command by index
 pub fn delete_custom_command(index: usize) -> Result<(), String> {
-    let mut commands = load_custom_commands();
+    let mut commands = read_custom_commands()?;
     if index < commands.len() {
         commands.remove(index);
         save_custom_commands_list(commands)?;
@@ -114,9 +256,22 @@ pub fn delete_custom_command(index: usize) -> Result<(), String> {
     }
 }
 
+/// Moves a custom command to a new position in the list
+pub fn move_custom_command(from: usize, to: usize) -> Result<(), String> {
+    let mut commands = read_custom_commands()?;
+    if from < commands.len() && to < commands.len() {
+        let command = commands.remove(from);
+        commands.insert(to, command);
+        save_custom_commands_list(commands)?;
+        Ok(())
+    } else {
+        Err("Invalid command index".to_string())
+    }
+}
+
 /// Updates a custom command by index
 pub fn update_custom_command(index: usize, command: CommandTemplate) -> Result<(), String> {
-    let mut commands = load_custom_commands();
+    let mut commands = read_custom_commands()?;
     if index < commands.len() {
         commands[index] = command;
         save_custom_commands_list(commands)?;
@@ -125,3 +280,222 @@ pub fn update_custom_command(index: usize, command: CommandTemplate) -> Result<(
         Err("Invalid command index".to_string())
     }
 }
+
+/// Key used to group categories case-insensitively, so "Recon" and "recon" merge
+pub fn category_key(category: &str) -> String {
+    category.trim().to_lowercase()
+}
+
+/// Collects the distinct categories in order of first appearance, keeping the first spelling seen
+pub fn command_categories(commands: &[CommandTemplate]) -> Vec<String> {
+    let mut categories: Vec<String> = Vec::new();
+    for cmd in commands {
+        let key = category_key(&cmd.category);
+        if !categories.iter().any(|c| category_key(c) == key) {
+            categories.push(cmd.category.trim().to_string());
+        }
+    }
+    categories
+}
+
+/// Indices of the commands in a category, in file order or alphabetically by name
+pub fn category_command_indices(commands: &[CommandTemplate], key: &str, alphabetical: bool) -> Vec<usize> {
+    let mut indices: Vec<usize> = commands
+        .iter()
+        .enumerate()
+        .filter(|(_, cmd)| category_key(&cmd.category) == key)
+        .map(|(idx, _)| idx)
+        .collect();
+    if alphabetical {
+        // Stable sort keeps file order for commands with the same name
+        indices.sort_by_key(|&idx| commands[idx].name.to_lowercase());
+    }
+    indices
+}
+
+/// Extracts the unique `{name}` placeholders in a command, in order of appearance
+///
+/// Shell syntax such as `${VAR}` and awk blocks like `{print $1}` are ignored.
+pub fn extract_placeholders(command: &str) -> Vec<String> {
+    let mut placeholders: Vec<String> = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(offset) = command[search_from..].find('{') {
+        let open = search_from + offset;
+        if let Some(len) = command[open + 1..].find('}') {
+            let name = &command[open + 1..open + 1 + len];
+            let is_placeholder = !name.is_empty()
+                && !command[..open].ends_with('$')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if is_placeholder && !placeholders.iter().any(|p| p == name) {
+                placeholders.push(name.to_string());
+            }
+        }
+        search_from = open + 1;
+    }
+
+    placeholders
+}
+
+/// Returns the placeholders in a command that are neither built in nor command variables
+pub fn unknown_placeholders(command: &str) -> Vec<String> {
+    let known = known_placeholders();
+    extract_placeholders(command)
+        .into_iter()
+        .filter(|name| !known.contains(name))
+        .collect()
+}
+
+/// Replaces every `{name}` placeholder with its value from the map
+///
+/// The command is scanned once from left to right, so braces inside a
+/// substituted value are never expanded again. Placeholders without a value
+/// and shell syntax are copied unchanged, following `extract_placeholders`.
+pub fn fill_placeholders(command: &str, values: &HashMap<String, String>) -> String {
+    let mut filled = String::with_capacity(command.len());
+    let mut copied_to = 0;
+    let mut search_from = 0;
+
+    while let Some(offset) = command[search_from..].find('{') {
+        let open = search_from + offset;
+        search_from = open + 1;
+        let Some(len) = command[open + 1..].find('}') else {
+            break;
+        };
+        let name = &command[open + 1..open + 1 + len];
+        if command[..open].ends_with('$') || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        if let Some(value) = values.get(name) {
+            filled.push_str(&command[copied_to..open]);
+            filled.push_str(value);
+            copied_to = open + len + 2;
+            search_from = copied_to;
+        }
+    }
+
+    filled.push_str(&command[copied_to..]);
+    filled
+}
+
+/// Command variables offered in settings even before they are given a value
+pub const DEFAULT_COMMAND_VARIABLES: [&str; 3] = ["lhost", "lport", "interface"];
+
+thread_local! {
+    /// Default interface from the system monitor's last network refresh; None until it first runs
+    static DEFAULT_INTERFACE: RefCell<Option<Option<(String, String)>>> = const { RefCell::new(None) };
+}
+
+/// Picks a sensible default network interface and its IPv4 address
+///
+/// VPN tunnels are preferred since engagements usually run over them, while
+/// container and virtual bridges are only used as a last resort.
+fn pick_default_interface(networks: &Networks) -> Option<(String, String)> {
+    let mut candidates: Vec<(u8, String, String)> = networks
+        .iter()
+        .filter_map(|(name, data)| {
+            let addr = data
+                .ip_networks()
+                .iter()
+                .find(|net| net.addr.is_ipv4() && !net.addr.is_loopback())?
+                .addr;
+            let rank = if ["tun", "tap", "wg"].iter().any(|p| name.starts_with(p)) {
+                0
+            } else if ["docker", "br-", "virbr", "veth", "podman"].iter().any(|p| name.starts_with(p)) {
+                2
+            } else {
+                1
+            };
+            Some((rank, name.clone(), addr.to_string()))
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next().map(|(_, name, addr)| (name, addr))
+}
+
+/// Records the default interface from the system monitor's refreshed network list
+pub fn update_default_interface(networks: &Networks) {
+    DEFAULT_INTERFACE.with(|cached| *cached.borrow_mut() = Some(pick_default_interface(networks)));
+}
+
+/// The default network interface and its IPv4 address
+///
+/// Reuses what the system monitor found on its last refresh, and only scans the
+/// interfaces itself before the monitor has run.
+pub fn detect_default_interface() -> Option<(String, String)> {
+    DEFAULT_INTERFACE
+        .with(|cached| cached.borrow().clone())
+        .unwrap_or_else(|| pick_default_interface(&Networks::new_with_refreshed_list()))
+}
+
+/// Builds the variables substituted into commands: detected defaults overridden by saved values
+pub fn resolve_command_variables() -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    if let Some((interface, addr)) = detect_default_interface() {
+        variables.insert("interface".to_string(), interface);
+        variables.insert("lhost".to_string(), addr);
+    }
+    for (name, value) in get_command_variables() {
+        if !value.trim().is_empty() {
+            variables.insert(name, value);
+        }
+    }
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_placeholders_skips_shell_syntax() {
+        assert_eq!(extract_placeholders("nmap -p {port} {target} -oN {target}.txt"), vec!["port", "target"]);
+        assert!(extract_placeholders("echo ${HOME} ${target}").is_empty());
+        assert!(extract_placeholders("cut -d: -f1 /etc/passwd | awk '{print $1}'").is_empty());
+        assert_eq!(extract_placeholders("awk '{print $1}' {file}"), vec!["file"]);
+    }
+
+    #[test]
+    fn test_fill_placeholders_leaves_shell_syntax() {
+        let values = HashMap::from([
+            ("target".to_string(), "10.0.0.5".to_string()),
+            ("print $1".to_string(), "oops".to_string()),
+        ]);
+        assert_eq!(
+            fill_placeholders("ping {target} && echo ${target} | awk '{print $1}'", &values),
+            "ping 10.0.0.5 && echo ${target} | awk '{print $1}'"
+        );
+        assert_eq!(fill_placeholders("curl {url} {target}", &values), "curl {url} 10.0.0.5");
+    }
+
+    #[test]
+    fn test_fill_placeholders_does_not_expand_values() {
+        let values = HashMap::from([
+            ("a".to_string(), "{b}".to_string()),
+            ("b".to_string(), "x".to_string()),
+        ]);
+        assert_eq!(fill_placeholders("{a} {b}", &values), "{b} x");
+    }
+
+    #[test]
+    fn test_dangerous_commands() {
+        assert!(is_dangerous_command("rm -rf /"));
+        assert!(is_dangerous_command("sudo  RM   -rf /tmp/x"));
+        assert!(is_dangerous_command("mkfs.ext4 /dev/sdb1"));
+        assert!(is_dangerous_command("dd of=/dev/sda bs=4M"));
+        assert!(is_dangerous_command("dd if=image.iso of=/dev/sdb"));
+        assert!(!is_dangerous_command("./rmtool --help"));
+        assert!(!is_dangerous_command("python3 reboot_test.py"));
+        assert!(!is_dangerous_command("echo asphalt"));
+    }
+
+    #[test]
+    fn test_contains_at_boundary() {
+        assert!(contains_at_boundary("rm -rf /", "rm"));
+        assert!(!contains_at_boundary("form", "rm"));
+        assert!(!contains_at_boundary("./rmtool", "rm"));
+        assert!(contains_at_boundary("mkfs.ext4", "mkfs"));
+        assert!(contains_at_boundary("rm -rfv /", "rm -rf"));
+        assert!(contains_at_boundary("echo hi > /dev/sda", "> /dev/sd"));
+    }
+}
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::process::{Command, Output};
 use std::path::PathBuf;
 use std::fs;
-use crate::config::{get_config_dir, get_base_dir, is_flatpak};
+use crate::config::{atomic_write, get_config_dir, get_base_dir, is_flatpak};
 
 /// Container runtime choice - podman or docker
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
@@ -1716,7 +1716,7 @@ pub fn save_container_config(config: &ContainerConfig) -> Result<(), String> {
     let yaml = serde_yaml::to_string(config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    fs::write(&path, yaml)
+    atomic_write(&path, yaml)
         .map_err(|e| format!("Failed to write config: {}", e))?;
 
     Ok(())
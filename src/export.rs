@@ -0,0 +1,108 @@
+//! Project export for PenEnv
+//!
+//! Bundles the base directory's project files and custom commands into a zip archive.
+
+use chrono::{Datelike, Local, Timelike};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use crate::config::{get_base_dir, get_custom_commands_path};
+use crate::report::{REPORT_FILE, REPORT_TEMPLATE_FILE};
+
+/// Project files always included when present in the base directory
+const PROJECT_FILES: &[&str] = &["targets.txt", "notes.md", "findings.yaml", "commands.log"];
+
+/// Collects the files to bundle as (archive name, source path) pairs
+fn project_files() -> Vec<(String, PathBuf)> {
+    let base_dir = get_base_dir();
+    let mut files: Vec<(String, PathBuf)> = PROJECT_FILES
+        .iter()
+        .map(|name| (name.to_string(), base_dir.join(name)))
+        .filter(|(_, path)| path.is_file())
+        .collect();
+
+    // Extra note tabs are markdown files next to notes.md
+    if let Ok(entries) = fs::read_dir(&base_dir) {
+        let mut notes: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+            .filter_map(|path| Some((path.file_name()?.to_string_lossy().to_string(), path)))
+            .filter(|(name, _)| !PROJECT_FILES.contains(&name.as_str()))
+            .filter(|(name, _)| name != REPORT_FILE && name != REPORT_TEMPLATE_FILE)
+            .collect();
+        notes.sort();
+        files.extend(notes);
+    }
+
+    let custom_commands = get_custom_commands_path();
+    if custom_commands.is_file() {
+        files.push(("custom_commands.yaml".to_string(), custom_commands));
+    }
+    files
+}
+
+/// Builds the manifest listing the bundled files with their modification times
+fn build_manifest(files: &[(String, PathBuf)]) -> String {
+    let mut manifest = format!(
+        "PenEnv project export\nVersion: {}\nExported: {}\nBase directory: {}\n\nFiles:\n",
+        env!("CARGO_PKG_VERSION"),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        get_base_dir().display(),
+    );
+    for (name, path) in files {
+        let modified = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|time| chrono::DateTime::<Local>::from(time).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        manifest.push_str(&format!("  {} (modified {})\n", name, modified));
+    }
+    manifest
+}
+
+/// Writes the project files and a manifest to a zip archive, returning the number of files bundled
+pub fn export_project(dest: &Path) -> Result<usize, String> {
+    let files = project_files();
+    if files.is_empty() {
+        return Err("No project files to export".to_string());
+    }
+
+    let now = Local::now();
+    let timestamp = DateTime::from_date_and_time(
+        now.year() as u16,
+        now.month() as u8,
+        now.day() as u8,
+        now.hour() as u8,
+        now.minute() as u8,
+        now.second() as u8,
+    )
+    .unwrap_or_default();
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(timestamp);
+
+    let file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    zip.start_file("MANIFEST.txt", options)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    zip.write_all(build_manifest(&files).as_bytes())
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    for (name, path) in &files {
+        let mut source = fs::File::open(path)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    Ok(files.len())
+}
@@ -0,0 +1,125 @@
+//! Findings tracking for PenEnv
+//!
+//! Findings are discrete issues with a severity and status, stored in
+//! `findings.yaml` in the base directory alongside targets and notes.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use crate::config::{atomic_write, get_file_path};
+
+/// How serious a finding is, ordered from least to most severe
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Low,
+    #[serde(alias = "Med")]
+    Medium,
+    High,
+    #[serde(alias = "Crit")]
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [Self::Info, Self::Low, Self::Medium, Self::High, Self::Critical];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+/// Where a finding stands in the engagement
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum FindingStatus {
+    Open,
+    Confirmed,
+    Remediated,
+}
+
+impl FindingStatus {
+    pub const ALL: [FindingStatus; 3] = [Self::Open, Self::Confirmed, Self::Remediated];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::Confirmed => "Confirmed",
+            Self::Remediated => "Remediated",
+        }
+    }
+}
+
+/// A single finding with title, severity, status, and description
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Finding {
+    pub title: String,
+    pub severity: Severity,
+    pub status: FindingStatus,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Container for the findings list (for YAML serialization)
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FindingsConfig {
+    pub findings: Vec<Finding>,
+}
+
+/// Gets the path to findings.yaml in the base directory
+pub fn get_findings_path() -> PathBuf {
+    get_file_path("findings.yaml")
+}
+
+/// Loads findings from the base directory, returning an empty list if there are none
+pub fn load_findings() -> Vec<Finding> {
+    let path = get_findings_path();
+    if let Ok(content) = fs::read_to_string(&path) {
+        match serde_yaml::from_str::<FindingsConfig>(&content) {
+            Ok(config) => return config.findings,
+            Err(e) => log::warn!("Failed to parse {}: {}", path.display(), e),
+        }
+    }
+    Vec::new()
+}
+
+/// Saves the entire list of findings
+pub fn save_findings(findings: Vec<Finding>) -> Result<(), String> {
+    let config = FindingsConfig { findings };
+    let yaml = serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
+    atomic_write(&get_findings_path(), yaml).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(())
+}
+
+/// Adds a new finding to the end of the list
+pub fn add_finding(finding: Finding) -> Result<(), String> {
+    let mut findings = load_findings();
+    findings.push(finding);
+    save_findings(findings)
+}
+
+/// Updates a finding by index
+pub fn update_finding(index: usize, finding: Finding) -> Result<(), String> {
+    let mut findings = load_findings();
+    if index < findings.len() {
+        findings[index] = finding;
+        save_findings(findings)
+    } else {
+        Err("Invalid finding index".to_string())
+    }
+}
+
+/// Deletes a finding by index
+pub fn delete_finding(index: usize) -> Result<(), String> {
+    let mut findings = load_findings();
+    if index < findings.len() {
+        findings.remove(index);
+        save_findings(findings)
+    } else {
+        Err("Invalid finding index".to_string())
+    }
+}
@@ -15,6 +15,11 @@
 mod config;
 mod commands;
 mod container;
+mod export;
+mod findings;
+mod report;
+mod ssh;
+mod targets;
 mod ui;
 
 use gtk4::prelude::*;
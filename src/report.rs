@@ -0,0 +1,118 @@
+//! Report generation for PenEnv
+//!
+//! Assembles targets, findings and notes from the base directory into a single
+//! Markdown report. The layout comes from `report_template.md` in the base
+//! directory when present, otherwise from the built-in template.
+
+use chrono::Local;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::fill_placeholders;
+use crate::config::{atomic_write, get_base_dir, get_file_path, load_targets};
+use crate::findings::{load_findings, Finding};
+
+/// File the generated report is written to
+pub const REPORT_FILE: &str = "report.md";
+
+/// Optional template in the base directory that overrides the built-in one
+pub const REPORT_TEMPLATE_FILE: &str = "report_template.md";
+
+/// Built-in template; `{date}`, `{base_dir}`, `{targets}`, `{findings}` and `{notes}` are replaced
+pub const DEFAULT_REPORT_TEMPLATE: &str = "# Penetration Test Report
+
+**Date:** {date}
+**Project:** {base_dir}
+
+## Targets
+
+{targets}
+
+## Findings
+
+{findings}
+
+## Notes
+
+{notes}
+";
+
+/// Reads the template from the base directory, falling back to the built-in one
+fn load_report_template() -> String {
+    fs::read_to_string(get_file_path(REPORT_TEMPLATE_FILE))
+        .ok()
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REPORT_TEMPLATE.to_string())
+}
+
+/// Formats targets as a bullet list
+fn targets_section(targets: &[String]) -> String {
+    if targets.is_empty() {
+        return "_No targets recorded._".to_string();
+    }
+    targets
+        .iter()
+        .map(|target| format!("- `{}`", target.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats findings as subsections, most severe first
+fn findings_section(findings: &[Finding]) -> String {
+    if findings.is_empty() {
+        return "_No findings recorded._".to_string();
+    }
+    let mut sorted: Vec<&Finding> = findings.iter().collect();
+    sorted.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+    sorted
+        .iter()
+        .map(|finding| {
+            let mut section = format!(
+                "### [{}] {}\n\n**Status:** {}",
+                finding.severity.label(),
+                finding.title,
+                finding.status.label()
+            );
+            if !finding.description.trim().is_empty() {
+                section.push_str("\n\n");
+                section.push_str(finding.description.trim());
+            }
+            section
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Uses notes.md as-is, since it is already Markdown
+fn notes_section() -> String {
+    let notes = fs::read_to_string(get_file_path("notes.md")).unwrap_or_default();
+    if notes.trim().is_empty() {
+        "_No notes recorded._".to_string()
+    } else {
+        notes.trim().to_string()
+    }
+}
+
+/// Builds the report Markdown from the template and the project files
+pub fn generate_report() -> String {
+    let template = load_report_template();
+    // Substituted in one pass so braces in notes or findings are left alone
+    let fields = HashMap::from([
+        ("date".to_string(), Local::now().format("%Y-%m-%d").to_string()),
+        ("base_dir".to_string(), get_base_dir().display().to_string()),
+        ("targets".to_string(), targets_section(&load_targets())),
+        ("findings".to_string(), findings_section(&load_findings())),
+        ("notes".to_string(), notes_section()),
+    ]);
+    fill_placeholders(&template, &fields)
+}
+
+/// Generates the report and writes it to report.md in the base directory
+pub fn write_report() -> Result<PathBuf, String> {
+    let report = generate_report();
+    let path = get_file_path(REPORT_FILE);
+    atomic_write(&path, report).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
@@ -0,0 +1,111 @@
+//! SSH connection profiles for PenEnv
+//!
+//! Profiles are stored in `ssh_profiles.yaml` in the user's config directory,
+//! alongside custom_commands.yaml, and open shell tabs that run `ssh` directly.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use crate::config::{atomic_write, get_ssh_profiles_path};
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+/// A saved SSH connection
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SshProfile {
+    pub name: String,
+    pub host: String,
+    #[serde(default)]
+    pub user: String,
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub identity_file: String,
+}
+
+impl SshProfile {
+    /// Builds the `ssh` argument vector for this profile
+    pub fn command_args(&self) -> Vec<String> {
+        let mut args = vec!["ssh".to_string()];
+        if self.port != 22 {
+            args.push("-p".to_string());
+            args.push(self.port.to_string());
+        }
+        if !self.identity_file.trim().is_empty() {
+            args.push("-i".to_string());
+            args.push(self.identity_file.trim().to_string());
+        }
+        args.push(self.destination());
+        args
+    }
+
+    /// The `user@host` (or bare host) to connect to
+    pub fn destination(&self) -> String {
+        if self.user.trim().is_empty() {
+            self.host.trim().to_string()
+        } else {
+            format!("{}@{}", self.user.trim(), self.host.trim())
+        }
+    }
+}
+
+/// Container for the profiles list (for YAML serialization)
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct SshProfilesConfig {
+    pub profiles: Vec<SshProfile>,
+}
+
+/// Loads SSH profiles from the config directory
+pub fn load_ssh_profiles() -> Vec<SshProfile> {
+    let path = get_ssh_profiles_path();
+    if let Ok(content) = fs::read_to_string(&path) {
+        match serde_yaml::from_str::<SshProfilesConfig>(&content) {
+            Ok(config) => return config.profiles,
+            Err(e) => log::warn!("Failed to parse {}: {}", path.display(), e),
+        }
+    }
+    Vec::new()
+}
+
+/// Saves the entire list of SSH profiles
+pub fn save_ssh_profiles(profiles: Vec<SshProfile>) -> Result<(), String> {
+    let config = SshProfilesConfig { profiles };
+    let yaml = serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
+    atomic_write(&get_ssh_profiles_path(), yaml).map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(())
+}
+
+/// Adds a new SSH profile
+pub fn add_ssh_profile(profile: SshProfile) -> Result<(), String> {
+    let mut profiles = load_ssh_profiles();
+    profiles.push(profile);
+    save_ssh_profiles(profiles)
+}
+
+/// Updates an SSH profile by index
+pub fn update_ssh_profile(index: usize, profile: SshProfile) -> Result<(), String> {
+    let mut profiles = load_ssh_profiles();
+    if index < profiles.len() {
+        profiles[index] = profile;
+        save_ssh_profiles(profiles)
+    } else {
+        Err("Invalid profile index".to_string())
+    }
+}
+
+/// Deletes an SSH profile by index
+pub fn delete_ssh_profile(index: usize) -> Result<(), String> {
+    let mut profiles = load_ssh_profiles();
+    if index < profiles.len() {
+        profiles.remove(index);
+        save_ssh_profiles(profiles)
+    } else {
+        Err("Invalid profile index".to_string())
+    }
+}
+
+/// Finds a profile by name
+pub fn find_ssh_profile(name: &str) -> Option<SshProfile> {
+    load_ssh_profiles().into_iter().find(|p| p.name == name)
+}
@@ -0,0 +1,358 @@
+//! Parsing of targets.txt
+//!
+//! Every line that isn't blank or a `#` comment is one target: an IP address, a CIDR
+//! network (`10.0.0.0/24`), an IPv4 range (`10.0.0.1-20` or `10.0.0.1-10.0.0.20`), or
+//! anything else such as a hostname or URL, which is kept as written. Lines that look
+//! like an address but don't parse, such as `10.0.0.300` or `10.0.0.0/33`, are malformed;
+//! [`parse_targets_verbatim`] still keeps them as written, since they may be valid for
+//! the tool they are passed to (e.g. nmap's `10.0.1-3.1-254`).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// One entry of targets.txt
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// A single IPv4 or IPv6 address
+    Address(IpAddr),
+    /// A network in CIDR notation, kept as written even if host bits are set
+    Network(IpAddr, u8),
+    /// An inclusive IPv4 range
+    Range(Ipv4Addr, Ipv4Addr),
+    /// A hostname, URL or anything else that isn't an address
+    Host(String),
+}
+
+/// A malformed entry in targets.txt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number, starting at 1
+    pub line: usize,
+    pub entry: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.entry, self.reason)
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Address(address) => write!(f, "{}", address),
+            Target::Network(address, prefix) => write!(f, "{}/{}", address, prefix),
+            // nmap-style when the range stays within one /24
+            Target::Range(start, end) if start.octets()[..3] == end.octets()[..3] => {
+                write!(f, "{}-{}", start, end.octets()[3])
+            }
+            Target::Range(start, end) => write!(f, "{}-{}", start, end),
+            Target::Host(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+/// An address as an integer, with whether it is IPv4
+fn to_bits(address: IpAddr) -> (u128, bool) {
+    match address {
+        IpAddr::V4(v4) => (u32::from(v4) as u128, true),
+        IpAddr::V6(v6) => (u128::from(v6), false),
+    }
+}
+
+fn from_bits(bits: u128, is_v4: bool) -> IpAddr {
+    if is_v4 {
+        IpAddr::V4(Ipv4Addr::from(bits as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(bits))
+    }
+}
+
+impl Target {
+    /// First and last address covered, as integers, with whether they are IPv4
+    fn bounds(&self) -> Option<(u128, u128, bool)> {
+        match self {
+            Target::Address(address) => {
+                let (bits, is_v4) = to_bits(*address);
+                Some((bits, bits, is_v4))
+            }
+            Target::Network(address, prefix) => {
+                let (bits, is_v4) = to_bits(*address);
+                let host_bits = (if is_v4 { 32 } else { 128 }) - *prefix as u32;
+                let host_mask = if host_bits >= 128 { u128::MAX } else { (1u128 << host_bits) - 1 };
+                Some((bits & !host_mask, bits | host_mask, is_v4))
+            }
+            Target::Range(start, end) => Some((u32::from(*start) as u128, u32::from(*end) as u128, true)),
+            Target::Host(_) => None,
+        }
+    }
+
+    /// Number of addresses covered; a hostname counts as one
+    pub fn address_count(&self) -> u128 {
+        match self.bounds() {
+            Some((first, last, _)) => (last - first).saturating_add(1),
+            None => 1,
+        }
+    }
+
+    /// Checks if an address is this target or falls inside its network or range
+    pub fn contains(&self, address: IpAddr) -> bool {
+        let (bits, is_v4) = to_bits(address);
+        self.bounds()
+            .is_some_and(|(first, last, target_is_v4)| target_is_v4 == is_v4 && (first..=last).contains(&bits))
+    }
+
+    /// Every address covered, or None for hostnames and entries covering more than `limit` addresses
+    pub fn expand(&self, limit: usize) -> Option<Vec<IpAddr>> {
+        let (first, last, is_v4) = self.bounds()?;
+        if last - first >= limit as u128 {
+            return None;
+        }
+        Some((first..=last).map(|bits| from_bits(bits, is_v4)).collect())
+    }
+}
+
+/// Digits, dots, slashes and dashes only, like a mistyped IPv4 address, network or range
+fn looks_like_ipv4(entry: &str) -> bool {
+    entry.contains('.') && entry.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '/' | '-'))
+}
+
+/// Parses one entry, already trimmed
+pub fn parse_target(entry: &str) -> Result<Target, String> {
+    if let Ok(address) = entry.parse::<IpAddr>() {
+        return Ok(Target::Address(address));
+    }
+
+    if let Some((address, prefix)) = entry.split_once('/') {
+        if let Ok(address) = address.parse::<IpAddr>() {
+            let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+            return match prefix.parse::<u8>() {
+                Ok(prefix) if prefix <= max_prefix => Ok(Target::Network(address, prefix)),
+                _ => Err(format!("invalid prefix length /{}", prefix)),
+            };
+        }
+    }
+
+    if let Some((start, end)) = entry.split_once('-') {
+        if let Ok(start) = start.parse::<Ipv4Addr>() {
+            let end = if end.contains('.') {
+                end.parse::<Ipv4Addr>().map_err(|_| format!("invalid range end {}", end))?
+            } else {
+                let last = end.parse::<u8>().map_err(|_| format!("invalid range end {}", end))?;
+                let [a, b, c, _] = start.octets();
+                Ipv4Addr::new(a, b, c, last)
+            };
+            if end < start {
+                return Err("range ends before it starts".to_string());
+            }
+            return Ok(Target::Range(start, end));
+        }
+    }
+
+    if looks_like_ipv4(entry) {
+        return Err("not a valid IPv4 address, network or range".to_string());
+    }
+    Ok(Target::Host(entry.to_string()))
+}
+
+/// Trimmed entries with their line numbers (starting at 1), without blank lines and comments
+fn target_entries(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, entry)| !entry.is_empty() && !entry.starts_with('#'))
+}
+
+/// Parses targets.txt content, skipping blank lines, comments and duplicates.
+/// Malformed entries are left out and returned separately.
+pub fn parse_targets_lenient(content: &str) -> (Vec<Target>, Vec<ParseError>) {
+    let mut targets = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for (line, entry) in target_entries(content) {
+        match parse_target(entry) {
+            Ok(target) => {
+                if seen.insert(target.clone()) {
+                    targets.push(target);
+                }
+            }
+            Err(reason) => errors.push(ParseError { line, entry: entry.to_string(), reason }),
+        }
+    }
+    (targets, errors)
+}
+
+/// Parses targets.txt content, skipping blank lines, comments and duplicates.
+/// Malformed entries stay in place as a [`Target::Host`] holding the line as written.
+pub fn parse_targets_verbatim(content: &str) -> Vec<Target> {
+    let mut seen = HashSet::new();
+    target_entries(content)
+        .map(|(_, entry)| parse_target(entry).unwrap_or_else(|_| Target::Host(entry.to_string())))
+        .filter(|target| seen.insert(target.clone()))
+        .collect()
+}
+
+/// Parses targets.txt content, failing on the first malformed entry
+pub fn parse_targets(content: &str) -> Result<Vec<Target>, ParseError> {
+    let (targets, errors) = parse_targets_lenient(content);
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(targets),
+    }
+}
+
+/// Trimmed targets.txt entries exactly as written, skipping blank lines, comments and
+/// lines that parse to a target listed earlier
+pub fn target_lines(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    target_entries(content)
+        .filter(|(_, entry)| seen.insert(parse_target(entry).unwrap_or_else(|_| Target::Host(entry.to_string()))))
+        .map(|(_, entry)| entry.to_string())
+        .collect()
+}
+
+/// Targets as text with networks and ranges of up to `limit` addresses listed one by one
+pub fn expand_targets(targets: &[Target], limit: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    targets
+        .iter()
+        .flat_map(|target| match target.expand(limit) {
+            Some(addresses) => addresses.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            None => vec![target.to_string()],
+        })
+        .filter(|target| seen.insert(target.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_addresses_and_hosts() {
+        assert_eq!(parse_target("10.0.0.5"), Ok(Target::Address(ip("10.0.0.5"))));
+        assert_eq!(parse_target("fe80::1"), Ok(Target::Address(ip("fe80::1"))));
+        assert_eq!(parse_target("dc01.corp.local"), Ok(Target::Host("dc01.corp.local".to_string())));
+        assert_eq!(parse_target("my-host"), Ok(Target::Host("my-host".to_string())));
+        assert_eq!(
+            parse_target("https://10.0.0.5:8443/login?a=1"),
+            Ok(Target::Host("https://10.0.0.5:8443/login?a=1".to_string()))
+        );
+        assert_eq!(parse_target("10.0.0.5:8080"), Ok(Target::Host("10.0.0.5:8080".to_string())));
+    }
+
+    #[test]
+    fn test_cidr_expansion() {
+        let network = parse_target("10.0.0.0/30").unwrap();
+        assert_eq!(network, Target::Network(ip("10.0.0.0"), 30));
+        assert_eq!(network.address_count(), 4);
+        assert_eq!(
+            network.expand(16),
+            Some(vec![ip("10.0.0.0"), ip("10.0.0.1"), ip("10.0.0.2"), ip("10.0.0.3")])
+        );
+        assert_eq!(network.expand(3), None);
+
+        // Host bits are kept as written but don't change the covered network
+        let network = parse_target("192.168.1.77/24").unwrap();
+        assert_eq!(network.to_string(), "192.168.1.77/24");
+        assert_eq!(network.address_count(), 256);
+        assert!(network.contains(ip("192.168.1.0")));
+        assert!(network.contains(ip("192.168.1.255")));
+        assert!(!network.contains(ip("192.168.2.1")));
+
+        assert_eq!(parse_target("10.0.0.5/32").unwrap().expand(1), Some(vec![ip("10.0.0.5")]));
+        assert_eq!(parse_target("0.0.0.0/0").unwrap().address_count(), 1 << 32);
+        assert_eq!(parse_target("::/0").unwrap().address_count(), u128::MAX);
+        assert!(!parse_target("fe80::/10").unwrap().contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let short = parse_target("10.0.0.1-3").unwrap();
+        assert_eq!(short, Target::Range(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 3)));
+        assert_eq!(short.expand(10), Some(vec![ip("10.0.0.1"), ip("10.0.0.2"), ip("10.0.0.3")]));
+
+        let long = parse_target("10.0.0.250-10.0.1.5").unwrap();
+        assert_eq!(long.address_count(), 12);
+        assert!(long.contains(ip("10.0.1.0")));
+        assert_eq!(long.to_string(), "10.0.0.250-10.0.1.5");
+
+        // Ranges within one /24 are written nmap-style
+        assert_eq!(parse_target("10.0.0.1-10.0.0.20").unwrap().to_string(), "10.0.0.1-20");
+    }
+
+    #[test]
+    fn test_malformed_entries() {
+        assert!(parse_target("10.0.0.300").is_err());
+        assert!(parse_target("10.0.0").is_err());
+        assert!(parse_target("10.0.0.0/33").is_err());
+        assert!(parse_target("fe80::/129").is_err());
+        assert!(parse_target("10.0.0.0/abc").is_err());
+        assert!(parse_target("10.0.0.9-3").is_err());
+        assert!(parse_target("10.0.0.1-300").is_err());
+        assert!(parse_target("10.0.0.1-10.0.0").is_err());
+
+        let error = parse_targets("10.0.0.1\n\n10.0.0.256\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.entry, "10.0.0.256");
+        assert!(error.to_string().starts_with("line 3: 10.0.0.256"));
+    }
+
+    #[test]
+    fn test_comments_blank_lines_and_duplicates() {
+        let content = "# Scope\n\n  10.0.0.5  \nexample.com\n10.0.0.5\n# 10.0.0.6\n10.0.0.0/24\nexample.com\n";
+        let targets = parse_targets(content).unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                Target::Address(ip("10.0.0.5")),
+                Target::Host("example.com".to_string()),
+                Target::Network(ip("10.0.0.0"), 24),
+            ]
+        );
+        assert!(parse_targets("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lenient_parsing_keeps_valid_entries() {
+        let (targets, errors) = parse_targets_lenient("10.0.0.1\n10.0.0.999\nexample.com\n");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+    }
+
+    #[test]
+    fn test_verbatim_parsing_keeps_malformed_entries() {
+        let targets = parse_targets_verbatim("10.0.0.1\n10.0.1-3.1-254\n# 10.0.0.2\n10.0.0.1-5/24\n10.0.0.1\n");
+        assert_eq!(
+            targets,
+            vec![
+                Target::Address(ip("10.0.0.1")),
+                Target::Host("10.0.1-3.1-254".to_string()),
+                Target::Host("10.0.0.1-5/24".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_target_lines_are_kept_as_written() {
+        let lines = target_lines("  10.0.0.1  \n# 10.0.0.2\n::0:1\n10.0.1-3.1-254\n10.0.0.1\n0::1\n");
+        assert_eq!(lines, vec!["10.0.0.1", "::0:1", "10.0.1-3.1-254"]);
+    }
+
+    #[test]
+    fn test_expand_targets() {
+        let targets = parse_targets("10.0.0.1\n10.0.0.0/30\nexample.com\n10.0.0.0/16\n").unwrap();
+        assert_eq!(
+            expand_targets(&targets, 256),
+            vec!["10.0.0.1", "10.0.0.0", "10.0.0.2", "10.0.0.3", "example.com", "10.0.0.0/16"]
+        );
+    }
+}
@@ -11,7 +11,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 
-use crate::config::{load_targets, get_browser_settings, ProxyType};
+use crate::config::{load_targets, get_browser_settings, ProxyType, get_keyboard_shortcuts, shortcut_hint};
 
 // Conditional webkit imports
 #[cfg(feature = "webkit")]
@@ -78,7 +78,7 @@ fn create_fallback_browser_tab(
 
     let insert_target_btn = Button::builder()
         .icon_name("list-add-symbolic")
-        .tooltip_text("Insert Target (Ctrl+T)")
+        .tooltip_text(&shortcut_hint("Insert Target", "insert_target"))
         .build();
     insert_target_btn.add_css_class("flat");
 
@@ -219,9 +219,10 @@ fn setup_fallback_keyboard(
     key_controller.connect_key_pressed(move |_, keyval, _, state| {
         let ctrl = state.contains(gtk::gdk::ModifierType::CONTROL_MASK);
         let shift = state.contains(gtk::gdk::ModifierType::SHIFT_MASK);
+        let shortcuts = get_keyboard_shortcuts();
 
-        // Ctrl+T: Show target selector
-        if ctrl && !shift && keyval == gtk::gdk::Key::t {
+        // Insert target (Ctrl+T by default)
+        if shortcuts.matches("insert_target", keyval, state) {
             show_target_selector_for_url(&url_entry_clone);
             return gtk::glib::Propagation::Stop;
         }
@@ -236,14 +237,9 @@ fn setup_fallback_keyboard(
             return gtk::glib::Propagation::Stop;
         }
 
-        // Ctrl+W: Close current tab
-        if ctrl && !shift && keyval == gtk::gdk::Key::w {
-            let current = notebook_clone.current_page();
-            if let Some(page_num) = current {
-                if page_num >= crate::config::tabs::FIRST_SHELL {
-                    notebook_clone.remove_page(Some(page_num));
-                }
-            }
+        // Close current tab (Ctrl+Shift+W by default)
+        if shortcuts.matches("close_tab", keyval, state) {
+            crate::ui::terminal::close_current_tab(&notebook_clone);
             return gtk::glib::Propagation::Stop;
         }
 
@@ -328,7 +324,7 @@ fn create_webkit_browser_tab(
 
     let insert_target_btn = Button::builder()
         .icon_name("list-add-symbolic")
-        .tooltip_text("Insert Target (Ctrl+T)")
+        .tooltip_text(&shortcut_hint("Insert Target", "insert_target"))
         .build();
     insert_target_btn.add_css_class("flat");
 
@@ -675,9 +671,10 @@ fn setup_webkit_keyboard(
     key_controller.connect_key_pressed(move |_, keyval, _, state| {
         let ctrl = state.contains(gtk::gdk::ModifierType::CONTROL_MASK);
         let shift = state.contains(gtk::gdk::ModifierType::SHIFT_MASK);
+        let shortcuts = get_keyboard_shortcuts();
 
-        // Ctrl+T: Show target selector
-        if ctrl && !shift && keyval == gtk::gdk::Key::t {
+        // Insert target (Ctrl+T by default)
+        if shortcuts.matches("insert_target", keyval, state) {
             show_target_selector_for_url(&url_entry_clone);
             return gtk::glib::Propagation::Stop;
         }
@@ -692,14 +689,9 @@ fn setup_webkit_keyboard(
             return gtk::glib::Propagation::Stop;
         }
 
-        // Ctrl+W: Close current tab
-        if ctrl && !shift && keyval == gtk::gdk::Key::w {
-            let current = notebook_clone.current_page();
-            if let Some(page_num) = current {
-                if page_num >= crate::config::tabs::FIRST_SHELL {
-                    notebook_clone.remove_page(Some(page_num));
-                }
-            }
+        // Close current tab (Ctrl+Shift+W by default)
+        if shortcuts.matches("close_tab", keyval, state) {
+            crate::ui::terminal::close_current_tab(&notebook_clone);
             return gtk::glib::Propagation::Stop;
         }
 
@@ -741,6 +733,7 @@ fn setup_webview_keyboard(webview: &WebView, url_entry: &Entry, notebook: &Noteb
         let ctrl = state.contains(gtk::gdk::ModifierType::CONTROL_MASK);
         let shift = state.contains(gtk::gdk::ModifierType::SHIFT_MASK);
         let alt = state.contains(gtk::gdk::ModifierType::ALT_MASK);
+        let shortcuts = get_keyboard_shortcuts();
 
         // F5: Reload
         if keyval == gtk::gdk::Key::F5 {
@@ -783,14 +776,9 @@ fn setup_webview_keyboard(webview: &WebView, url_entry: &Entry, notebook: &Noteb
             return gtk::glib::Propagation::Stop;
         }
 
-        // Ctrl+W: Close current tab
-        if ctrl && !shift && keyval == gtk::gdk::Key::w {
-            let current = notebook_clone.current_page();
-            if let Some(page_num) = current {
-                if page_num >= crate::config::tabs::FIRST_SHELL {
-                    notebook_clone.remove_page(Some(page_num));
-                }
-            }
+        // Close current tab (Ctrl+Shift+W by default)
+        if shortcuts.matches("close_tab", keyval, state) {
+            crate::ui::terminal::close_current_tab(&notebook_clone);
             return gtk::glib::Propagation::Stop;
         }
 
@@ -858,6 +846,7 @@ fn normalize_url(input: &str) -> String {
 fn show_target_selector_for_url(url_entry: &Entry) {
     let targets = load_targets();
     if targets.is_empty() {
+        crate::ui::window::show_no_targets_toast();
         return;
     }
 
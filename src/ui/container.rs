@@ -17,7 +17,7 @@
 use gtk4::prelude::*;
 use gtk4::{
     Box as GtkBox, Button, Label, ListBox, ListBoxRow, Orientation,
-    ScrolledWindow, Separator, Entry, CheckButton, Frame, Paned, TextView,
+    ScrolledWindow, Separator, Entry, CheckButton, Paned, TextView,
     ResponseType,
 };
 use std::fs;
@@ -33,7 +33,9 @@ use crate::container::{
 };
 use crate::ui::dialogs::{show_settings_dialog_at_tab, settings_tabs};
 use crate::ui::desktop::create_desktop_tab;
-use crate::ui::terminal::create_editable_tab_label;
+use crate::ui::window::MonitorFrames;
+use crate::ui::terminal::{create_editable_tab_label, drawer_open_position, remember_drawer_width,
+                          remember_split_position, DRAWER_CLOSED_POSITION};
 
 /// Creates the container management tab
 pub fn create_container_tab(
@@ -41,9 +43,7 @@ pub fn create_container_tab(
     shell_counter: Rc<RefCell<usize>>,
     toast_overlay: Option<adw::ToastOverlay>,
     window: &adw::ApplicationWindow,
-    cpu_frame: &Frame,
-    ram_frame: &Frame,
-    net_frame: &Frame,
+    monitor_frames: &MonitorFrames,
 ) -> GtkBox {
     let outer_container = GtkBox::new(Orientation::Vertical, 0);
     outer_container.set_margin_top(12);
@@ -203,17 +203,9 @@ pub fn create_container_tab(
 
     // Settings button - opens main settings dialog at Containers tab
     let window_clone = window.clone();
-    let cpu_frame_clone = cpu_frame.clone();
-    let ram_frame_clone = ram_frame.clone();
-    let net_frame_clone = net_frame.clone();
+    let monitor_frames_clone = monitor_frames.clone();
     settings_btn.connect_clicked(move |_| {
-        show_settings_dialog_at_tab(
-            &window_clone,
-            &cpu_frame_clone,
-            &ram_frame_clone,
-            &net_frame_clone,
-            settings_tabs::CONTAINERS,
-        );
+        show_settings_dialog_at_tab(&window_clone, &monitor_frames_clone, settings_tabs::CONTAINERS);
     });
 
     // X11 diagnostic button - shows X11 configuration status
@@ -718,11 +710,8 @@ fn connect_to_container(
     match mgr.get_connection_command(name) {
         Ok((cmd, is_exec)) => {
             // Create a new shell tab with the connection command
-            let shell_id = {
-                let mut counter = shell_counter.borrow_mut();
-                *counter += 1;
-                *counter
-            };
+            // Container tabs are named after the container, so they don't use up a shell number
+            let shell_id = *shell_counter.borrow();
 
             let tab_icon = if split_view { "📝" } else if is_exec { "📦" } else { "🔗" };
             let tab_name = format!("{} {}", tab_icon, name);
@@ -790,7 +779,7 @@ fn create_ssh_shell_tab(
     use vte4::prelude::*;
     use vte4::Terminal;
     use gtk4::Paned;
-    use crate::config::{get_base_dir, is_flatpak, load_targets, get_keyboard_shortcuts};
+    use crate::config::{resolve_shell_start_dir, is_flatpak, load_targets, get_keyboard_shortcuts, shell_quote, shortcut_hint};
 
     let outer_container = GtkBox::new(Orientation::Vertical, 0);
     outer_container.set_margin_top(6);
@@ -830,13 +819,13 @@ fn create_ssh_shell_tab(
 
     let insert_target_btn = Button::builder()
         .icon_name("list-add-symbolic")
-        .tooltip_text("Insert Target (Ctrl+T)")
+        .tooltip_text(&shortcut_hint("Insert Target", "insert_target"))
         .build();
     insert_target_btn.add_css_class("flat");
 
     let drawer_toggle = gtk4::ToggleButton::builder()
         .icon_name("view-list-symbolic")
-        .tooltip_text("Commands (Ctrl+`)")
+        .tooltip_text(&shortcut_hint("Commands", "toggle_drawer"))
         .build();
     drawer_toggle.add_css_class("flat");
 
@@ -853,6 +842,7 @@ fn create_ssh_shell_tab(
     let terminal = Terminal::new();
     terminal.set_vexpand(true);
     terminal.set_hexpand(true);
+    crate::ui::terminal::apply_terminal_colors(&terminal);
 
     // Apply terminal zoom and scroll zoom
     let current_scale = crate::config::get_terminal_zoom_scale();
@@ -891,9 +881,10 @@ fn create_ssh_shell_tab(
 
     paned.set_start_child(Some(&terminal_container));
     paned.set_end_child(Some(&drawer));
-    paned.set_position(10000);
+    paned.set_position(DRAWER_CLOSED_POSITION);
     paned.set_shrink_start_child(false);
     paned.set_shrink_end_child(false);
+    remember_drawer_width(&paned, &drawer);
 
     // Drawer toggle handler
     let drawer_clone = drawer.clone();
@@ -902,10 +893,10 @@ fn create_ssh_shell_tab(
     drawer_toggle.connect_toggled(move |btn| {
         drawer_clone.set_visible(btn.is_active());
         if btn.is_active() {
-            paned_clone.set_position(600);
+            paned_clone.set_position(drawer_open_position(&paned_clone));
             search_entry_clone.grab_focus();
         } else {
-            paned_clone.set_position(10000);
+            paned_clone.set_position(DRAWER_CLOSED_POSITION);
         }
     });
 
@@ -914,7 +905,7 @@ fn create_ssh_shell_tab(
     let target_combo_clone = target_combo.clone();
     insert_target_btn.connect_clicked(move |_| {
         if let Some(target) = target_combo_clone.active_text() {
-            terminal_clone.feed_child(target.as_bytes());
+            terminal_clone.feed_child(shell_quote(&target).as_bytes());
             terminal_clone.grab_focus();
         }
     });
@@ -929,7 +920,7 @@ fn create_ssh_shell_tab(
     ];
     let env_refs: Vec<&str> = env_vars.iter().map(|s| s.as_str()).collect();
 
-    let working_dir = get_base_dir();
+    let working_dir = resolve_shell_start_dir();
     let working_dir_str = working_dir.to_str();
 
     // Spawn shell that runs SSH command
@@ -942,6 +933,7 @@ fn create_ssh_shell_tab(
         vec!["/bin/bash", "-c", &shell_cmd]
     };
 
+    let terminal_spawn = terminal.clone();
     let _ = terminal.spawn_async(
         vte4::PtyFlags::DEFAULT,
         working_dir_str,
@@ -951,9 +943,10 @@ fn create_ssh_shell_tab(
         || {},
         -1,
         None::<&gtk4::gio::Cancellable>,
-        |result| {
-            if let Err(e) = result {
-                log::error!("Failed to spawn SSH shell: {:?}", e);
+        move |result| {
+            match result {
+                Ok(pid) => crate::ui::terminal::track_shell_pid(&terminal_spawn, pid.0),
+                Err(e) => crate::ui::window::show_error_toast(&format!("Failed to spawn SSH shell: {}", e)),
             }
         },
     );
@@ -966,25 +959,24 @@ fn create_ssh_shell_tab(
     let search_entry_keys = search_entry.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk4::gdk::ModifierType::CONTROL_MASK) {
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
-
-            // Toggle drawer
-            if key_name == shortcuts.toggle_drawer {
-                drawer_toggle_clone.set_active(!drawer_toggle_clone.is_active());
-                if drawer_toggle_clone.is_active() {
-                    search_entry_keys.grab_focus();
-                }
-                return gtk4::glib::Propagation::Stop;
-            }
+        let shortcuts = get_keyboard_shortcuts();
 
-            // Insert target
-            if key_name == shortcuts.insert_target {
-                show_target_selector_popup_for_terminal(&terminal_keys);
-                return gtk4::glib::Propagation::Stop;
+        // Toggle drawer
+        if shortcuts.matches("toggle_drawer", keyval, modifier) {
+            drawer_toggle_clone.set_active(!drawer_toggle_clone.is_active());
+            if drawer_toggle_clone.is_active() {
+                search_entry_keys.grab_focus();
             }
+            return gtk4::glib::Propagation::Stop;
+        }
 
+        // Insert target
+        if shortcuts.matches("insert_target", keyval, modifier) {
+            show_target_selector_popup_for_terminal(&terminal_keys);
+            return gtk4::glib::Propagation::Stop;
+        }
+
+        if modifier.contains(gtk4::gdk::ModifierType::CONTROL_MASK) {
             // Tab switching Ctrl+1-9
             let page_num = match keyval {
                 gtk4::gdk::Key::_1 => Some(0),
@@ -1094,9 +1086,10 @@ fn create_container_split_view_tab(
     ssh_cmd: &str,
     container_name: &str,
 ) -> Paned {
-    use crate::config::{get_file_path, get_keyboard_shortcuts};
+    use crate::config::{get_file_path, get_keyboard_shortcuts, get_autosave_debounce_ms, get_split_position};
     use crate::ui::editor::apply_markdown_highlighting;
     use crate::ui::editor::track_notes_view;
+    use crate::ui::editor::{create_save_state_label, set_save_state, save_editor_text, save_editor_text_async};
 
     let paned = Paned::new(Orientation::Horizontal);
     paned.set_margin_top(6);
@@ -1129,34 +1122,36 @@ fn create_container_split_view_tab(
     apply_markdown_highlighting(&notes_view);
 
     // Track notes view for wrap mode updates
-    track_notes_view(&notes_view);
+    track_notes_view(&notes_view, &notes_path);
 
     // Add text view to zoom tracking
     crate::ui::editor::add_textview_scroll_zoom(&notes_view);
 
     // Auto-save notes
+    let save_state_label = create_save_state_label();
     let notes_path_clone = notes_path.clone();
-    let notes_view_clone = notes_view.clone();
+    let save_state_clone = save_state_label.clone();
     let save_timeout_id: Rc<RefCell<Option<gtk4::glib::SourceId>>> = Rc::new(RefCell::new(None));
     let save_timeout_clone = Rc::clone(&save_timeout_id);
 
     notes_view.buffer().connect_changed(move |buffer| {
         let file_path = notes_path_clone.clone();
-        let notes_view_ref = notes_view_clone.clone();
 
         if let Some(id) = save_timeout_clone.borrow_mut().take() {
             id.remove();
         }
 
-        apply_markdown_highlighting(&notes_view_ref);
+        set_save_state(&save_state_clone, false);
 
         let save_timeout_inner = Rc::clone(&save_timeout_clone);
         let buffer_clone = buffer.clone();
-        let source_id = gtk4::glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+        let save_state_ref = save_state_clone.clone();
+        let delay = std::time::Duration::from_millis(get_autosave_debounce_ms() as u64);
+        let source_id = gtk4::glib::timeout_add_local(delay, move || {
             let start = buffer_clone.start_iter();
             let end = buffer_clone.end_iter();
             let text = buffer_clone.text(&start, &end, false);
-            let _ = fs::write(&file_path, text.as_str());
+            save_editor_text_async(&file_path, &text, &save_state_ref);
             *save_timeout_inner.borrow_mut() = None;
             gtk4::glib::ControlFlow::Break
         });
@@ -1169,37 +1164,35 @@ fn create_container_split_view_tab(
     let key_controller = gtk4::EventControllerKey::new();
     let notes_path_clone3 = notes_path.clone();
     let notes_view_clone3 = notes_view.clone();
+    let save_state_clone3 = save_state_label.clone();
     let notes_view_clone4 = notes_view.clone();
     let notes_view_clone5 = notes_view.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk4::gdk::ModifierType::CONTROL_MASK) {
-            // Ctrl+S to save
-            if keyval == gtk4::gdk::Key::s {
-                let buffer = notes_view_clone3.buffer();
-                let start = buffer.start_iter();
-                let end = buffer.end_iter();
-                let text = buffer.text(&start, &end, false);
-                let _ = fs::write(&notes_path_clone3, text.as_str());
-                return gtk4::glib::Propagation::Stop;
-            }
+        // Ctrl+S to save
+        if modifier.contains(gtk4::gdk::ModifierType::CONTROL_MASK) && keyval == gtk4::gdk::Key::s {
+            let buffer = notes_view_clone3.buffer();
+            let start = buffer.start_iter();
+            let end = buffer.end_iter();
+            let text = buffer.text(&start, &end, false);
+            save_editor_text(&notes_path_clone3, &text, &save_state_clone3);
+            return gtk4::glib::Propagation::Stop;
+        }
 
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
+        let shortcuts = get_keyboard_shortcuts();
 
-            // Ctrl+T (or custom key) for target insertion
-            if key_name == shortcuts.insert_target {
-                crate::ui::editor::show_target_selector_for_textview(&notes_view_clone4);
-                return gtk4::glib::Propagation::Stop;
-            }
+        // Ctrl+T (or custom key) for target insertion
+        if shortcuts.matches("insert_target", keyval, modifier) {
+            crate::ui::editor::show_target_selector_for_textview(&notes_view_clone4);
+            return gtk4::glib::Propagation::Stop;
+        }
 
-            // Ctrl+Shift+T (or custom key) for timestamp insertion
-            if modifier.contains(gtk4::gdk::ModifierType::SHIFT_MASK) && key_name == shortcuts.insert_timestamp {
-                let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
-                let buffer = notes_view_clone5.buffer();
-                buffer.insert_at_cursor(&timestamp);
-                return gtk4::glib::Propagation::Stop;
-            }
+        // Ctrl+Shift+T (or custom key) for timestamp insertion
+        if shortcuts.matches("insert_timestamp", keyval, modifier) {
+            let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
+            let buffer = notes_view_clone5.buffer();
+            buffer.insert_at_cursor(&timestamp);
+            return gtk4::glib::Propagation::Stop;
         }
         gtk4::glib::Propagation::Proceed
     });
@@ -1217,12 +1210,13 @@ fn create_container_split_view_tab(
 
     let notes_path_clone2 = notes_path.clone();
     let notes_view_clone2 = notes_view.clone();
+    let save_state_clone2 = save_state_label.clone();
     save_btn.connect_clicked(move |_| {
         let buffer = notes_view_clone2.buffer();
         let start = buffer.start_iter();
         let end = buffer.end_iter();
         let text = buffer.text(&start, &end, false);
-        let _ = fs::write(&notes_path_clone2, text.as_str());
+        save_editor_text(&notes_path_clone2, &text, &save_state_clone2);
     });
 
     let file_label = Label::new(Some("notes.md"));
@@ -1235,6 +1229,7 @@ fn create_container_split_view_tab(
 
     notes_bar.append(&save_btn);
     notes_bar.append(&file_label);
+    notes_bar.append(&save_state_label);
     notes_bar.append(&container_label);
 
     notes_container.append(&notes_scrolled);
@@ -1245,7 +1240,8 @@ fn create_container_split_view_tab(
 
     paned.set_start_child(Some(&notes_container));
     paned.set_end_child(Some(&shell_container));
-    paned.set_position(400);
+    paned.set_position(get_split_position());
+    remember_split_position(&paned);
     paned.set_shrink_start_child(false);
     paned.set_shrink_end_child(false);
     paned.set_resize_start_child(true);
@@ -1287,14 +1283,75 @@ fn create_command_drawer_for_container(
     drawer.append(&scrolled);
 
     // Load commands (load_command_templates already includes custom commands)
-    let all_commands = load_command_templates();
+    let all_commands = Rc::new(RefCell::new(load_command_templates()));
+    populate_container_command_rows(&list_box, &all_commands.borrow());
 
     // Populate command list
     let terminal_clone = terminal.clone();
     let drawer_toggle_clone = drawer_toggle.clone();
     let paned_clone = paned.clone();
 
-    for cmd in &all_commands {
+    // Row activation - insert command
+    let terminal_insert = terminal_clone.clone();
+    let drawer_toggle_insert = drawer_toggle_clone.clone();
+    let paned_insert = paned_clone.clone();
+    list_box.connect_row_activated(move |_, row| {
+        let command = crate::commands::fill_placeholders(
+            &row.widget_name(),
+            &crate::commands::resolve_command_variables(),
+        );
+        let placeholders = crate::commands::extract_placeholders(&command);
+
+        // Commands with placeholders beyond {target} get the full form
+        if placeholders.iter().any(|p| p != "target") {
+            crate::ui::terminal::show_placeholder_form(&terminal_insert, command.clone(), placeholders, false);
+        } else if command.contains("{target}") {
+            let targets = load_targets();
+            if !targets.is_empty() {
+                // Show target selector for this command
+                show_target_selector_for_command_container(&terminal_insert, &command);
+            } else {
+                feed_template_command(&terminal_insert, &command);
+            }
+        } else {
+            feed_template_command(&terminal_insert, &command);
+        }
+
+        // Close drawer and focus terminal
+        drawer_toggle_insert.set_active(false);
+        paned_insert.set_position(DRAWER_CLOSED_POSITION);
+        terminal_insert.grab_focus();
+    });
+
+    // Search filtering
+    let list_box_filter = list_box.clone();
+    let commands_for_filter = Rc::clone(&all_commands);
+    search_entry.connect_search_changed(move |entry| {
+        filter_container_command_rows(&list_box_filter, &commands_for_filter.borrow(), &entry.text().to_lowercase());
+    });
+
+    // Pick up edits to custom_commands.yaml while the drawer is open
+    let list_box_weak = list_box.downgrade();
+    let search_entry_weak = search_entry.downgrade();
+    crate::ui::terminal::register_command_drawer(&list_box, move || {
+        let (Some(list_box), Some(search_entry)) = (list_box_weak.upgrade(), search_entry_weak.upgrade()) else {
+            return;
+        };
+        *all_commands.borrow_mut() = load_command_templates();
+        populate_container_command_rows(&list_box, &all_commands.borrow());
+        filter_container_command_rows(&list_box, &all_commands.borrow(), &search_entry.text().to_lowercase());
+    });
+
+    (drawer, search_entry)
+}
+
+/// Fills the container drawer with one row per command, replacing any existing rows
+fn populate_container_command_rows(list_box: &ListBox, commands: &[crate::commands::CommandTemplate]) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    for cmd in commands {
         let row = ListBoxRow::new();
         let row_box = GtkBox::new(Orientation::Vertical, 2);
         row_box.set_margin_top(6);
@@ -1321,66 +1378,36 @@ fn create_command_drawer_for_container(
 
         list_box.append(&row);
     }
+}
 
-    // Row activation - insert command
-    let terminal_insert = terminal_clone.clone();
-    let drawer_toggle_insert = drawer_toggle_clone.clone();
-    let paned_insert = paned_clone.clone();
-    list_box.connect_row_activated(move |_, row| {
-        let command = row.widget_name().to_string();
-
-        // Check if command has {target} placeholder
-        if command.contains("{target}") {
-            let targets = load_targets();
-            if !targets.is_empty() {
-                // Show target selector for this command
-                show_target_selector_for_command_container(&terminal_insert, &command);
-            } else {
-                terminal_insert.feed_child(command.as_bytes());
-            }
-        } else {
-            terminal_insert.feed_child(command.as_bytes());
-        }
-
-        // Close drawer and focus terminal
-        drawer_toggle_insert.set_active(false);
-        paned_insert.set_position(10000);
-        terminal_insert.grab_focus();
-    });
-
-    // Search filtering
-    let list_box_filter = list_box.clone();
-    let commands_for_filter = all_commands.clone();
-    search_entry.connect_search_changed(move |entry| {
-        let query = entry.text().to_lowercase();
-
-        let mut child = list_box_filter.first_child();
-        let mut idx = 0;
-        while let Some(widget) = child {
-            if let Some(row) = widget.downcast_ref::<ListBoxRow>() {
-                if idx < commands_for_filter.len() {
-                    let cmd = &commands_for_filter[idx];
-                    let matches = query.is_empty() ||
-                        cmd.name.to_lowercase().contains(&query) ||
-                        cmd.command.to_lowercase().contains(&query) ||
-                        cmd.category.to_lowercase().contains(&query);
-                    row.set_visible(matches);
-                }
-                idx += 1;
+/// Shows container drawer rows whose name, command or category contains the query
+fn filter_container_command_rows(list_box: &ListBox, commands: &[crate::commands::CommandTemplate], query: &str) {
+    let mut child = list_box.first_child();
+    let mut idx = 0;
+    while let Some(widget) = child {
+        if let Some(row) = widget.downcast_ref::<ListBoxRow>() {
+            if idx < commands.len() {
+                let cmd = &commands[idx];
+                let matches = query.is_empty() ||
+                    cmd.name.to_lowercase().contains(query) ||
+                    cmd.command.to_lowercase().contains(query) ||
+                    cmd.category.to_lowercase().contains(query);
+                row.set_visible(matches);
             }
-            child = widget.next_sibling();
+            idx += 1;
         }
-    });
-
-    (drawer, search_entry)
+        child = widget.next_sibling();
+    }
 }
 
 /// Show target selector popup for container terminal (matches regular shell tab style)
 fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
-    use crate::config::load_targets;
+    use crate::config::{load_targets, shell_quote};
+    use crate::ui::editor::copy_target_to_clipboard;
 
     let targets = load_targets();
     if targets.is_empty() {
+        crate::ui::window::show_no_targets_toast();
         return;
     }
 
@@ -1433,13 +1460,25 @@ fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
         if let Some(row) = list_box_clone.selected_row() {
             let index = row.index() as usize;
             if index < targets_clone.len() {
-                terminal_clone.feed_child(targets_clone[index].as_bytes());
+                terminal_clone.feed_child(shell_quote(&targets_clone[index]).as_bytes());
                 terminal_clone.grab_focus();
             }
         }
         popup_clone.close();
     });
 
+    let copy_btn = Button::with_label("Copy");
+    copy_btn.set_tooltip_text(Some("Copy the selected target to the clipboard (Ctrl+C)"));
+    let popup_clone5 = popup.clone();
+    let list_box_clone3 = list_box.clone();
+    let targets_clone4 = targets.clone();
+    copy_btn.connect_clicked(move |_| {
+        if let Some(target) = list_box_clone3.selected_row().and_then(|row| targets_clone4.get(row.index() as usize)) {
+            copy_target_to_clipboard(target);
+        }
+        popup_clone5.close();
+    });
+
     let popup_clone2 = popup.clone();
     cancel_btn.connect_clicked(move |_| {
         popup_clone2.close();
@@ -1452,7 +1491,7 @@ fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
     list_box.connect_row_activated(move |_list_box, row| {
         let index = row.index() as usize;
         if index < targets_clone2.len() {
-            terminal_clone2.feed_child(targets_clone2[index].as_bytes());
+            terminal_clone2.feed_child(shell_quote(&targets_clone2[index]).as_bytes());
             terminal_clone2.grab_focus();
         }
         popup_clone3.close();
@@ -1464,15 +1503,23 @@ fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
     let terminal_clone3 = terminal.clone();
     let list_box_clone2 = list_box.clone();
     let targets_clone3 = targets.clone();
-    key_controller.connect_key_pressed(move |_, keyval, _, _| {
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
         if keyval == gtk4::gdk::Key::Escape {
             popup_clone4.close();
             return gtk4::glib::Propagation::Stop;
+        } else if modifier.contains(gtk4::gdk::ModifierType::CONTROL_MASK)
+            && (keyval == gtk4::gdk::Key::c || keyval == gtk4::gdk::Key::C)
+        {
+            if let Some(target) = list_box_clone2.selected_row().and_then(|row| targets_clone3.get(row.index() as usize)) {
+                copy_target_to_clipboard(target);
+            }
+            popup_clone4.close();
+            return gtk4::glib::Propagation::Stop;
         } else if keyval == gtk4::gdk::Key::Return || keyval == gtk4::gdk::Key::KP_Enter {
             if let Some(row) = list_box_clone2.selected_row() {
                 let index = row.index() as usize;
                 if index < targets_clone3.len() {
-                    terminal_clone3.feed_child(targets_clone3[index].as_bytes());
+                    terminal_clone3.feed_child(shell_quote(&targets_clone3[index]).as_bytes());
                     terminal_clone3.grab_focus();
                 }
             }
@@ -1484,6 +1531,7 @@ fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
     popup.add_controller(key_controller);
 
     button_box.append(&cancel_btn);
+    button_box.append(&copy_btn);
     button_box.append(&insert_btn);
 
     popup_box.append(&scrolled);
@@ -1494,13 +1542,22 @@ fn show_target_selector_popup_for_terminal(terminal: &vte4::Terminal) {
     popup.present();
 }
 
+/// Inserts a drawer command, asking first if it matches a dangerous pattern
+fn feed_template_command(terminal: &vte4::Terminal, command: &str) {
+    if crate::commands::is_dangerous_command(command) {
+        crate::ui::terminal::confirm_dangerous_command(terminal, command, false);
+    } else {
+        terminal.feed_child(command.as_bytes());
+    }
+}
+
 /// Show target selector for command with {target} placeholder in container terminal
 fn show_target_selector_for_command_container(terminal: &vte4::Terminal, command: &str) {
-    use crate::config::load_targets;
+    use crate::config::{load_targets, shell_quote};
 
     let targets = load_targets();
     if targets.is_empty() {
-        terminal.feed_child(command.as_bytes());
+        feed_template_command(terminal, command);
         return;
     }
 
@@ -1554,8 +1611,8 @@ fn show_target_selector_for_command_container(terminal: &vte4::Terminal, command
         if let Some(row) = list_box_clone.selected_row() {
             let index = row.index() as usize;
             if index < targets_clone.len() {
-                let final_cmd = command_clone.replace("{target}", &targets_clone[index]);
-                terminal_clone.feed_child(final_cmd.as_bytes());
+                let final_cmd = command_clone.replace("{target}", &shell_quote(&targets_clone[index]));
+                feed_template_command(&terminal_clone, &final_cmd);
                 terminal_clone.grab_focus();
             }
         }
@@ -1575,8 +1632,8 @@ fn show_target_selector_for_command_container(terminal: &vte4::Terminal, command
     list_box.connect_row_activated(move |_list_box, row| {
         let index = row.index() as usize;
         if index < targets_clone2.len() {
-            let final_cmd = command_clone2.replace("{target}", &targets_clone2[index]);
-            terminal_clone2.feed_child(final_cmd.as_bytes());
+            let final_cmd = command_clone2.replace("{target}", &shell_quote(&targets_clone2[index]));
+            feed_template_command(&terminal_clone2, &final_cmd);
             terminal_clone2.grab_focus();
         }
         popup_clone3.close();
@@ -1597,8 +1654,8 @@ fn show_target_selector_for_command_container(terminal: &vte4::Terminal, command
             if let Some(row) = list_box_clone2.selected_row() {
                 let index = row.index() as usize;
                 if index < targets_clone3.len() {
-                    let final_cmd = command_clone3.replace("{target}", &targets_clone3[index]);
-                    terminal_clone3.feed_child(final_cmd.as_bytes());
+                    let final_cmd = command_clone3.replace("{target}", &shell_quote(&targets_clone3[index]));
+                    feed_template_command(&terminal_clone3, &final_cmd);
                     terminal_clone3.grab_focus();
                 }
             }
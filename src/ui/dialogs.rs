@@ -7,16 +7,34 @@ use gtk4::{self as gtk, Application, Box as GtkBox, Button, Label, Orientation,
           ScrolledWindow, ListBox, Frame, CheckButton, Notebook, ComboBoxText, PasswordEntry};
 use libadwaita::{self as adw, prelude::*};
 use std::path::PathBuf;
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::config::{
-    get_app_settings, save_app_settings, get_keyboard_shortcuts, key_to_display,
+    get_app_settings, save_app_settings, get_keyboard_shortcuts,
     get_text_zoom_scale, get_terminal_zoom_scale, is_command_logging_enabled, zoom,
     is_notes_wrap_text_enabled, get_browser_settings, BrowserSettings, ProxyType,
     is_browser_enabled, is_containers_enabled, get_desktop_settings, DesktopSettings,
+    get_color_scheme, ColorScheme, get_terminal_theme, is_log_dedup_enabled, get_command_variables,
+    get_redaction_patterns, default_redaction_patterns, get_notes_backup_interval,
+    get_dangerous_patterns, default_dangerous_patterns, is_restore_session_enabled,
+    is_scrollback_persistence_enabled, is_command_preview_enabled,
+    get_host_section_template, default_host_section_template, is_targets_autosave_enabled,
+    is_spell_check_enabled, get_spell_check_language, get_log_rotate_size_mb, get_log_archive_keep, get_terminal_timestamp_prefix,
+    load_recent_dirs, get_vpn_interface, get_monitor_interval_ms, MIN_MONITOR_INTERVAL_MS,
+    get_autosave_debounce_ms, MIN_AUTOSAVE_DEBOUNCE_MS, is_project_settings_active, PROJECT_SETTINGS_FILE,
+    get_monitored_interfaces, get_shell_start_dir, ShellStartDir, is_target_autocomplete_enabled,
+    shortcut_title, shortcut_from_event, shortcut_to_display, is_valid_shortcut, SHORTCUT_TITLES,
+    reserved_shortcut_title,
+    known_placeholders,
 };
 use crate::commands::{load_custom_commands, save_custom_command, delete_custom_command,
-                      update_custom_command, CommandTemplate};
+                      update_custom_command, move_custom_command, CommandTemplate, CommandOs, import_custom_commands, export_custom_commands, detect_default_interface,
+                      DEFAULT_COMMAND_VARIABLES, unknown_placeholders, load_command_templates,
+                      command_categories, category_key};
+use crate::findings::{add_finding, update_finding, Finding, FindingStatus, Severity};
+use crate::ssh::{load_ssh_profiles, add_ssh_profile, update_ssh_profile, delete_ssh_profile, SshProfile};
+use crate::ui::window::{report_error, show_error_toast, MonitorFrames};
 use crate::container::{
     ContainerConfig, ContainerRuntime, load_container_config, save_container_config,
 };
@@ -128,6 +146,44 @@ where
     button_box.append(&browse_btn);
 
     dialog_box.append(&header_box);
+
+    // Recent projects, most recent first
+    let recent_dirs = load_recent_dirs();
+    if !recent_dirs.is_empty() {
+        let recent_label = Label::new(Some("Recent Projects"));
+        recent_label.add_css_class("heading");
+        recent_label.set_halign(gtk::Align::Start);
+        dialog_box.append(&recent_label);
+
+        let recent_list = ListBox::new();
+        recent_list.set_selection_mode(gtk::SelectionMode::None);
+        recent_list.add_css_class("boxed-list");
+
+        for dir in &recent_dirs {
+            let row = adw::ActionRow::new();
+            let name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| dir.to_string_lossy().to_string());
+            row.set_title(&name);
+            row.set_subtitle(&dir.to_string_lossy());
+            row.set_activatable(true);
+            row.set_widget_name(&dir.to_string_lossy());
+            row.add_prefix(&gtk::Image::from_icon_name("folder-symbolic"));
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            recent_list.append(&row);
+        }
+
+        let dialog_clone4 = dialog.clone();
+        let callback_clone4 = Rc::clone(&callback_rc);
+        recent_list.connect_row_activated(move |_, row| {
+            callback_clone4(Some(PathBuf::from(row.widget_name().as_str())));
+            dialog_clone4.close();
+        });
+
+        dialog_box.append(&recent_list);
+    }
+
     dialog_box.append(&button_box);
 
     content.set_child(Some(&dialog_box));
@@ -261,29 +317,23 @@ pub mod settings_tabs {
     pub const GENERAL: u32 = 0;
     pub const SHORTCUTS: u32 = 1;
     pub const COMMANDS: u32 = 2;
-    pub const BROWSER: u32 = 3;
-    pub const CONTAINERS: u32 = 4;
-    pub const DESKTOP: u32 = 5;
-    pub const ABOUT: u32 = 6;
+    pub const SSH: u32 = 3;
+    pub const BROWSER: u32 = 4;
+    pub const CONTAINERS: u32 = 5;
+    pub const DESKTOP: u32 = 6;
+    pub const ABOUT: u32 = 7;
 }
 
-pub fn show_settings_dialog(
-    parent: &adw::ApplicationWindow,
-    cpu_frame: &Frame,
-    ram_frame: &Frame,
-    net_frame: &Frame
-) {
-    show_settings_dialog_at_tab(parent, cpu_frame, ram_frame, net_frame, settings_tabs::GENERAL);
+pub fn show_settings_dialog(parent: &adw::ApplicationWindow, frames: &MonitorFrames) {
+    show_settings_dialog_at_tab(parent, frames, settings_tabs::GENERAL);
 }
 
-/// Shows the settings dialog with a specific tab selected
+/// Shows the settings dialog with a specific tab selected, returning its toast overlay
 pub fn show_settings_dialog_at_tab(
     parent: &adw::ApplicationWindow,
-    cpu_frame: &Frame,
-    ram_frame: &Frame,
-    net_frame: &Frame,
+    frames: &MonitorFrames,
     tab_index: u32,
-) {
+) -> adw::ToastOverlay {
     let dialog = adw::Window::builder()
         .transient_for(parent)
         .modal(true)
@@ -296,6 +346,10 @@ pub fn show_settings_dialog_at_tab(
 
     // Header bar
     let header_bar = adw::HeaderBar::new();
+    if is_project_settings_active() {
+        let subtitle = format!("Project settings are saved to {}", PROJECT_SETTINGS_FILE);
+        header_bar.set_title_widget(Some(&adw::WindowTitle::new("Settings", &subtitle)));
+    }
     main_box.append(&header_bar);
 
     // Create notebook for tabs (compatible with libadwaita 0.7)
@@ -306,7 +360,7 @@ pub fn show_settings_dialog_at_tab(
     notebook.set_margin_end(6);
 
     // ===== GENERAL TAB =====
-    let general_page = create_general_settings_page(cpu_frame, ram_frame, net_frame);
+    let general_page = create_general_settings_page(frames);
     let general_label = Label::new(Some("General"));
     notebook.append_page(&general_page, Some(&general_label));
 
@@ -316,10 +370,15 @@ pub fn show_settings_dialog_at_tab(
     notebook.append_page(&shortcuts_page, Some(&shortcuts_label));
 
     // ===== COMMANDS TAB =====
-    let commands_page = create_commands_page(parent, &dialog, cpu_frame, ram_frame, net_frame);
+    let commands_page = create_commands_page(parent, &dialog, frames);
     let commands_label = Label::new(Some("Commands"));
     notebook.append_page(&commands_page, Some(&commands_label));
 
+    // ===== SSH TAB =====
+    let ssh_page = create_ssh_profiles_page();
+    let ssh_label = Label::new(Some("SSH"));
+    notebook.append_page(&ssh_page, Some(&ssh_label));
+
     // ===== BROWSER TAB =====
     let browser_page = create_browser_settings_page();
     let browser_label = Label::new(Some("Browser"));
@@ -344,12 +403,149 @@ pub fn show_settings_dialog_at_tab(
     notebook.set_current_page(Some(tab_index));
 
     main_box.append(&notebook);
-    dialog.set_content(Some(&main_box));
+    let toast_overlay = adw::ToastOverlay::new();
+    toast_overlay.set_child(Some(&main_box));
+    dialog.set_content(Some(&toast_overlay));
     dialog.present();
+
+    toast_overlay
+}
+
+/// Creates a one-pattern-per-line editor with a reset button.
+///
+/// `on_change` receives the trimmed, non-empty lines after every edit.
+fn create_pattern_list_editor<F>(patterns: &[String], defaults: Vec<String>, on_change: F) -> GtkBox
+where
+    F: Fn(Vec<String>) + 'static,
+{
+    let editor_box = GtkBox::new(Orientation::Vertical, 6);
+
+    let text_view = gtk::TextView::builder()
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::None)
+        .top_margin(4)
+        .bottom_margin(4)
+        .left_margin(4)
+        .right_margin(4)
+        .build();
+    text_view.buffer().set_text(&patterns.join("\n"));
+    text_view.buffer().connect_changed(move |buffer| {
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        on_change(
+            text.lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect(),
+        );
+    });
+
+    let scroll = ScrolledWindow::builder()
+        .child(&text_view)
+        .min_content_height(90)
+        .hscrollbar_policy(gtk::PolicyType::Automatic)
+        .build();
+    let frame = Frame::new(None);
+    frame.set_child(Some(&scroll));
+    editor_box.append(&frame);
+
+    let reset_btn = Button::with_label("Reset to Defaults");
+    reset_btn.set_halign(gtk::Align::Start);
+    let text_view_clone = text_view.clone();
+    reset_btn.connect_clicked(move |_| {
+        text_view_clone.buffer().set_text(&defaults.join("\n"));
+    });
+    editor_box.append(&reset_btn);
+
+    editor_box
+}
+
+/// How long typing has to pause before a text setting is written to disk
+const SETTING_SAVE_DELAY_MS: u64 = 500;
+
+/// Runs `save` once typing has paused, replacing a save still waiting in `pending`
+fn save_setting_when_idle(pending: &Rc<RefCell<Option<gtk::glib::SourceId>>>, save: impl FnOnce() + 'static) {
+    if let Some(id) = pending.borrow_mut().take() {
+        id.remove();
+    }
+    let pending_clone = Rc::clone(pending);
+    let id = gtk::glib::timeout_add_local_once(std::time::Duration::from_millis(SETTING_SAVE_DELAY_MS), move || {
+        pending_clone.borrow_mut().take();
+        save();
+    });
+    *pending.borrow_mut() = Some(id);
+}
+
+/// File filter for YAML command packs
+fn yaml_file_filter() -> gtk::FileFilter {
+    let filter = gtk::FileFilter::new();
+    filter.set_name(Some("YAML files"));
+    filter.add_pattern("*.yaml");
+    filter.add_pattern("*.yml");
+    filter
+}
+
+/// Text shown on the interface selector for the chosen interfaces
+fn interface_selection_label(selected: &[String]) -> String {
+    if selected.is_empty() {
+        "All interfaces".to_string()
+    } else {
+        selected.join(", ")
+    }
+}
+
+/// Creates a dropdown of checkable network interfaces for the network monitor
+fn create_interface_selector() -> gtk::MenuButton {
+    let selected = get_monitored_interfaces();
+    let button = gtk::MenuButton::new();
+    button.set_label(&interface_selection_label(&selected));
+
+    // Current interfaces plus any saved ones that are down right now
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut names: Vec<String> = networks.keys().cloned().collect();
+    for name in &selected {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names.sort();
+
+    let list = GtkBox::new(Orientation::Vertical, 4);
+    list.set_margin_top(6);
+    list.set_margin_bottom(6);
+    list.set_margin_start(6);
+    list.set_margin_end(6);
+
+    let hint = Label::new(Some("None checked = all interfaces"));
+    hint.add_css_class("dim-label");
+    hint.add_css_class("caption");
+    hint.set_halign(gtk::Align::Start);
+    list.append(&hint);
+
+    for name in names {
+        let check = CheckButton::with_label(&name);
+        check.set_active(selected.contains(&name));
+        let button_clone = button.clone();
+        check.connect_toggled(move |check| {
+            let mut settings = get_app_settings();
+            settings.monitored_interfaces.retain(|n| *n != name);
+            if check.is_active() {
+                settings.monitored_interfaces.push(name.clone());
+                settings.monitored_interfaces.sort();
+            }
+            button_clone.set_label(&interface_selection_label(&settings.monitored_interfaces));
+            report_error(save_app_settings(&settings));
+        });
+        list.append(&check);
+    }
+
+    let popover = gtk::Popover::new();
+    popover.set_child(Some(&list));
+    button.set_popover(Some(&popover));
+    button
 }
 
 /// Creates the general settings page
-fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame: &Frame) -> ScrolledWindow {
+fn create_general_settings_page(frames: &MonitorFrames) -> ScrolledWindow {
     let scrolled = ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Never)
         .vscrollbar_policy(gtk::PolicyType::Automatic)
@@ -365,6 +561,83 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     page.set_margin_start(12);
     page.set_margin_end(12);
 
+    // Appearance Group
+    let appearance_heading = Label::new(Some("Appearance"));
+    appearance_heading.add_css_class("title-4");
+    appearance_heading.set_halign(gtk::Align::Start);
+    appearance_heading.set_margin_bottom(12);
+    page.append(&appearance_heading);
+
+    let appearance_box = GtkBox::new(Orientation::Horizontal, 12);
+    appearance_box.set_margin_start(12);
+    appearance_box.set_margin_bottom(24);
+
+    let scheme_label = Label::new(Some("Color Scheme:"));
+    scheme_label.set_xalign(0.0);
+    scheme_label.set_hexpand(true);
+    appearance_box.append(&scheme_label);
+
+    let scheme_combo = ComboBoxText::new();
+    scheme_combo.append_text("System");
+    scheme_combo.append_text("Light");
+    scheme_combo.append_text("Dark");
+    scheme_combo.set_active(Some(match get_color_scheme() {
+        ColorScheme::System => 0,
+        ColorScheme::Light => 1,
+        ColorScheme::Dark => 2,
+    }));
+    scheme_combo.connect_changed(move |combo| {
+        let scheme = match combo.active() {
+            Some(1) => ColorScheme::Light,
+            Some(2) => ColorScheme::Dark,
+            _ => ColorScheme::System,
+        };
+        crate::ui::window::apply_color_scheme(scheme);
+        let mut settings = get_app_settings();
+        settings.color_scheme = scheme;
+        report_error(save_app_settings(&settings));
+    });
+    appearance_box.append(&scheme_combo);
+
+    page.append(&appearance_box);
+
+    // Session Group
+    let session_heading = Label::new(Some("Session"));
+    session_heading.add_css_class("title-4");
+    session_heading.set_halign(gtk::Align::Start);
+    session_heading.set_margin_bottom(12);
+    page.append(&session_heading);
+
+    let session_box = GtkBox::new(Orientation::Vertical, 8);
+    session_box.set_margin_start(12);
+    session_box.set_margin_bottom(24);
+
+    let restore_check = CheckButton::with_label("Restore previous session (shell, split view and note tabs)");
+    restore_check.set_active(is_restore_session_enabled());
+    session_box.append(&restore_check);
+
+    let scrollback_check = CheckButton::with_label("Save shell scrollback with the session (secrets masked by redaction patterns)");
+    scrollback_check.set_active(is_scrollback_persistence_enabled());
+    scrollback_check.set_sensitive(is_restore_session_enabled());
+    scrollback_check.set_tooltip_text(Some("Written to the scrollback folder in the base directory; can be large"));
+    scrollback_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.persist_scrollback = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    session_box.append(&scrollback_check);
+
+    // Scrollback is only replayed when the session is restored
+    let scrollback_check_clone = scrollback_check.clone();
+    restore_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.restore_session = check.is_active();
+        report_error(save_app_settings(&settings));
+        scrollback_check_clone.set_sensitive(check.is_active());
+    });
+
+    page.append(&session_box);
+
     // Monitor Settings Group
     let monitor_heading = Label::new(Some("System Monitors"));
     monitor_heading.add_css_class("title-4");
@@ -378,40 +651,103 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
 
     // CPU toggle
     let cpu_check = CheckButton::with_label("Show CPU Monitor");
-    cpu_check.set_active(cpu_frame.is_visible());
-    let cpu_frame_clone = cpu_frame.clone();
+    cpu_check.set_active(frames.cpu.is_visible());
+    let cpu_frame_clone = frames.cpu.clone();
     cpu_check.connect_toggled(move |check| {
         cpu_frame_clone.set_visible(check.is_active());
         let mut settings = get_app_settings();
         settings.monitor_visibility.show_cpu = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     monitor_box.append(&cpu_check);
 
     // RAM toggle
     let ram_check = CheckButton::with_label("Show RAM Monitor");
-    ram_check.set_active(ram_frame.is_visible());
-    let ram_frame_clone = ram_frame.clone();
+    ram_check.set_active(frames.ram.is_visible());
+    let ram_frame_clone = frames.ram.clone();
     ram_check.connect_toggled(move |check| {
         ram_frame_clone.set_visible(check.is_active());
         let mut settings = get_app_settings();
         settings.monitor_visibility.show_ram = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     monitor_box.append(&ram_check);
 
     // Network toggle
     let net_check = CheckButton::with_label("Show Network Monitor");
-    net_check.set_active(net_frame.is_visible());
-    let net_frame_clone = net_frame.clone();
+    net_check.set_active(frames.net.is_visible());
+    let net_frame_clone = frames.net.clone();
     net_check.connect_toggled(move |check| {
         net_frame_clone.set_visible(check.is_active());
         let mut settings = get_app_settings();
         settings.monitor_visibility.show_network = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     monitor_box.append(&net_check);
 
+    // Disk toggle
+    let disk_check = CheckButton::with_label("Show Disk Monitor");
+    disk_check.set_active(frames.disk.is_visible());
+    let disk_frame_clone = frames.disk.clone();
+    disk_check.connect_toggled(move |check| {
+        disk_frame_clone.set_visible(check.is_active());
+        let mut settings = get_app_settings();
+        settings.monitor_visibility.show_disk = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    monitor_box.append(&disk_check);
+
+    // Interfaces summed by the network monitor
+    let interfaces_box = GtkBox::new(Orientation::Horizontal, 12);
+    let interfaces_label = Label::new(Some("Network Interfaces:"));
+    interfaces_label.set_xalign(0.0);
+    interfaces_label.set_hexpand(true);
+    interfaces_box.append(&interfaces_label);
+    interfaces_box.append(&create_interface_selector());
+    monitor_box.append(&interfaces_box);
+
+    // Refresh interval
+    let interval_box = GtkBox::new(Orientation::Horizontal, 12);
+    let interval_label = Label::new(Some("Refresh Interval (ms):"));
+    interval_label.set_xalign(0.0);
+    interval_label.set_hexpand(true);
+    interval_box.append(&interval_label);
+
+    let interval_spin = gtk::SpinButton::with_range(MIN_MONITOR_INTERVAL_MS as f64, 10000.0, 250.0);
+    interval_spin.set_value(get_monitor_interval_ms() as f64);
+    interval_spin.set_digits(0);
+    interval_spin.connect_value_changed(move |spin| {
+        let mut settings = get_app_settings();
+        settings.monitor_interval_ms = spin.value() as u32;
+        report_error(save_app_settings(&settings));
+        crate::ui::window::restart_system_monitoring();
+    });
+    interval_box.append(&interval_spin);
+    monitor_box.append(&interval_box);
+
+    // VPN interface indicator
+    let vpn_box = GtkBox::new(Orientation::Horizontal, 12);
+    let vpn_label = Label::new(Some("VPN Interface (empty = hidden):"));
+    vpn_label.set_xalign(0.0);
+    vpn_label.set_hexpand(true);
+    vpn_box.append(&vpn_label);
+
+    let vpn_entry = Entry::new();
+    vpn_entry.set_text(&get_vpn_interface());
+    vpn_entry.set_placeholder_text(Some("tun0"));
+    vpn_entry.set_width_chars(10);
+    let vpn_save = Rc::new(RefCell::new(None));
+    vpn_entry.connect_changed(move |entry| {
+        let interface = entry.text().trim().to_string();
+        save_setting_when_idle(&vpn_save, move || {
+            let mut settings = get_app_settings();
+            settings.vpn_interface = interface;
+            report_error(save_app_settings(&settings));
+        });
+    });
+    vpn_box.append(&vpn_entry);
+    monitor_box.append(&vpn_box);
+
     page.append(&monitor_box);
 
     // Logging Group
@@ -430,10 +766,76 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     logging_check.connect_toggled(move |check| {
         let mut settings = get_app_settings();
         settings.enable_command_logging = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     logging_box.append(&logging_check);
 
+    let dedup_check = CheckButton::with_label("Collapse repeated commands in the Log viewer");
+    dedup_check.set_active(is_log_dedup_enabled());
+    dedup_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.dedup_log = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    logging_box.append(&dedup_check);
+
+    let rotate_box = GtkBox::new(Orientation::Horizontal, 12);
+    let rotate_label = Label::new(Some("Rotate commands.log at (MB, 0 = never):"));
+    rotate_label.set_xalign(0.0);
+    rotate_label.set_hexpand(true);
+    rotate_label.set_tooltip_text(Some("The full log is kept as commands.log.1, .2, ...; the Log viewer can include them"));
+    rotate_box.append(&rotate_label);
+
+    let rotate_spin = gtk::SpinButton::with_range(0.0, 1024.0, 1.0);
+    rotate_spin.set_value(get_log_rotate_size_mb() as f64);
+    rotate_spin.set_digits(0);
+    rotate_spin.connect_value_changed(move |spin| {
+        let mut settings = get_app_settings();
+        settings.log_rotate_size_mb = spin.value() as u32;
+        report_error(save_app_settings(&settings));
+    });
+    rotate_box.append(&rotate_spin);
+
+    logging_box.append(&rotate_box);
+
+    let keep_box = GtkBox::new(Orientation::Horizontal, 12);
+    let keep_label = Label::new(Some("Rotated logs to keep (0 = all):"));
+    keep_label.set_xalign(0.0);
+    keep_label.set_hexpand(true);
+    keep_label.set_tooltip_text(Some("Older archives beyond this count are deleted when the log rotates"));
+    keep_box.append(&keep_label);
+
+    let keep_spin = gtk::SpinButton::with_range(0.0, 1000.0, 1.0);
+    keep_spin.set_value(get_log_archive_keep() as f64);
+    keep_spin.set_digits(0);
+    keep_spin.connect_value_changed(move |spin| {
+        let mut settings = get_app_settings();
+        settings.log_archive_keep = spin.value() as u32;
+        report_error(save_app_settings(&settings));
+    });
+    keep_box.append(&keep_spin);
+
+    logging_box.append(&keep_box);
+
+    let redaction_label = Label::new(Some(
+        "Redaction patterns (one regex per line, matches are shown as *** in the Log viewer):",
+    ));
+    redaction_label.set_halign(gtk::Align::Start);
+    redaction_label.set_wrap(true);
+    redaction_label.set_margin_top(8);
+    logging_box.append(&redaction_label);
+
+    let redaction_editor = create_pattern_list_editor(
+        &get_redaction_patterns(),
+        default_redaction_patterns(),
+        |patterns| {
+            let mut settings = get_app_settings();
+            settings.redaction_patterns = patterns;
+            report_error(save_app_settings(&settings));
+        },
+    );
+    logging_box.append(&redaction_editor);
+
     page.append(&logging_box);
 
     // Features Group
@@ -452,7 +854,7 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     browser_check.connect_toggled(move |check| {
         let mut settings = get_app_settings();
         settings.enable_browser = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     features_box.append(&browser_check);
 
@@ -461,7 +863,7 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     containers_check.connect_toggled(move |check| {
         let mut settings = get_app_settings();
         settings.enable_containers = check.is_active();
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     features_box.append(&containers_check);
 
@@ -491,11 +893,98 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     scrollback_spin.connect_value_changed(move |spin| {
         let mut settings = get_app_settings();
         settings.terminal_scrollback_lines = spin.value() as i64;
-        let _ = save_app_settings(&settings);
+        report_error(save_app_settings(&settings));
     });
     scrollback_box.append(&scrollback_spin);
 
     terminal_box.append(&scrollback_box);
+
+    // Starting directory for new shells
+    let start_dir_box = GtkBox::new(Orientation::Horizontal, 12);
+    let start_dir_label = Label::new(Some("New Shells Start In:"));
+    start_dir_label.set_xalign(0.0);
+    start_dir_label.set_hexpand(true);
+    start_dir_box.append(&start_dir_label);
+
+    let start_dir_combo = ComboBoxText::new();
+    start_dir_combo.append_text("Base Directory");
+    start_dir_combo.append_text("Home");
+    start_dir_combo.append_text("App Working Directory");
+    start_dir_combo.set_active(Some(match get_shell_start_dir() {
+        ShellStartDir::BaseDir => 0,
+        ShellStartDir::Home => 1,
+        ShellStartDir::AppCwd => 2,
+    }));
+    start_dir_combo.connect_changed(move |combo| {
+        let mut settings = get_app_settings();
+        settings.shell_start_dir = match combo.active() {
+            Some(1) => ShellStartDir::Home,
+            Some(2) => ShellStartDir::AppCwd,
+            _ => ShellStartDir::BaseDir,
+        };
+        report_error(save_app_settings(&settings));
+    });
+    start_dir_box.append(&start_dir_combo);
+
+    terminal_box.append(&start_dir_box);
+
+    // Terminal color theme
+    let theme_box = GtkBox::new(Orientation::Horizontal, 12);
+    let theme_label = Label::new(Some("Terminal Color Theme:"));
+    theme_label.set_xalign(0.0);
+    theme_label.set_hexpand(true);
+    theme_box.append(&theme_label);
+
+    let theme_combo = ComboBoxText::new();
+    theme_combo.append(Some("System"), "System (follow color scheme)");
+    for theme in crate::ui::terminal::TERMINAL_THEMES.iter() {
+        theme_combo.append(Some(theme.name), theme.name);
+    }
+    if !theme_combo.set_active_id(Some(&get_terminal_theme())) {
+        theme_combo.set_active(Some(0));
+    }
+    theme_combo.connect_changed(move |combo| {
+        if let Some(id) = combo.active_id() {
+            crate::ui::terminal::set_terminal_theme(&id);
+        }
+    });
+    theme_box.append(&theme_combo);
+
+    terminal_box.append(&theme_box);
+
+    // Target autocomplete while typing
+    let autocomplete_check = CheckButton::with_label("Suggest targets while typing (Tab to complete, Ctrl+Space to show)");
+    autocomplete_check.set_active(is_target_autocomplete_enabled());
+    autocomplete_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.target_autocomplete = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    terminal_box.append(&autocomplete_check);
+
+    // Text typed before timestamps inserted with the Insert Timestamp shortcut
+    let timestamp_box = GtkBox::new(Orientation::Horizontal, 12);
+    let timestamp_label = Label::new(Some("Timestamp Prefix in Terminals:"));
+    timestamp_label.set_xalign(0.0);
+    timestamp_label.set_hexpand(true);
+    timestamp_label.set_tooltip_text(Some("Keep it a shell comment (\"# \") so pressing Enter doesn't run the timestamp"));
+    timestamp_box.append(&timestamp_label);
+
+    let timestamp_entry = Entry::new();
+    timestamp_entry.set_text(&get_terminal_timestamp_prefix());
+    timestamp_entry.set_width_chars(10);
+    let timestamp_save = Rc::new(RefCell::new(None));
+    timestamp_entry.connect_changed(move |entry| {
+        let prefix = entry.text().to_string();
+        save_setting_when_idle(&timestamp_save, move || {
+            let mut settings = get_app_settings();
+            settings.terminal_timestamp_prefix = prefix;
+            report_error(save_app_settings(&settings));
+        });
+    });
+    timestamp_box.append(&timestamp_entry);
+    terminal_box.append(&timestamp_box);
+
     page.append(&terminal_box);
 
     // Notes Group
@@ -516,6 +1005,137 @@ fn create_general_settings_page(cpu_frame: &Frame, ram_frame: &Frame, net_frame:
     });
     notes_box.append(&wrap_check);
 
+    // Notes snapshot interval
+    let backup_box = GtkBox::new(Orientation::Horizontal, 12);
+    let backup_label = Label::new(Some("Snapshot Interval (minutes, 0 = off):"));
+    backup_label.set_xalign(0.0);
+    backup_label.set_hexpand(true);
+    backup_box.append(&backup_label);
+
+    let backup_spin = gtk::SpinButton::with_range(0.0, 240.0, 1.0);
+    backup_spin.set_value(get_notes_backup_interval() as f64);
+    backup_spin.set_digits(0);
+    backup_spin.connect_value_changed(move |spin| {
+        let mut settings = get_app_settings();
+        settings.notes_backup_interval_minutes = spin.value() as u32;
+        report_error(save_app_settings(&settings));
+    });
+    backup_box.append(&backup_spin);
+
+    notes_box.append(&backup_box);
+
+    // Autosave delay after the last keystroke
+    let autosave_box = GtkBox::new(Orientation::Horizontal, 12);
+    let autosave_label = Label::new(Some("Autosave Delay (ms):"));
+    autosave_label.set_xalign(0.0);
+    autosave_label.set_hexpand(true);
+    autosave_box.append(&autosave_label);
+
+    let autosave_spin = gtk::SpinButton::with_range(MIN_AUTOSAVE_DEBOUNCE_MS as f64, 10000.0, 100.0);
+    autosave_spin.set_value(get_autosave_debounce_ms() as f64);
+    autosave_spin.set_digits(0);
+    autosave_spin.connect_value_changed(move |spin| {
+        let mut settings = get_app_settings();
+        settings.autosave_debounce_ms = spin.value() as u32;
+        report_error(save_app_settings(&settings));
+    });
+    autosave_box.append(&autosave_spin);
+
+    notes_box.append(&autosave_box);
+
+    let autosave_targets_check = CheckButton::with_label("Autosave targets.txt while editing");
+    autosave_targets_check.set_active(is_targets_autosave_enabled());
+    autosave_targets_check.set_tooltip_text(Some("When off, targets are only saved with Ctrl+S or the save button. Applies after a restart"));
+    autosave_targets_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.autosave_targets = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    notes_box.append(&autosave_targets_check);
+
+    // Spell checking, using the Hunspell dictionaries installed on the system
+    let spell_check = CheckButton::with_label("Check spelling in notes");
+    spell_check.set_active(is_spell_check_enabled());
+    spell_check.set_tooltip_text(Some("Underlines unknown words outside code; right-click a word for suggestions"));
+    spell_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.spell_check = check.is_active();
+        report_error(save_app_settings(&settings));
+        crate::ui::editor::refresh_notes_spelling();
+    });
+    notes_box.append(&spell_check);
+
+    let language_box = GtkBox::new(Orientation::Horizontal, 12);
+    let language_label = Label::new(Some("Spelling Language:"));
+    language_label.set_xalign(0.0);
+    language_label.set_hexpand(true);
+    language_box.append(&language_label);
+
+    let language_combo = ComboBoxText::new();
+    let languages = crate::ui::spellcheck::available_languages();
+    for language in &languages {
+        language_combo.append(Some(language), language);
+    }
+    if languages.is_empty() {
+        language_combo.append(Some(""), "No dictionaries found");
+        language_combo.set_active(Some(0));
+        language_combo.set_sensitive(false);
+        language_combo.set_tooltip_text(Some("Install a Hunspell dictionary, e.g. hunspell-en-us"));
+    } else if !language_combo.set_active_id(Some(&get_spell_check_language())) {
+        language_combo.set_active(Some(0));
+    }
+    language_combo.connect_changed(move |combo| {
+        if let Some(id) = combo.active_id().filter(|id| !id.is_empty()) {
+            let mut settings = get_app_settings();
+            settings.spell_check_language = id.to_string();
+            report_error(save_app_settings(&settings));
+            crate::ui::editor::refresh_notes_spelling();
+        }
+    });
+    language_box.append(&language_combo);
+
+    notes_box.append(&language_box);
+
+    // Scaffold inserted by the notes toolbar's New Host Section button
+    let host_section_label = Label::new(Some("New host section template ({target} and {timestamp} are filled in):"));
+    host_section_label.set_xalign(0.0);
+    host_section_label.set_wrap(true);
+    host_section_label.set_margin_top(8);
+    notes_box.append(&host_section_label);
+
+    let host_section_view = gtk::TextView::builder()
+        .monospace(true)
+        .top_margin(4)
+        .bottom_margin(4)
+        .left_margin(4)
+        .right_margin(4)
+        .build();
+    host_section_view.buffer().set_text(&get_host_section_template());
+    let host_section_save = Rc::new(RefCell::new(None));
+    host_section_view.buffer().connect_changed(move |buffer| {
+        let template = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string();
+        save_setting_when_idle(&host_section_save, move || {
+            let mut settings = get_app_settings();
+            settings.host_section_template = template;
+            report_error(save_app_settings(&settings));
+        });
+    });
+    let host_section_scroll = ScrolledWindow::builder()
+        .child(&host_section_view)
+        .min_content_height(120)
+        .hscrollbar_policy(gtk::PolicyType::Automatic)
+        .build();
+    let host_section_frame = Frame::new(None);
+    host_section_frame.set_child(Some(&host_section_scroll));
+    notes_box.append(&host_section_frame);
+
+    let host_section_reset = Button::with_label("Reset to Default");
+    host_section_reset.set_halign(gtk::Align::Start);
+    host_section_reset.connect_clicked(move |_| {
+        host_section_view.buffer().set_text(&default_host_section_template());
+    });
+    notes_box.append(&host_section_reset);
+
     page.append(&notes_box);
 
     // Zoom Group
@@ -623,63 +1243,13 @@ fn create_shortcuts_page(parent: &adw::ApplicationWindow) -> ScrolledWindow {
     list_box.set_selection_mode(gtk::SelectionMode::None);
     list_box.add_css_class("boxed-list");
 
-    // Toggle drawer shortcut
-    let drawer_row = create_shortcut_row(
-        "Toggle Command Drawer",
-        &format!("Ctrl+{}", key_to_display(&shortcuts.toggle_drawer)),
-        parent,
-        "toggle_drawer",
-        false,
-    );
-    list_box.append(&drawer_row);
-
-    // Insert target shortcut
-    let target_row = create_shortcut_row(
-        "Insert Target",
-        &format!("Ctrl+{}", key_to_display(&shortcuts.insert_target)),
-        parent,
-        "insert_target",
-        false,
-    );
-    list_box.append(&target_row);
-
-    // Insert timestamp shortcut
-    let timestamp_row = create_shortcut_row(
-        "Insert Timestamp",
-        &format!("Ctrl+Shift+{}", key_to_display(&shortcuts.insert_timestamp)),
-        parent,
-        "insert_timestamp",
-        true,
-    );
-    list_box.append(&timestamp_row);
-
-    // New shell shortcut
-    let new_shell_text = shortcuts.new_shell
-        .as_ref()
-        .map(|k| format!("Ctrl+Shift+{}", key_to_display(k)))
-        .unwrap_or_else(|| "Not assigned".to_string());
-    let new_shell_row = create_shortcut_row(
-        "New Shell Tab",
-        &new_shell_text,
-        parent,
-        "new_shell",
-        true,
-    );
-    list_box.append(&new_shell_row);
-
-    // New split shortcut
-    let new_split_text = shortcuts.new_split
-        .as_ref()
-        .map(|k| format!("Ctrl+Shift+{}", key_to_display(k)))
-        .unwrap_or_else(|| "Not assigned".to_string());
-    let new_split_row = create_shortcut_row(
-        "New Split View",
-        &new_split_text,
-        parent,
-        "new_split",
-        true,
-    );
-    list_box.append(&new_split_row);
+    for (name, title) in SHORTCUT_TITLES {
+        let current = shortcuts
+            .get(name)
+            .map(|shortcut| shortcut_to_display(&shortcut))
+            .unwrap_or_else(|| "Not assigned".to_string());
+        list_box.append(&create_shortcut_row(title, &current, parent, name));
+    }
 
     page.append(&list_box);
 
@@ -695,7 +1265,6 @@ fn create_shortcut_row(
     current_value: &str,
     parent: &adw::ApplicationWindow,
     shortcut_name: &str,
-    _requires_shift: bool,
 ) -> gtk::ListBoxRow {
     let row = gtk::ListBoxRow::new();
 
@@ -712,6 +1281,7 @@ fn create_shortcut_row(
     let shortcut_label = Label::new(Some(current_value));
     shortcut_label.add_css_class("dim-label");
     shortcut_label.add_css_class("numeric");
+    shortcut_label.set_widget_name(&format!("shortcut_{}", shortcut_name));
 
     let change_btn = Button::with_label("Change");
     change_btn.add_css_class("flat");
@@ -729,18 +1299,41 @@ fn create_shortcut_row(
     clear_btn.add_css_class("flat");
     let shortcut_name_owned2 = shortcut_name.to_string();
     let shortcut_label_clone2 = shortcut_label.clone();
-    clear_btn.connect_clicked(move |_| {
+    clear_btn.connect_clicked(move |btn| {
         let mut settings = get_app_settings();
-        match shortcut_name_owned2.as_str() {
-            "toggle_drawer" => settings.keyboard_shortcuts.toggle_drawer = String::new(),
-            "insert_target" => settings.keyboard_shortcuts.insert_target = String::new(),
-            "insert_timestamp" => settings.keyboard_shortcuts.insert_timestamp = String::new(),
-            "new_shell" => settings.keyboard_shortcuts.new_shell = None,
-            "new_split" => settings.keyboard_shortcuts.new_split = None,
-            _ => {}
+        let Some(previous) = settings.keyboard_shortcuts.get(&shortcut_name_owned2) else {
+            return;
+        };
+        settings.keyboard_shortcuts.set(&shortcut_name_owned2, None);
+        if let Err(e) = save_app_settings(&settings) {
+            show_error_toast(&e);
+            return;
         }
-        let _ = save_app_settings(&settings);
         shortcut_label_clone2.set_text("Not assigned");
+
+        // Offer to put the old key back in case the clear was accidental
+        let Some(toast_overlay) = btn.ancestor(adw::ToastOverlay::static_type()).and_downcast::<adw::ToastOverlay>() else {
+            return;
+        };
+        let toast = adw::Toast::new(&format!("Cleared {}", shortcut_to_display(&previous)));
+        toast.set_button_label(Some("Undo"));
+        toast.set_timeout(5);
+        let shortcut_name_undo = shortcut_name_owned2.clone();
+        let shortcut_label_undo = shortcut_label_clone2.clone();
+        toast.connect_button_clicked(move |_| {
+            let mut settings = get_app_settings();
+            // Don't double-book the key if it was given to another action in the meantime
+            if let Some(other) = settings.keyboard_shortcuts.conflicting_shortcut(&shortcut_name_undo, &previous) {
+                show_error_toast(&format!("{} is now used by {}", shortcut_to_display(&previous), shortcut_title(other)));
+                return;
+            }
+            settings.keyboard_shortcuts.set(&shortcut_name_undo, Some(previous.clone()));
+            match save_app_settings(&settings) {
+                Ok(()) => shortcut_label_undo.set_text(&shortcut_to_display(&previous)),
+                Err(e) => show_error_toast(&e),
+            }
+        });
+        toast_overlay.add_toast(toast);
     });
 
     row_box.append(&title_label);
@@ -772,23 +1365,49 @@ fn show_key_capture_dialog(parent: &adw::ApplicationWindow, shortcut_name: &str,
     dialog_box.set_margin_end(24);
     dialog_box.set_halign(gtk::Align::Center);
 
-    let info = Label::new(Some("Press Ctrl + any key"));
+    let info = Label::new(Some("Press a key combination with Ctrl, Alt or Super, or a function key"));
     info.set_wrap(true);
+    info.set_justify(gtk::Justification::Center);
     info.add_css_class("dim-label");
 
     let current_key = Label::new(Some("Waiting for key..."));
     current_key.add_css_class("title-2");
 
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::Center);
+
     let cancel_btn = Button::with_label("Cancel");
-    cancel_btn.set_halign(gtk::Align::Center);
     let dialog_clone = dialog.clone();
     cancel_btn.connect_clicked(move |_| {
         dialog_clone.close();
     });
 
+    // Shown when the pressed key is taken; moves it over from the other action,
+    // or keeps it anyway when it shadows a built-in key
+    let reassign_btn = Button::with_label("Reassign");
+    reassign_btn.add_css_class("destructive-action");
+    reassign_btn.set_visible(false);
+
+    button_box.append(&cancel_btn);
+    button_box.append(&reassign_btn);
+
     dialog_box.append(&info);
     dialog_box.append(&current_key);
-    dialog_box.append(&cancel_btn);
+    dialog_box.append(&button_box);
+
+    // Key and display text waiting on a conflict decision, with the action that holds the key
+    // (None when the key is a built-in one)
+    let pending: Rc<RefCell<Option<(String, String, Option<&'static str>)>>> = Rc::new(RefCell::new(None));
+
+    let pending_clone = Rc::clone(&pending);
+    let shortcut_name_owned2 = shortcut_name.to_string();
+    let display_label_clone2 = display_label.clone();
+    let dialog_clone3 = dialog.clone();
+    reassign_btn.connect_clicked(move |_| {
+        if let Some((key_name, display_text, other)) = pending_clone.borrow_mut().take() {
+            save_captured_shortcut(&shortcut_name_owned2, &key_name, &display_text, other, &display_label_clone2, &dialog_clone3);
+        }
+    });
 
     // Keyboard handler
     let key_controller = gtk::EventControllerKey::new();
@@ -796,43 +1415,64 @@ fn show_key_capture_dialog(parent: &adw::ApplicationWindow, shortcut_name: &str,
     let display_label_clone = display_label.clone();
     let dialog_clone2 = dialog.clone();
     let current_key_clone = current_key.clone();
+    let info_clone = info.clone();
+    let reassign_btn_clone = reassign_btn.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
-            let key_name = keyval.name().unwrap_or_default().to_string();
-            let has_shift = modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK);
-
-            let display_text = if has_shift {
-                format!("Ctrl+Shift+{}", key_to_display(&key_name))
-            } else {
-                format!("Ctrl+{}", key_to_display(&key_name))
-            };
-            current_key_clone.set_text(&display_text);
+        // Wait for the rest of the combination when only a modifier is down
+        let Some(shortcut) = shortcut_from_event(keyval, modifier) else {
+            return gtk::glib::Propagation::Proceed;
+        };
+        if keyval == gtk::gdk::Key::Escape && modifier.is_empty() {
+            dialog_clone2.close();
+            return gtk::glib::Propagation::Stop;
+        }
 
-            // Save the shortcut
-            let mut settings = get_app_settings();
-            match shortcut_name_owned.as_str() {
-                "toggle_drawer" => settings.keyboard_shortcuts.toggle_drawer = key_name.clone(),
-                "insert_target" => settings.keyboard_shortcuts.insert_target = key_name.clone(),
-                "insert_timestamp" => settings.keyboard_shortcuts.insert_timestamp = key_name.clone(),
-                "new_shell" => settings.keyboard_shortcuts.new_shell = Some(key_name.clone()),
-                "new_split" => settings.keyboard_shortcuts.new_split = Some(key_name.clone()),
-                _ => {}
-            }
+        let display_text = shortcut_to_display(&shortcut);
+        current_key_clone.set_text(&display_text);
 
-            if save_app_settings(&settings).is_ok() {
-                display_label_clone.set_text(&display_text);
+        // Plain keys would stop that character from being typed
+        if !is_valid_shortcut(&shortcut) {
+            info_clone.set_text("Combine the key with Ctrl, Alt or Super, or use a function key.");
+            info_clone.remove_css_class("dim-label");
+            info_clone.add_css_class("warning");
+            reassign_btn_clone.set_visible(false);
+            pending.borrow_mut().take();
+            return gtk::glib::Propagation::Stop;
+        }
 
-                // Close after delay
-                let dialog = dialog_clone2.clone();
-                gtk4::glib::timeout_add_local_once(std::time::Duration::from_millis(400), move || {
-                    dialog.close();
-                });
-            }
+        // Don't let two actions share a key; one of them would silently never fire
+        let shortcuts = get_keyboard_shortcuts();
+        if let Some(other) = shortcuts.conflicting_shortcut(&shortcut_name_owned, &shortcut) {
+            info_clone.set_text(&format!(
+                "{} is already used by {}. Press another key, or reassign it.",
+                display_text,
+                shortcut_title(other)
+            ));
+            info_clone.remove_css_class("dim-label");
+            info_clone.add_css_class("warning");
+            reassign_btn_clone.set_label("Reassign");
+            reassign_btn_clone.set_visible(true);
+            *pending.borrow_mut() = Some((shortcut, display_text, Some(other)));
+            return gtk::glib::Propagation::Stop;
+        }
 
+        // Built-in keys such as Ctrl+1..9 would stop working where the shortcut is handled first
+        if let Some(builtin) = reserved_shortcut_title(&shortcut) {
+            info_clone.set_text(&format!(
+                "{} is a built-in key for {}. Press another key, or use it anyway.",
+                display_text, builtin
+            ));
+            info_clone.remove_css_class("dim-label");
+            info_clone.add_css_class("warning");
+            reassign_btn_clone.set_label("Use Anyway");
+            reassign_btn_clone.set_visible(true);
+            *pending.borrow_mut() = Some((shortcut, display_text, None));
             return gtk::glib::Propagation::Stop;
         }
-        gtk::glib::Propagation::Proceed
+
+        save_captured_shortcut(&shortcut_name_owned, &shortcut, &display_text, None, &display_label_clone, &dialog_clone2);
+        gtk::glib::Propagation::Stop
     });
 
     content.set_child(Some(&dialog_box));
@@ -841,6 +1481,64 @@ fn show_key_capture_dialog(parent: &adw::ApplicationWindow, shortcut_name: &str,
     dialog.present();
 }
 
+/// Saves a captured shortcut, first unassigning the action it was taken from, then closes the dialog
+fn save_captured_shortcut(
+    shortcut_name: &str,
+    key_name: &str,
+    display_text: &str,
+    taken_from: Option<&str>,
+    display_label: &Label,
+    dialog: &adw::Window,
+) {
+    let mut settings = get_app_settings();
+    if let Some(other) = taken_from {
+        settings.keyboard_shortcuts.set(other, None);
+    }
+    settings.keyboard_shortcuts.set(shortcut_name, Some(key_name.to_string()));
+
+    if let Err(e) = save_app_settings(&settings) {
+        show_error_toast(&e);
+        return;
+    }
+    display_label.set_text(display_text);
+
+    // The other action's row lives in the same list; show that it lost its key
+    if let Some(other) = taken_from {
+        let other_name = format!("shortcut_{}", other);
+        let mut row = display_label
+            .ancestor(ListBox::static_type())
+            .and_then(|list| list.first_child());
+        while let Some(current) = row {
+            if let Some(label) = find_label_by_name(&current, &other_name) {
+                label.set_text("Not assigned");
+                break;
+            }
+            row = current.next_sibling();
+        }
+    }
+
+    // Close after delay
+    let dialog = dialog.clone();
+    gtk4::glib::timeout_add_local_once(std::time::Duration::from_millis(400), move || {
+        dialog.close();
+    });
+}
+
+/// Finds a label with the given widget name inside a widget tree
+fn find_label_by_name(widget: &gtk::Widget, name: &str) -> Option<Label> {
+    if widget.widget_name() == name {
+        return widget.clone().downcast::<Label>().ok();
+    }
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        if let Some(label) = find_label_by_name(&current, name) {
+            return Some(label);
+        }
+        child = current.next_sibling();
+    }
+    None
+}
+
 /// Creates the custom commands page
 /// Creates the browser settings page with proxy configuration
 fn create_browser_settings_page() -> ScrolledWindow {
@@ -1067,12 +1765,15 @@ fn create_browser_settings_page() -> ScrolledWindow {
         // Save to app settings
         let mut app_settings = get_app_settings();
         app_settings.browser_settings = new_settings;
-        if save_app_settings(&app_settings).is_ok() {
-            btn.set_label("Saved!");
-            let btn_clone = btn.clone();
-            gtk::glib::timeout_add_seconds_local_once(2, move || {
-                btn_clone.set_label("Save Browser Settings");
-            });
+        match save_app_settings(&app_settings) {
+            Ok(()) => {
+                btn.set_label("Saved!");
+                let btn_clone = btn.clone();
+                gtk::glib::timeout_add_seconds_local_once(2, move || {
+                    btn_clone.set_label("Save Browser Settings");
+                });
+            }
+            Err(e) => show_error_toast(&e),
         }
     });
 
@@ -1091,12 +1792,39 @@ fn create_browser_settings_page() -> ScrolledWindow {
     scrolled
 }
 
+/// Lets a custom command row be dragged onto another; `on_move(from, to)` saves the new order
+fn add_command_reorder_dnd<F>(row: &gtk::ListBoxRow, index: usize, on_move: F)
+where
+    F: Fn(usize, usize) + 'static,
+{
+    let drag_source = gtk::DragSource::new();
+    drag_source.set_actions(gtk::gdk::DragAction::MOVE);
+    drag_source.connect_prepare(move |_, _, _| {
+        Some(gtk::gdk::ContentProvider::for_value(&(index as u32).to_value()))
+    });
+    let row_clone = row.clone();
+    drag_source.connect_drag_begin(move |source, _| {
+        source.set_icon(Some(&gtk::WidgetPaintable::new(Some(&row_clone))), 0, 0);
+    });
+    row.add_controller(drag_source);
+
+    let drop_target = gtk::DropTarget::new(u32::static_type(), gtk::gdk::DragAction::MOVE);
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Ok(from) = value.get::<u32>() else {
+            return false;
+        };
+        if from as usize != index {
+            on_move(from as usize, index);
+        }
+        true
+    });
+    row.add_controller(drop_target);
+}
+
 fn create_commands_page(
     parent: &adw::ApplicationWindow,
     settings_dialog: &adw::Window,
-    cpu_frame: &Frame,
-    ram_frame: &Frame,
-    net_frame: &Frame,
+    frames: &MonitorFrames,
 ) -> ScrolledWindow {
     let scrolled = ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Never)
@@ -1122,7 +1850,7 @@ fn create_commands_page(
     let inner_box = GtkBox::new(Orientation::Vertical, 8);
     inner_box.set_margin_start(12);
 
-    let hint_label = Label::new(Some("Add your own command templates. Use {target} as placeholder."));
+    let hint_label = Label::new(Some("Add your own command templates. Use {target} as placeholder. Drag rows to reorder them."));
     hint_label.add_css_class("dim-label");
     hint_label.set_halign(gtk::Align::Start);
     hint_label.set_wrap(true);
@@ -1153,6 +1881,10 @@ fn create_commands_page(
             row_box.set_margin_start(12);
             row_box.set_margin_end(12);
 
+            let drag_handle = gtk::Image::from_icon_name("list-drag-handle-symbolic");
+            drag_handle.add_css_class("dim-label");
+            drag_handle.set_tooltip_text(Some("Drag to reorder"));
+
             let info_box = GtkBox::new(Orientation::Vertical, 2);
             info_box.set_hexpand(true);
 
@@ -1176,19 +1908,15 @@ fn create_commands_page(
 
             let parent_clone = parent.clone();
             let dialog_clone = settings_dialog.clone();
-            let cpu_clone = cpu_frame.clone();
-            let ram_clone = ram_frame.clone();
-            let net_clone = net_frame.clone();
+            let frames_clone = frames.clone();
             let cmd_clone = cmd.clone();
             edit_btn.connect_clicked(move |_| {
                 let parent_ref = parent_clone.clone();
                 let dialog_ref = dialog_clone.clone();
-                let cpu_ref = cpu_clone.clone();
-                let ram_ref = ram_clone.clone();
-                let net_ref = net_clone.clone();
+                let frames_ref = frames_clone.clone();
                 show_edit_command_dialog(&parent_clone, idx, cmd_clone.clone(), move || {
                     dialog_ref.close();
-                    show_settings_dialog(&parent_ref, &cpu_ref, &ram_ref, &net_ref);
+                    show_settings_dialog(&parent_ref, &frames_ref);
                 });
             });
 
@@ -1201,21 +1929,38 @@ fn create_commands_page(
 
             let parent_clone2 = parent.clone();
             let dialog_clone2 = settings_dialog.clone();
-            let cpu_clone2 = cpu_frame.clone();
-            let ram_clone2 = ram_frame.clone();
-            let net_clone2 = net_frame.clone();
+            let frames_clone2 = frames.clone();
             delete_btn.connect_clicked(move |_| {
-                if delete_custom_command(idx).is_ok() {
-                    dialog_clone2.close();
-                    show_settings_dialog(&parent_clone2, &cpu_clone2, &ram_clone2, &net_clone2);
+                match delete_custom_command(idx) {
+                    Ok(()) => {
+                        dialog_clone2.close();
+                        show_settings_dialog(&parent_clone2, &frames_clone2);
+                    }
+                    Err(e) => show_error_toast(&format!("Failed to delete command: {}", e)),
                 }
             });
 
+            // Dropping a row on another moves it to that position
+            let parent_clone3 = parent.clone();
+            let dialog_clone3 = settings_dialog.clone();
+            let frames_clone3 = frames.clone();
+            row_box.append(&drag_handle);
             row_box.append(&info_box);
             row_box.append(&edit_btn);
             row_box.append(&delete_btn);
 
             row.set_child(Some(&row_box));
+            add_command_reorder_dnd(&row, idx, move |from, to| {
+                match move_custom_command(from, to) {
+                    Ok(()) => {
+                        dialog_clone3.close();
+                        show_settings_dialog_at_tab(
+                            &parent_clone3, &frames_clone3, settings_tabs::COMMANDS,
+                        );
+                    }
+                    Err(e) => show_error_toast(&format!("Failed to move command: {}", e)),
+                }
+            });
             list_box.append(&row);
         }
     }
@@ -1231,60 +1976,986 @@ fn create_commands_page(
 
     let parent_clone = parent.clone();
     let dialog_clone = settings_dialog.clone();
-    let cpu_clone = cpu_frame.clone();
-    let ram_clone = ram_frame.clone();
-    let net_clone = net_frame.clone();
+    let frames_clone = frames.clone();
     add_btn.connect_clicked(move |_| {
         let parent_ref = parent_clone.clone();
         let dialog_ref = dialog_clone.clone();
-        let cpu_ref = cpu_clone.clone();
-        let ram_ref = ram_clone.clone();
-        let net_ref = net_clone.clone();
+        let frames_ref = frames_clone.clone();
         show_add_command_dialog(&parent_clone, move || {
             dialog_ref.close();
-            show_settings_dialog(&parent_ref, &cpu_ref, &ram_ref, &net_ref);
+            show_settings_dialog(&parent_ref, &frames_ref);
         });
     });
 
     inner_box.append(&add_btn);
-    page.append(&inner_box);
 
-    content.set_child(Some(&page));
-    scrolled.set_child(Some(&content));
+    // Import / export command packs
+    let pack_box = GtkBox::new(Orientation::Horizontal, 8);
+    pack_box.set_halign(gtk::Align::Center);
+    pack_box.set_margin_top(6);
 
-    scrolled
-}
+    let import_btn = Button::with_label("Import...");
+    import_btn.set_tooltip_text(Some("Merge commands from a YAML command pack"));
+    let export_btn = Button::with_label("Export...");
+    export_btn.set_tooltip_text(Some("Save your custom commands to a YAML file"));
+    export_btn.set_sensitive(!commands.is_empty());
+    pack_box.append(&import_btn);
+    pack_box.append(&export_btn);
+    inner_box.append(&pack_box);
 
-/// Shows dialog to add a new custom command
-fn show_add_command_dialog<F>(parent: &adw::ApplicationWindow, on_save: F)
-where
-    F: Fn() + 'static,
-{
-    let dialog = adw::Window::builder()
-        .transient_for(parent)
-        .modal(true)
-        .title("Add Custom Command")
-        .default_width(450)
-        .default_height(400)
-        .build();
+    let parent_clone = parent.clone();
+    let dialog_clone = settings_dialog.clone();
+    let frames_clone = frames.clone();
+    import_btn.connect_clicked(move |_| {
+        let chooser = gtk::FileChooserNative::new(
+            Some("Import Command Pack"),
+            Some(&dialog_clone),
+            gtk::FileChooserAction::Open,
+            Some("Import"),
+            Some("Cancel"),
+        );
+        chooser.add_filter(&yaml_file_filter());
 
-    let main_box = GtkBox::new(Orientation::Vertical, 0);
+        let parent_ref = parent_clone.clone();
+        let dialog_ref = dialog_clone.clone();
+        let frames_ref = frames_clone.clone();
+        chooser.connect_response(move |chooser, response| {
+            if response != gtk::ResponseType::Accept {
+                return;
+            }
+            let Some(path) = chooser.file().and_then(|f| f.path()) else { return };
+            let message = match import_custom_commands(&path) {
+                Ok(0) => "No new commands to import".to_string(),
+                Ok(1) => "Imported 1 command".to_string(),
+                Ok(added) => format!("Imported {} commands", added),
+                Err(e) => e,
+            };
+            dialog_ref.close();
+            let toast_overlay = show_settings_dialog_at_tab(
+                &parent_ref, &frames_ref, settings_tabs::COMMANDS,
+            );
+            toast_overlay.add_toast(adw::Toast::new(&message));
+        });
 
-    let header = adw::HeaderBar::new();
-    main_box.append(&header);
+        chooser.show();
+    });
 
-    let content = adw::Clamp::new();
-    content.set_maximum_size(400);
+    let dialog_clone = settings_dialog.clone();
+    export_btn.connect_clicked(move |_| {
+        let chooser = gtk::FileChooserNative::new(
+            Some("Export Command Pack"),
+            Some(&dialog_clone),
+            gtk::FileChooserAction::Save,
+            Some("Export"),
+            Some("Cancel"),
+        );
+        chooser.set_current_name("penenv_commands.yaml");
+        chooser.add_filter(&yaml_file_filter());
 
-    let page = GtkBox::new(Orientation::Vertical, 12);
-    page.set_margin_top(24);
-    page.set_margin_bottom(24);
-    page.set_margin_start(12);
-    page.set_margin_end(12);
+        let dialog_ref = dialog_clone.clone();
+        chooser.connect_response(move |chooser, response| {
+            if response != gtk::ResponseType::Accept {
+                return;
+            }
+            let Some(path) = chooser.file().and_then(|f| f.path()) else { return };
+            let message = match export_custom_commands(&path) {
+                Ok(count) => format!("Exported {} commands to {}", count, path.display()),
+                Err(e) => e,
+            };
+            if let Some(toast_overlay) = dialog_ref.content().and_downcast::<adw::ToastOverlay>() {
+                toast_overlay.add_toast(adw::Toast::new(&message));
+            }
+        });
 
-    // Name entry
-    let name_box = GtkBox::new(Orientation::Vertical, 4);
-    let name_label = Label::new(Some("Name"));
+        chooser.show();
+    });
+
+    page.append(&inner_box);
+
+    // Command variables section
+    let variables_heading = Label::new(Some("Command Variables"));
+    variables_heading.add_css_class("title-4");
+    variables_heading.set_halign(gtk::Align::Start);
+    variables_heading.set_margin_top(24);
+    variables_heading.set_margin_bottom(12);
+    page.append(&variables_heading);
+
+    let variables_box = GtkBox::new(Orientation::Vertical, 8);
+    variables_box.set_margin_start(12);
+
+    let variables_hint = Label::new(Some(
+        "Values filled into {name} placeholders automatically. Leave empty to use the detected default or be prompted.",
+    ));
+    variables_hint.add_css_class("dim-label");
+    variables_hint.set_halign(gtk::Align::Start);
+    variables_hint.set_wrap(true);
+    variables_box.append(&variables_hint);
+
+    let variables_list = ListBox::new();
+    variables_list.set_selection_mode(gtk::SelectionMode::None);
+    variables_list.add_css_class("boxed-list");
+    variables_list.set_margin_top(12);
+
+    let detected = detect_default_interface();
+    let saved_variables = get_command_variables();
+    let mut variable_names: Vec<String> = DEFAULT_COMMAND_VARIABLES.iter().map(|n| n.to_string()).collect();
+    let mut extra_names: Vec<String> = saved_variables
+        .keys()
+        .filter(|k| !variable_names.contains(k))
+        .cloned()
+        .collect();
+    extra_names.sort();
+    variable_names.extend(extra_names);
+
+    for name in &variable_names {
+        let placeholder = match (name.as_str(), &detected) {
+            ("interface", Some((interface, _))) => Some(interface.clone()),
+            ("lhost", Some((_, addr))) => Some(addr.clone()),
+            _ => None,
+        };
+        let value = saved_variables.get(name).cloned().unwrap_or_default();
+        variables_list.append(&create_command_variable_row(name, &value, placeholder.as_deref()));
+    }
+    variables_box.append(&variables_list);
+
+    // Add a custom variable
+    let add_variable_box = GtkBox::new(Orientation::Horizontal, 8);
+    add_variable_box.set_margin_top(8);
+    let new_variable_entry = Entry::new();
+    new_variable_entry.set_placeholder_text(Some("New variable name (e.g. wordlist)"));
+    new_variable_entry.set_hexpand(true);
+    let add_variable_btn = Button::with_label("Add Variable");
+
+    let variables_list_clone = variables_list.clone();
+    let new_variable_entry_clone = new_variable_entry.clone();
+    add_variable_btn.connect_clicked(move |_| {
+        let name = new_variable_entry_clone.text().trim().trim_matches(|c| c == '{' || c == '}').to_string();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return;
+        }
+        variables_list_clone.append(&create_command_variable_row(&name, "", None));
+        new_variable_entry_clone.set_text("");
+    });
+
+    add_variable_box.append(&new_variable_entry);
+    add_variable_box.append(&add_variable_btn);
+    variables_box.append(&add_variable_box);
+
+    page.append(&variables_box);
+
+    // Dangerous command patterns
+    let dangerous_heading = Label::new(Some("Dangerous Commands"));
+    dangerous_heading.add_css_class("title-4");
+    dangerous_heading.set_halign(gtk::Align::Start);
+    dangerous_heading.set_margin_top(24);
+    dangerous_heading.set_margin_bottom(12);
+    page.append(&dangerous_heading);
+
+    let dangerous_box = GtkBox::new(Orientation::Vertical, 8);
+    dangerous_box.set_margin_start(12);
+
+    let dangerous_hint = Label::new(Some(
+        "Commands containing any of these (one per line, case-insensitive) ask for confirmation before being inserted or run.",
+    ));
+    dangerous_hint.add_css_class("dim-label");
+    dangerous_hint.set_halign(gtk::Align::Start);
+    dangerous_hint.set_wrap(true);
+    dangerous_box.append(&dangerous_hint);
+
+    let dangerous_editor = create_pattern_list_editor(
+        &get_dangerous_patterns(),
+        default_dangerous_patterns(),
+        |patterns| {
+            let mut settings = get_app_settings();
+            settings.dangerous_patterns = patterns;
+            report_error(save_app_settings(&settings));
+        },
+    );
+    dangerous_box.append(&dangerous_editor);
+
+    let preview_check = CheckButton::with_label("Preview commands before inserting them");
+    preview_check.set_active(is_command_preview_enabled());
+    preview_check.set_tooltip_text(Some("Shows the final command after placeholders are filled so it can be edited first"));
+    preview_check.set_margin_top(12);
+    preview_check.connect_toggled(move |check| {
+        let mut settings = get_app_settings();
+        settings.preview_commands = check.is_active();
+        report_error(save_app_settings(&settings));
+    });
+    dangerous_box.append(&preview_check);
+
+    page.append(&dangerous_box);
+
+    content.set_child(Some(&page));
+    scrolled.set_child(Some(&content));
+
+    scrolled
+}
+
+/// Creates an editable row for a command variable, saving on every change
+fn create_command_variable_row(name: &str, value: &str, placeholder: Option<&str>) -> gtk::ListBoxRow {
+    let row = gtk::ListBoxRow::new();
+    let row_box = GtkBox::new(Orientation::Horizontal, 12);
+    row_box.set_margin_top(8);
+    row_box.set_margin_bottom(8);
+    row_box.set_margin_start(12);
+    row_box.set_margin_end(12);
+
+    let name_label = Label::new(Some(&format!("{{{}}}", name)));
+    name_label.set_width_chars(12);
+    name_label.set_xalign(0.0);
+    name_label.add_css_class("monospace");
+
+    let value_entry = Entry::new();
+    value_entry.set_text(value);
+    value_entry.set_hexpand(true);
+    if let Some(placeholder) = placeholder {
+        value_entry.set_placeholder_text(Some(&format!("{} (detected)", placeholder)));
+    }
+
+    let name_owned = name.to_string();
+    value_entry.connect_changed(move |entry| {
+        let mut settings = get_app_settings();
+        let value = entry.text().trim().to_string();
+        if value.is_empty() {
+            settings.command_variables.remove(&name_owned);
+        } else {
+            settings.command_variables.insert(name_owned.clone(), value);
+        }
+        report_error(save_app_settings(&settings));
+    });
+
+    row_box.append(&name_label);
+    row_box.append(&value_entry);
+    row.set_child(Some(&row_box));
+    row
+}
+
+/// Shows a dialog prompting for the file name of a new note tab
+pub fn show_new_note_dialog<F>(parent: &adw::ApplicationWindow, on_create: F)
+where
+    F: Fn(String) + 'static,
+{
+    let dialog = adw::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title("New Note")
+        .default_width(400)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    let name_label = Label::new(Some("File name (saved in the base directory)"));
+    name_label.set_halign(gtk::Align::Start);
+    let name_entry = Entry::new();
+    name_entry.set_placeholder_text(Some("recon.md"));
+    page.append(&name_label);
+    page.append(&name_entry);
+
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::End);
+    button_box.set_margin_top(12);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let create_btn = Button::with_label("Create");
+    create_btn.add_css_class("suggested-action");
+
+    let on_create = Rc::new(on_create);
+    let dialog_clone2 = dialog.clone();
+    let name_entry_clone = name_entry.clone();
+    let on_create_clone = Rc::clone(&on_create);
+    create_btn.connect_clicked(move |_| {
+        let name = name_entry_clone.text().to_string();
+        if name.trim().is_empty() {
+            return;
+        }
+        on_create_clone(name);
+        dialog_clone2.close();
+    });
+
+    let dialog_clone3 = dialog.clone();
+    let on_create_clone2 = Rc::clone(&on_create);
+    name_entry.connect_activate(move |entry| {
+        let name = entry.text().to_string();
+        if name.trim().is_empty() {
+            return;
+        }
+        on_create_clone2(name);
+        dialog_clone3.close();
+    });
+
+    button_box.append(&cancel_btn);
+    button_box.append(&create_btn);
+    page.append(&button_box);
+
+    main_box.append(&page);
+    dialog.set_content(Some(&main_box));
+    dialog.present();
+}
+
+/// Shows the snapshots of a note and calls `on_restore` with the one chosen
+pub fn show_restore_backup_dialog<F>(parent: Option<&gtk::Window>, file_path: &str, on_restore: F)
+where
+    F: Fn(PathBuf) + 'static,
+{
+    let mut builder = adw::Window::builder()
+        .modal(true)
+        .title("Restore from Backup")
+        .default_width(420)
+        .default_height(420);
+    if let Some(parent) = parent {
+        builder = builder.transient_for(parent);
+    }
+    let dialog = builder.build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(12);
+    page.set_margin_bottom(12);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    let hint_label = Label::new(Some(
+        "Replaces the current note with the selected snapshot. The current content is snapshotted first.",
+    ));
+    hint_label.add_css_class("dim-label");
+    hint_label.set_halign(gtk::Align::Start);
+    hint_label.set_wrap(true);
+    page.append(&hint_label);
+
+    let list_box = ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::Single);
+    list_box.add_css_class("boxed-list");
+
+    let backups = crate::ui::editor::list_note_backups(file_path);
+    if backups.is_empty() {
+        let empty_row = gtk::ListBoxRow::new();
+        empty_row.set_selectable(false);
+        empty_row.set_activatable(false);
+        let empty_label = Label::new(Some("No snapshots yet"));
+        empty_label.add_css_class("dim-label");
+        empty_label.set_margin_top(12);
+        empty_label.set_margin_bottom(12);
+        empty_row.set_child(Some(&empty_label));
+        list_box.append(&empty_row);
+    }
+    for backup in &backups {
+        let row = adw::ActionRow::new();
+        let title = crate::ui::editor::note_backup_time(file_path, backup)
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        row.set_title(&title);
+        let size = std::fs::metadata(backup).map(|m| m.len()).unwrap_or(0);
+        row.set_subtitle(&format!("{} bytes", size));
+        row.set_widget_name(&backup.to_string_lossy());
+        list_box.append(&row);
+    }
+
+    let list_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vexpand(true)
+        .child(&list_box)
+        .build();
+    page.append(&list_scroll);
+
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::End);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let restore_btn = Button::with_label("Restore");
+    restore_btn.add_css_class("suggested-action");
+    restore_btn.set_sensitive(false);
+
+    let restore_btn_clone = restore_btn.clone();
+    list_box.connect_row_selected(move |_, row| {
+        restore_btn_clone.set_sensitive(row.is_some());
+    });
+
+    let on_restore = Rc::new(on_restore);
+    let dialog_clone2 = dialog.clone();
+    let list_box_clone = list_box.clone();
+    let on_restore_clone = Rc::clone(&on_restore);
+    restore_btn.connect_clicked(move |_| {
+        if let Some(row) = list_box_clone.selected_row() {
+            on_restore_clone(PathBuf::from(row.widget_name().as_str()));
+            dialog_clone2.close();
+        }
+    });
+
+    let dialog_clone3 = dialog.clone();
+    let on_restore_clone2 = Rc::clone(&on_restore);
+    list_box.connect_row_activated(move |_, row| {
+        on_restore_clone2(PathBuf::from(row.widget_name().as_str()));
+        dialog_clone3.close();
+    });
+
+    button_box.append(&cancel_btn);
+    button_box.append(&restore_btn);
+    page.append(&button_box);
+
+    main_box.append(&page);
+    dialog.set_content(Some(&main_box));
+    dialog.present();
+}
+
+/// Prompts for the size of a markdown table and calls `on_insert` with (rows, columns)
+pub fn show_insert_table_dialog<F>(parent: Option<&gtk::Window>, on_insert: F)
+where
+    F: Fn(usize, usize) + 'static,
+{
+    let mut builder = adw::Window::builder()
+        .modal(true)
+        .title("Insert Table")
+        .default_width(320);
+    if let Some(parent) = parent {
+        builder = builder.transient_for(parent);
+    }
+    let dialog = builder.build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    let rows_box = GtkBox::new(Orientation::Horizontal, 12);
+    let rows_label = Label::new(Some("Rows (excluding header):"));
+    rows_label.set_xalign(0.0);
+    rows_label.set_hexpand(true);
+    let rows_spin = gtk::SpinButton::with_range(1.0, 50.0, 1.0);
+    rows_spin.set_value(3.0);
+    rows_box.append(&rows_label);
+    rows_box.append(&rows_spin);
+    page.append(&rows_box);
+
+    let columns_box = GtkBox::new(Orientation::Horizontal, 12);
+    let columns_label = Label::new(Some("Columns:"));
+    columns_label.set_xalign(0.0);
+    columns_label.set_hexpand(true);
+    let columns_spin = gtk::SpinButton::with_range(1.0, 20.0, 1.0);
+    columns_spin.set_value(3.0);
+    columns_box.append(&columns_label);
+    columns_box.append(&columns_spin);
+    page.append(&columns_box);
+
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::End);
+    button_box.set_margin_top(12);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let insert_btn = Button::with_label("Insert");
+    insert_btn.add_css_class("suggested-action");
+    let dialog_clone2 = dialog.clone();
+    insert_btn.connect_clicked(move |_| {
+        on_insert(rows_spin.value() as usize, columns_spin.value() as usize);
+        dialog_clone2.close();
+    });
+
+    button_box.append(&cancel_btn);
+    button_box.append(&insert_btn);
+    page.append(&button_box);
+
+    main_box.append(&page);
+    dialog.set_content(Some(&main_box));
+    dialog.set_default_widget(Some(&insert_btn));
+    dialog.present();
+}
+
+/// Creates the SSH profiles page
+fn create_ssh_profiles_page() -> ScrolledWindow {
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .vexpand(true)
+        .build();
+
+    let content = adw::Clamp::new();
+    content.set_maximum_size(500);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    let ssh_heading = Label::new(Some("SSH Profiles"));
+    ssh_heading.add_css_class("title-4");
+    ssh_heading.set_halign(gtk::Align::Start);
+    ssh_heading.set_margin_bottom(12);
+    page.append(&ssh_heading);
+
+    let inner_box = GtkBox::new(Orientation::Vertical, 8);
+    inner_box.set_margin_start(12);
+
+    let hint_label = Label::new(Some("Saved connections open from the SSH button in the header bar as a shell tab running ssh."));
+    hint_label.add_css_class("dim-label");
+    hint_label.set_halign(gtk::Align::Start);
+    hint_label.set_wrap(true);
+    inner_box.append(&hint_label);
+
+    let list_box = ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::None);
+    list_box.add_css_class("boxed-list");
+    list_box.set_margin_top(12);
+    populate_ssh_profiles_list(&list_box);
+    inner_box.append(&list_box);
+
+    let add_btn = Button::with_label("Add Profile");
+    add_btn.add_css_class("suggested-action");
+    add_btn.add_css_class("pill");
+    add_btn.set_halign(gtk::Align::Center);
+    add_btn.set_margin_top(12);
+
+    let list_box_clone = list_box.clone();
+    add_btn.connect_clicked(move |btn| {
+        let parent = btn.root().and_downcast::<gtk::Window>();
+        let list_box_ref = list_box_clone.clone();
+        show_ssh_profile_dialog(parent.as_ref(), None, move || {
+            populate_ssh_profiles_list(&list_box_ref);
+        });
+    });
+    inner_box.append(&add_btn);
+
+    page.append(&inner_box);
+    content.set_child(Some(&page));
+    scrolled.set_child(Some(&content));
+
+    scrolled
+}
+
+/// Rebuilds the SSH profile rows from ssh_profiles.yaml
+fn populate_ssh_profiles_list(list_box: &ListBox) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let profiles = load_ssh_profiles();
+    if profiles.is_empty() {
+        let empty_row = gtk::ListBoxRow::new();
+        let empty_label = Label::new(Some("No SSH profiles yet"));
+        empty_label.add_css_class("dim-label");
+        empty_label.set_margin_top(12);
+        empty_label.set_margin_bottom(12);
+        empty_row.set_child(Some(&empty_label));
+        list_box.append(&empty_row);
+        return;
+    }
+
+    for (idx, profile) in profiles.into_iter().enumerate() {
+        let row = adw::ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&profile.name))
+            .subtitle(gtk::glib::markup_escape_text(&profile.command_args().join(" ")))
+            .subtitle_lines(1)
+            .build();
+
+        let edit_btn = Button::builder()
+            .icon_name("document-edit-symbolic")
+            .tooltip_text("Edit")
+            .valign(gtk::Align::Center)
+            .build();
+        edit_btn.add_css_class("flat");
+
+        let list_box_clone = list_box.clone();
+        let profile_clone = profile.clone();
+        edit_btn.connect_clicked(move |btn| {
+            let parent = btn.root().and_downcast::<gtk::Window>();
+            let list_box_ref = list_box_clone.clone();
+            show_ssh_profile_dialog(parent.as_ref(), Some((idx, profile_clone.clone())), move || {
+                populate_ssh_profiles_list(&list_box_ref);
+            });
+        });
+
+        let delete_btn = Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text("Delete")
+            .valign(gtk::Align::Center)
+            .build();
+        delete_btn.add_css_class("flat");
+        delete_btn.add_css_class("error");
+
+        let list_box_clone2 = list_box.clone();
+        delete_btn.connect_clicked(move |_| {
+            match delete_ssh_profile(idx) {
+                Ok(()) => populate_ssh_profiles_list(&list_box_clone2),
+                Err(e) => show_error_toast(&format!("Failed to delete profile: {}", e)),
+            }
+        });
+
+        row.add_suffix(&edit_btn);
+        row.add_suffix(&delete_btn);
+        list_box.append(&row);
+    }
+}
+
+/// Shows a dialog to add an SSH profile, or to edit the profile at the given index
+fn show_ssh_profile_dialog<F>(parent: Option<&gtk::Window>, existing: Option<(usize, SshProfile)>, on_save: F)
+where
+    F: Fn() + 'static,
+{
+    let mut builder = adw::Window::builder()
+        .modal(true)
+        .title(if existing.is_some() { "Edit SSH Profile" } else { "Add SSH Profile" })
+        .default_width(400)
+        .default_height(420);
+    if let Some(parent) = parent {
+        builder = builder.transient_for(parent);
+    }
+    let dialog = builder.build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let content = adw::Clamp::new();
+    content.set_maximum_size(350);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    let (index, profile) = match existing {
+        Some((index, profile)) => (Some(index), profile),
+        None => (None, SshProfile {
+            name: String::new(),
+            host: String::new(),
+            user: String::new(),
+            port: 22,
+            identity_file: String::new(),
+        }),
+    };
+
+    let add_field = |label_text: &str, placeholder: &str, value: &str| -> Entry {
+        let field_box = GtkBox::new(Orientation::Vertical, 4);
+        let label = Label::new(Some(label_text));
+        label.set_halign(gtk::Align::Start);
+        let entry = Entry::new();
+        entry.set_placeholder_text(Some(placeholder));
+        entry.set_text(value);
+        field_box.append(&label);
+        field_box.append(&entry);
+        page.append(&field_box);
+        entry
+    };
+
+    let name_entry = add_field("Name", "Jump box", &profile.name);
+    let host_entry = add_field("Host", "10.10.10.5", &profile.host);
+    let user_entry = add_field("User (optional)", "root", &profile.user);
+
+    let port_box = GtkBox::new(Orientation::Horizontal, 12);
+    let port_label = Label::new(Some("Port"));
+    port_label.set_hexpand(true);
+    port_label.set_halign(gtk::Align::Start);
+    let port_spin = gtk::SpinButton::with_range(1.0, 65535.0, 1.0);
+    port_spin.set_value(profile.port as f64);
+    port_spin.set_digits(0);
+    port_box.append(&port_label);
+    port_box.append(&port_spin);
+    page.append(&port_box);
+
+    let identity_entry = add_field("Identity File (optional)", "~/.ssh/id_ed25519", &profile.identity_file);
+
+    // Buttons
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::End);
+    button_box.set_margin_top(12);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let save_btn = Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    let dialog_clone2 = dialog.clone();
+    save_btn.connect_clicked(move |_| {
+        let name = name_entry.text().trim().to_string();
+        let host = host_entry.text().trim().to_string();
+        name_entry.remove_css_class("error");
+        host_entry.remove_css_class("error");
+        if name.is_empty() || host.is_empty() {
+            if name.is_empty() {
+                name_entry.add_css_class("error");
+            }
+            if host.is_empty() {
+                host_entry.add_css_class("error");
+            }
+            return;
+        }
+
+        let profile = SshProfile {
+            name,
+            host,
+            user: user_entry.text().trim().to_string(),
+            port: port_spin.value() as u16,
+            identity_file: identity_entry.text().trim().to_string(),
+        };
+        let result = match index {
+            Some(index) => update_ssh_profile(index, profile),
+            None => add_ssh_profile(profile),
+        };
+        match result {
+            Ok(()) => {
+                on_save();
+                dialog_clone2.close();
+            }
+            Err(e) => show_error_toast(&format!("Failed to save SSH profile: {}", e)),
+        }
+    });
+
+    button_box.append(&cancel_btn);
+    button_box.append(&save_btn);
+    page.append(&button_box);
+
+    content.set_child(Some(&page));
+    main_box.append(&content);
+    dialog.set_content(Some(&main_box));
+    dialog.present();
+}
+
+/// Shows a dialog to add a finding, or to edit the finding at the given index
+pub fn show_finding_dialog<F>(parent: Option<&gtk::Window>, existing: Option<(usize, Finding)>, on_save: F)
+where
+    F: Fn() + 'static,
+{
+    let mut builder = adw::Window::builder()
+        .modal(true)
+        .title(if existing.is_some() { "Edit Finding" } else { "Add Finding" })
+        .default_width(450)
+        .default_height(460);
+    if let Some(parent) = parent {
+        builder = builder.transient_for(parent);
+    }
+    let dialog = builder.build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let content = adw::Clamp::new();
+    content.set_maximum_size(400);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    // Title entry
+    let title_box = GtkBox::new(Orientation::Vertical, 4);
+    let title_label = Label::new(Some("Title"));
+    title_label.set_halign(gtk::Align::Start);
+    let title_entry = Entry::new();
+    title_entry.set_placeholder_text(Some("SQL injection in login form"));
+    title_box.append(&title_label);
+    title_box.append(&title_entry);
+    page.append(&title_box);
+
+    // Severity and status selectors
+    let choice_box = GtkBox::new(Orientation::Horizontal, 12);
+
+    let severity_box = GtkBox::new(Orientation::Vertical, 4);
+    severity_box.set_hexpand(true);
+    let severity_label = Label::new(Some("Severity"));
+    severity_label.set_halign(gtk::Align::Start);
+    let severity_combo = ComboBoxText::new();
+    for severity in Severity::ALL {
+        severity_combo.append_text(severity.label());
+    }
+    severity_box.append(&severity_label);
+    severity_box.append(&severity_combo);
+
+    let status_box = GtkBox::new(Orientation::Vertical, 4);
+    status_box.set_hexpand(true);
+    let status_label = Label::new(Some("Status"));
+    status_label.set_halign(gtk::Align::Start);
+    let status_combo = ComboBoxText::new();
+    for status in FindingStatus::ALL {
+        status_combo.append_text(status.label());
+    }
+    status_box.append(&status_label);
+    status_box.append(&status_combo);
+
+    choice_box.append(&severity_box);
+    choice_box.append(&status_box);
+    page.append(&choice_box);
+
+    // Description
+    let desc_box = GtkBox::new(Orientation::Vertical, 4);
+    let desc_label = Label::new(Some("Description"));
+    desc_label.set_halign(gtk::Align::Start);
+    let desc_view = gtk::TextView::builder()
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .left_margin(6)
+        .right_margin(6)
+        .top_margin(6)
+        .bottom_margin(6)
+        .build();
+    let desc_scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(140)
+        .child(&desc_view)
+        .build();
+    desc_scrolled.add_css_class("card");
+    desc_box.append(&desc_label);
+    desc_box.append(&desc_scrolled);
+    page.append(&desc_box);
+
+    let (index, finding) = match existing {
+        Some((index, finding)) => (Some(index), finding),
+        None => (None, Finding {
+            title: String::new(),
+            severity: Severity::Medium,
+            status: FindingStatus::Open,
+            description: String::new(),
+        }),
+    };
+    title_entry.set_text(&finding.title);
+    severity_combo.set_active(Severity::ALL.iter().position(|s| *s == finding.severity).map(|i| i as u32));
+    status_combo.set_active(FindingStatus::ALL.iter().position(|s| *s == finding.status).map(|i| i as u32));
+    desc_view.buffer().set_text(&finding.description);
+
+    // Buttons
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk::Align::End);
+    button_box.set_margin_top(12);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+    });
+
+    let save_btn = Button::with_label("Save");
+    save_btn.add_css_class("suggested-action");
+    let dialog_clone2 = dialog.clone();
+    save_btn.connect_clicked(move |_| {
+        let title = title_entry.text().trim().to_string();
+        if title.is_empty() {
+            title_entry.add_css_class("error");
+            return;
+        }
+
+        let buffer = desc_view.buffer();
+        let finding = Finding {
+            title,
+            severity: severity_combo
+                .active()
+                .and_then(|i| Severity::ALL.get(i as usize).copied())
+                .unwrap_or(Severity::Medium),
+            status: status_combo
+                .active()
+                .and_then(|i| FindingStatus::ALL.get(i as usize).copied())
+                .unwrap_or(FindingStatus::Open),
+            description: buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).trim_end().to_string(),
+        };
+
+        let result = match index {
+            Some(index) => update_finding(index, finding),
+            None => add_finding(finding),
+        };
+        match result {
+            Ok(()) => {
+                on_save();
+                dialog_clone2.close();
+            }
+            Err(e) => show_error_toast(&format!("Failed to save finding: {}", e)),
+        }
+    });
+
+    button_box.append(&cancel_btn);
+    button_box.append(&save_btn);
+    page.append(&button_box);
+
+    content.set_child(Some(&page));
+    main_box.append(&content);
+    dialog.set_content(Some(&main_box));
+    dialog.present();
+}
+
+/// Creates the reference URL entry; its icon opens the link in the browser
+fn create_reference_entry(current: &str) -> Entry {
+    let entry = Entry::new();
+    entry.set_text(current);
+    entry.set_placeholder_text(Some("https://book.hacktricks.xyz/..."));
+    entry.set_secondary_icon_name(Some("help-browser-symbolic"));
+    entry.set_secondary_icon_tooltip_text(Some("Open reference"));
+    entry.connect_icon_press(|entry, _| {
+        let reference = entry.text();
+        if !reference.trim().is_empty() {
+            crate::ui::terminal::open_command_reference(reference.trim());
+        }
+    });
+    entry
+}
+
+/// Shows dialog to add a new custom command
+fn show_add_command_dialog<F>(parent: &adw::ApplicationWindow, on_save: F)
+where
+    F: Fn() + 'static,
+{
+    let dialog = adw::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title("Add Custom Command")
+        .default_width(450)
+        .default_height(400)
+        .build();
+
+    let main_box = GtkBox::new(Orientation::Vertical, 0);
+
+    let header = adw::HeaderBar::new();
+    main_box.append(&header);
+
+    let content = adw::Clamp::new();
+    content.set_maximum_size(400);
+
+    let page = GtkBox::new(Orientation::Vertical, 12);
+    page.set_margin_top(24);
+    page.set_margin_bottom(24);
+    page.set_margin_start(12);
+    page.set_margin_end(12);
+
+    // Name entry
+    let name_box = GtkBox::new(Orientation::Vertical, 4);
+    let name_label = Label::new(Some("Name"));
     name_label.set_halign(gtk::Align::Start);
     let name_entry = Entry::new();
     name_entry.set_placeholder_text(Some("Command name"));
@@ -1300,6 +2971,8 @@ where
     command_entry.set_placeholder_text(Some("nmap -sV {target}"));
     command_box.append(&command_label);
     command_box.append(&command_entry);
+    let placeholder_warning = create_placeholder_warning();
+    command_box.append(&placeholder_warning);
     page.append(&command_box);
 
     // Description entry
@@ -1312,16 +2985,42 @@ where
     desc_box.append(&desc_entry);
     page.append(&desc_box);
 
+    // Reference link entry
+    let ref_box = GtkBox::new(Orientation::Vertical, 4);
+    let ref_label = Label::new(Some("Reference (optional)"));
+    ref_label.set_halign(gtk::Align::Start);
+    let ref_entry = create_reference_entry("");
+    ref_box.append(&ref_label);
+    ref_box.append(&ref_entry);
+    page.append(&ref_box);
+
     // Category entry
     let cat_box = GtkBox::new(Orientation::Vertical, 4);
     let cat_label = Label::new(Some("Category"));
     cat_label.set_halign(gtk::Align::Start);
-    let cat_entry = Entry::new();
-    cat_entry.set_text("Custom");
+    let cat_combo = create_category_combo("Custom");
     cat_box.append(&cat_label);
-    cat_box.append(&cat_entry);
+    cat_box.append(&cat_combo);
     page.append(&cat_box);
 
+    // Target OS selector
+    let os_box = GtkBox::new(Orientation::Vertical, 4);
+    let os_label = Label::new(Some("Target OS"));
+    os_label.set_halign(gtk::Align::Start);
+    let os_combo = ComboBoxText::new();
+    for os in CommandOs::ALL {
+        os_combo.append_text(os.label());
+    }
+    os_combo.set_active(CommandOs::ALL.iter().position(|os| *os == CommandOs::Any).map(|i| i as u32));
+    os_box.append(&os_label);
+    os_box.append(&os_combo);
+    page.append(&os_box);
+
+    // Auto-run toggle
+    let auto_run_check = CheckButton::with_label("Run immediately (press Enter after inserting)");
+    auto_run_check.set_active(false);
+    page.append(&auto_run_check);
+
     // Tip
     let tip_label = Label::new(Some("💡 Use {target} as a placeholder for target selection"));
     tip_label.add_css_class("dim-label");
@@ -1342,19 +3041,26 @@ where
 
     let save_btn = Button::with_label("Save");
     save_btn.add_css_class("suggested-action");
+    reset_placeholder_warning_on_edit(&command_entry, &placeholder_warning, &save_btn);
     let dialog_clone2 = dialog.clone();
     let name_entry_clone = name_entry.clone();
     let command_entry_clone = command_entry.clone();
     let desc_entry_clone = desc_entry.clone();
-    let cat_entry_clone = cat_entry.clone();
-    save_btn.connect_clicked(move |_| {
+    let ref_entry_clone = ref_entry.clone();
+    let os_combo_clone = os_combo.clone();
+    let cat_combo_clone = cat_combo.clone();
+    let auto_run_check_clone = auto_run_check.clone();
+    save_btn.connect_clicked(move |btn| {
         let name = name_entry_clone.text().to_string();
         let command = command_entry_clone.text().to_string();
         let description = desc_entry_clone.text().to_string();
-        let category = cat_entry_clone.text().to_string();
+        let category = selected_category(&cat_combo_clone);
 
         if name.is_empty() || command.is_empty() {
-            log::warn!("Name and command are required");
+            show_error_toast("Name and command are required");
+            return;
+        }
+        if !check_command_placeholders(&command, &placeholder_warning, btn) {
             return;
         }
 
@@ -1363,11 +3069,20 @@ where
             command,
             description: if description.is_empty() { "Custom command".to_string() } else { description },
             category: if category.is_empty() { "Custom".to_string() } else { category },
+            autorun: auto_run_check_clone.is_active(),
+            reference: ref_entry_clone.text().trim().to_string(),
+            os: os_combo_clone
+                .active()
+                .and_then(|i| CommandOs::ALL.get(i as usize).copied())
+                .unwrap_or_default(),
         };
 
-        if save_custom_command(cmd_template).is_ok() {
-            on_save();
-            dialog_clone2.close();
+        match save_custom_command(cmd_template) {
+            Ok(()) => {
+                on_save();
+                dialog_clone2.close();
+            }
+            Err(e) => show_error_toast(&format!("Failed to save command: {}", e)),
         }
     });
 
@@ -1381,6 +3096,82 @@ where
     dialog.present();
 }
 
+/// Creates an editable category picker listing the categories of built-in and custom commands
+fn create_category_combo(current: &str) -> ComboBoxText {
+    let combo = ComboBoxText::with_entry();
+    for category in command_categories(&load_command_templates()) {
+        combo.append_text(&category);
+    }
+    if let Some(entry) = combo.child().and_downcast::<Entry>() {
+        entry.set_placeholder_text(Some("Custom"));
+        entry.set_text(current);
+    }
+    combo
+}
+
+/// Reads the chosen category, reusing the existing spelling when it only differs in case
+fn selected_category(combo: &ComboBoxText) -> String {
+    let typed = combo.active_text().map(|text| text.trim().to_string()).unwrap_or_default();
+    let key = category_key(&typed);
+    command_categories(&load_command_templates())
+        .into_iter()
+        .find(|category| category_key(category) == key)
+        .unwrap_or(typed)
+}
+
+/// Creates the hidden warning shown under a command entry for unrecognized placeholders
+fn create_placeholder_warning() -> Label {
+    let warning_label = Label::new(None);
+    warning_label.add_css_class("warning");
+    warning_label.add_css_class("caption");
+    warning_label.set_halign(gtk::Align::Start);
+    warning_label.set_xalign(0.0);
+    warning_label.set_wrap(true);
+    warning_label.set_visible(false);
+    warning_label
+}
+
+/// Hides the placeholder warning when the command is edited so it is checked again on save
+fn reset_placeholder_warning_on_edit(command_entry: &Entry, warning_label: &Label, save_btn: &Button) {
+    let warning_clone = warning_label.clone();
+    let save_btn_clone = save_btn.clone();
+    command_entry.connect_changed(move |_| {
+        if warning_clone.is_visible() {
+            warning_clone.set_visible(false);
+            save_btn_clone.set_label("Save");
+        }
+    });
+}
+
+/// Warns once about placeholders that are neither built in nor command variables
+///
+/// Returns true when the command can be saved: either every placeholder is known,
+/// or the warning is already showing and the user pressed "Save Anyway".
+fn check_command_placeholders(command: &str, warning_label: &Label, save_btn: &Button) -> bool {
+    let unknown = unknown_placeholders(command);
+    if unknown.is_empty() || warning_label.is_visible() {
+        return true;
+    }
+
+    let names = unknown
+        .iter()
+        .map(|name| format!("{{{}}}", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let known = known_placeholders()
+        .iter()
+        .map(|name| format!("{{{}}}", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    warning_label.set_text(&format!(
+        "⚠️ Unknown placeholder {} will be prompted for every time. Known placeholders: {}. Check for typos or save anyway.",
+        names, known
+    ));
+    warning_label.set_visible(true);
+    save_btn.set_label("Save Anyway");
+    false
+}
+
 /// Shows dialog to edit an existing custom command
 fn show_edit_command_dialog<F>(parent: &adw::ApplicationWindow, index: usize, cmd: CommandTemplate, on_save: F)
 where
@@ -1426,6 +3217,8 @@ where
     command_entry.set_text(&cmd.command);
     command_box.append(&command_label);
     command_box.append(&command_entry);
+    let placeholder_warning = create_placeholder_warning();
+    command_box.append(&placeholder_warning);
     page.append(&command_box);
 
     // Description entry
@@ -1438,16 +3231,42 @@ where
     desc_box.append(&desc_entry);
     page.append(&desc_box);
 
+    // Reference link entry
+    let ref_box = GtkBox::new(Orientation::Vertical, 4);
+    let ref_label = Label::new(Some("Reference (optional)"));
+    ref_label.set_halign(gtk::Align::Start);
+    let ref_entry = create_reference_entry(&cmd.reference);
+    ref_box.append(&ref_label);
+    ref_box.append(&ref_entry);
+    page.append(&ref_box);
+
     // Category entry
     let cat_box = GtkBox::new(Orientation::Vertical, 4);
     let cat_label = Label::new(Some("Category"));
     cat_label.set_halign(gtk::Align::Start);
-    let cat_entry = Entry::new();
-    cat_entry.set_text(&cmd.category);
+    let cat_combo = create_category_combo(&cmd.category);
     cat_box.append(&cat_label);
-    cat_box.append(&cat_entry);
+    cat_box.append(&cat_combo);
     page.append(&cat_box);
 
+    // Target OS selector
+    let os_box = GtkBox::new(Orientation::Vertical, 4);
+    let os_label = Label::new(Some("Target OS"));
+    os_label.set_halign(gtk::Align::Start);
+    let os_combo = ComboBoxText::new();
+    for os in CommandOs::ALL {
+        os_combo.append_text(os.label());
+    }
+    os_combo.set_active(CommandOs::ALL.iter().position(|os| *os == cmd.os).map(|i| i as u32));
+    os_box.append(&os_label);
+    os_box.append(&os_combo);
+    page.append(&os_box);
+
+    // Auto-run toggle
+    let auto_run_check = CheckButton::with_label("Run immediately (press Enter after inserting)");
+    auto_run_check.set_active(cmd.autorun);
+    page.append(&auto_run_check);
+
     // Buttons
     let button_box = GtkBox::new(Orientation::Horizontal, 12);
     button_box.set_halign(gtk::Align::End);
@@ -1461,19 +3280,26 @@ where
 
     let save_btn = Button::with_label("Save");
     save_btn.add_css_class("suggested-action");
+    reset_placeholder_warning_on_edit(&command_entry, &placeholder_warning, &save_btn);
     let dialog_clone2 = dialog.clone();
     let name_entry_clone = name_entry.clone();
     let command_entry_clone = command_entry.clone();
     let desc_entry_clone = desc_entry.clone();
-    let cat_entry_clone = cat_entry.clone();
-    save_btn.connect_clicked(move |_| {
+    let ref_entry_clone = ref_entry.clone();
+    let os_combo_clone = os_combo.clone();
+    let cat_combo_clone = cat_combo.clone();
+    let auto_run_check_clone = auto_run_check.clone();
+    save_btn.connect_clicked(move |btn| {
         let name = name_entry_clone.text().to_string();
         let command = command_entry_clone.text().to_string();
         let description = desc_entry_clone.text().to_string();
-        let category = cat_entry_clone.text().to_string();
+        let category = selected_category(&cat_combo_clone);
 
         if name.is_empty() || command.is_empty() {
-            log::warn!("Name and command are required");
+            show_error_toast("Name and command are required");
+            return;
+        }
+        if !check_command_placeholders(&command, &placeholder_warning, btn) {
             return;
         }
 
@@ -1482,11 +3308,20 @@ where
             command,
             description: if description.is_empty() { "Custom command".to_string() } else { description },
             category: if category.is_empty() { "Custom".to_string() } else { category },
+            autorun: auto_run_check_clone.is_active(),
+            reference: ref_entry_clone.text().trim().to_string(),
+            os: os_combo_clone
+                .active()
+                .and_then(|i| CommandOs::ALL.get(i as usize).copied())
+                .unwrap_or_default(),
         };
 
-        if update_custom_command(index, cmd_template).is_ok() {
-            on_save();
-            dialog_clone2.close();
+        match update_custom_command(index, cmd_template) {
+            Ok(()) => {
+                on_save();
+                dialog_clone2.close();
+            }
+            Err(e) => show_error_toast(&format!("Failed to save command: {}", e)),
         }
     });
 
@@ -1902,7 +3737,7 @@ fn create_containers_settings_page() -> ScrolledWindow {
                 });
             }
             Err(e) => {
-                log::error!("Failed to save container settings: {}", e);
+                show_error_toast(&format!("Failed to save container settings: {}", e));
                 btn.set_label("Save Failed!");
                 let btn_clone = btn.clone();
                 gtk::glib::timeout_add_local_once(std::time::Duration::from_millis(1500), move || {
@@ -2013,7 +3848,7 @@ fn create_desktop_settings_page() -> ScrolledWindow {
                 });
             }
             Err(e) => {
-                log::error!("Failed to save desktop settings: {}", e);
+                show_error_toast(&format!("Failed to save desktop settings: {}", e));
                 btn.set_label("Save Failed!");
                 let btn_clone = btn.clone();
                 gtk::glib::timeout_add_local_once(std::time::Duration::from_millis(1500), move || {
@@ -7,22 +7,44 @@ use gtk4::{self as gtk, Box as GtkBox, Button, Label, Orientation, ScrolledWindo
 use gtk4::glib;
 use libadwaita::{self as adw, prelude::*};
 use std::cell::RefCell;
+use std::cell::Cell;
 use std::rc::Rc;
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use regex::{Regex, RegexBuilder};
 
 use crate::config::{
     get_file_path, get_app_settings, save_app_settings, get_keyboard_shortcuts,
     get_text_zoom_scale, set_text_zoom_scale_raw, load_targets, zoom, is_notes_wrap_text_enabled,
+    is_log_dedup_enabled, get_redaction_patterns, get_notes_backup_interval, get_autosave_debounce_ms,
+    atomic_write, get_host_section_template, fill_host_section, is_targets_autosave_enabled,
+    is_editor_line_numbers_enabled, is_editor_wrap_text_enabled, log_archives,
 };
 
 use crate::ui::terminal::reload_targets_in_shells;
+use crate::ui::preview::{create_preview_view, render_markdown_preview, refresh_preview_colors,
+                         unregister_previews};
+use crate::ui::spellcheck::{add_spell_check, check_spelling, MISSPELLED_TAG};
+use crate::ui::window::{report_error, show_no_targets_toast};
 
 // Track all text views for global zoom
 thread_local! {
     static TEXT_VIEWS: RefCell<Vec<TextView>> = RefCell::new(Vec::new());
-    static NOTES_VIEWS: RefCell<Vec<TextView>> = RefCell::new(Vec::new());
+    static NOTES_VIEWS: RefCell<Vec<NotesView>> = RefCell::new(Vec::new());
+    /// Files with a background write running, and the newest text waiting to follow it
+    static WRITES_IN_FLIGHT: RefCell<HashMap<PathBuf, Option<(String, Label)>>> = RefCell::new(HashMap::new());
+    /// Modification time and size of each log file when its viewer last loaded it
+    static LOG_STAMPS: RefCell<HashMap<String, Option<(Option<std::time::SystemTime>, u64)>>> = RefCell::new(HashMap::new());
+    /// Editor toolbar toggles kept in step across open editors, keyed by the setting they control
+    static EDITOR_TOGGLES: RefCell<Vec<(&'static str, glib::WeakRef<gtk::ToggleButton>)>> = RefCell::new(Vec::new());
+    /// Redaction patterns and their compiled regexes, rebuilt when the settings change
+    static REDACTION_REGEXES: RefCell<Option<(Vec<String>, Vec<Regex>)>> = const { RefCell::new(None) };
 }
 
+/// Space between the line numbers and the gutter edges, in pixels
+const GUTTER_PADDING: f64 = 6.0;
+
 /// Sets the text zoom scale and updates all text views
 pub fn set_text_zoom_scale(scale: f64) {
     let clamped = scale.clamp(zoom::MIN_SCALE, zoom::MAX_SCALE);
@@ -39,7 +61,7 @@ pub fn set_text_zoom_scale(scale: f64) {
     // Save to settings
     let mut settings = get_app_settings();
     settings.text_zoom_scale = Some(clamped);
-    let _ = save_app_settings(&settings);
+    report_error(save_app_settings(&settings));
 }
 
 /// Apply zoom scale to a specific text view using CSS
@@ -55,10 +77,25 @@ fn apply_text_zoom_to_view(text_view: &TextView, scale: f64) {
     style_context.add_provider(&css_provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
 }
 
-/// Tracks a notes view for wrap mode updates
-pub fn track_notes_view(text_view: &TextView) {
+/// A notes view, the file it edits and the text it last read from or wrote to that file
+struct NotesView {
+    view: TextView,
+    path: PathBuf,
+    saved_text: String,
+}
+
+fn buffer_text(buffer: &gtk::TextBuffer) -> String {
+    buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string()
+}
+
+/// Tracks a notes view for wrap mode updates and reloads; call it once the file is loaded
+pub fn track_notes_view(text_view: &TextView, file_path: &Path) {
     NOTES_VIEWS.with(|views| {
-        views.borrow_mut().push(text_view.clone());
+        views.borrow_mut().push(NotesView {
+            view: text_view.clone(),
+            path: file_path.to_path_buf(),
+            saved_text: buffer_text(&text_view.buffer()),
+        });
     });
 
     // Apply current wrap mode setting
@@ -68,6 +105,191 @@ pub fn track_notes_view(text_view: &TextView) {
         gtk::WrapMode::None
     };
     text_view.set_wrap_mode(wrap_mode);
+
+    add_incremental_highlighting(text_view);
+    add_task_list_toggle(text_view);
+    add_spell_check(text_view);
+    add_link_handling(text_view);
+}
+
+/// Finds a task-list checkbox (`- [ ]` / `- [x]`) in a line.
+///
+/// Returns the byte offset of the opening bracket and whether the box is checked.
+pub(crate) fn find_task_checkbox(line: &str) -> Option<(usize, bool)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let rest = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))?;
+    let bytes = rest.as_bytes();
+    if bytes.len() < 3 || bytes[0] != b'[' || bytes[2] != b']' {
+        return None;
+    }
+    match bytes[1] {
+        b' ' => Some((indent + 2, false)),
+        b'x' | b'X' => Some((indent + 2, true)),
+        _ => None,
+    }
+}
+
+/// Checks if a line is a markdown table delimiter row such as `|---|:---:|`
+pub(crate) fn is_table_separator(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with('|')
+        && trimmed.contains('-')
+        && trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+/// Marks which lines belong to a pipe table block: a header row, a separator row, then body rows
+fn table_block_lines(lines: &[&str]) -> Vec<bool> {
+    let is_row = |line: &str| line.trim_start().starts_with('|');
+    let mut in_table = vec![false; lines.len()];
+    let mut i = 0;
+    while i + 1 < lines.len() {
+        if is_row(lines[i]) && !is_table_separator(lines[i]) && is_table_separator(lines[i + 1]) {
+            in_table[i] = true;
+            in_table[i + 1] = true;
+            i += 2;
+            while i < lines.len() && is_row(lines[i]) {
+                in_table[i] = true;
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    in_table
+}
+
+/// Toggles task-list checkboxes when they are clicked in a notes view
+fn add_task_list_toggle(text_view: &TextView) {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_button(1);
+    let text_view_clone = text_view.clone();
+    gesture.connect_released(move |_, n_press, x, y| {
+        if n_press != 1 {
+            return;
+        }
+        let (bx, by) = text_view_clone.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+        let Some(click_iter) = text_view_clone.iter_at_location(bx, by) else {
+            return;
+        };
+
+        let buffer = text_view_clone.buffer();
+        let mut line_start = click_iter.clone();
+        line_start.set_line_offset(0);
+        let mut line_end = line_start.clone();
+        if !line_end.ends_line() {
+            line_end.forward_to_line_end();
+        }
+        let line = buffer.text(&line_start, &line_end, false);
+
+        if let Some((bracket_pos, checked)) = find_task_checkbox(&line) {
+            let bracket_offset = line[..bracket_pos].chars().count() as i32;
+            let click_offset = click_iter.line_offset();
+            if click_offset < bracket_offset || click_offset > bracket_offset + 2 {
+                return;
+            }
+
+            let mut mark_start = line_start.clone();
+            mark_start.forward_chars(bracket_offset + 1);
+            let mut mark_end = mark_start.clone();
+            mark_end.forward_char();
+
+            buffer.begin_user_action();
+            buffer.delete(&mut mark_start, &mut mark_end);
+            buffer.insert(&mut mark_start, if checked { " " } else { "x" });
+            buffer.end_user_action();
+        }
+    });
+    text_view.add_controller(gesture);
+}
+
+/// Finds the URL of a `[text](url)` link covering a character offset in a line
+fn link_url_at(line: &str, char_offset: usize) -> Option<String> {
+    let byte_offset = line.char_indices().nth(char_offset).map(|(i, _)| i)?;
+    let mut search = 0;
+    while let Some(open) = line[search..].find('[') {
+        let start = search + open;
+        if byte_offset < start {
+            return None;
+        }
+        let close = start + line[start..].find("](")?;
+        let end = close + 2 + line[close + 2..].find(')')?;
+        if byte_offset <= end {
+            let url = line[close + 2..end].trim();
+            return (!url.is_empty()).then(|| url.to_string());
+        }
+        search = end + 1;
+    }
+    None
+}
+
+/// Turns a link target into a URI, treating bare hosts as https and absolute paths as files
+fn link_uri(url: &str) -> String {
+    if url.contains("://") || url.starts_with("mailto:") {
+        url.to_string()
+    } else if url.starts_with('/') {
+        format!("file://{}", url)
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+/// Returns the link URL under widget coordinates in a notes view
+fn link_url_at_location(text_view: &TextView, x: f64, y: f64) -> Option<String> {
+    let (bx, by) = text_view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+    let iter = text_view.iter_at_location(bx, by)?;
+    let buffer = text_view.buffer();
+    let link_tag = buffer.tag_table().lookup("link")?;
+    if !iter.has_tag(&link_tag) {
+        return None;
+    }
+
+    let mut line_start = iter.clone();
+    line_start.set_line_offset(0);
+    let mut line_end = line_start.clone();
+    if !line_end.ends_line() {
+        line_end.forward_to_line_end();
+    }
+    let line = buffer.text(&line_start, &line_end, false);
+    link_url_at(&line, iter.line_offset() as usize)
+}
+
+/// Opens markdown links on Ctrl+click and shows a pointer cursor over them
+fn add_link_handling(text_view: &TextView) {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_button(1);
+    let text_view_clone = text_view.clone();
+    gesture.connect_released(move |gesture, n_press, x, y| {
+        if n_press != 1 || !gesture.current_event_state().contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+            return;
+        }
+        // Leave drag selections alone
+        if text_view_clone.buffer().has_selection() {
+            return;
+        }
+        if let Some(url) = link_url_at_location(&text_view_clone, x, y) {
+            let uri = link_uri(&url);
+            if let Err(e) = gtk::gio::AppInfo::launch_default_for_uri(&uri, None::<&gtk::gio::AppLaunchContext>) {
+                log::error!("Failed to open link {}: {}", uri, e);
+            }
+        }
+    });
+    text_view.add_controller(gesture);
+
+    let motion = gtk::EventControllerMotion::new();
+    let text_view_clone2 = text_view.clone();
+    motion.connect_motion(move |_, x, y| {
+        let cursor = if link_url_at_location(&text_view_clone2, x, y).is_some() {
+            "pointer"
+        } else {
+            "text"
+        };
+        text_view_clone2.set_cursor_from_name(Some(cursor));
+    });
+    text_view.add_controller(motion);
 }
 
 /// Sets the notes wrap mode and updates all notes views
@@ -80,15 +302,247 @@ pub fn set_notes_wrap_mode(enabled: bool) {
 
     NOTES_VIEWS.with(|views| {
         let views = views.borrow();
-        for view in views.iter() {
-            view.set_wrap_mode(wrap_mode);
+        for notes in views.iter() {
+            notes.view.set_wrap_mode(wrap_mode);
         }
     });
 
     // Save to settings
     let mut settings = get_app_settings();
     settings.notes_wrap_text = enabled;
-    let _ = save_app_settings(&settings);
+    report_error(save_app_settings(&settings));
+
+    sync_editor_toggles("notes_wrap", enabled);
+}
+
+/// Sets every open editor toggle for a setting to the same state
+fn sync_editor_toggles(key: &str, active: bool) {
+    let toggles: Vec<gtk::ToggleButton> = EDITOR_TOGGLES.with(|toggles| {
+        let mut toggles = toggles.borrow_mut();
+        toggles.retain(|(_, toggle)| toggle.upgrade().is_some());
+        toggles
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .filter_map(|(_, toggle)| toggle.upgrade())
+            .collect()
+    });
+    // set_active only emits toggled on a change, so this doesn't recurse
+    for toggle in toggles {
+        toggle.set_active(active);
+    }
+}
+
+/// Creates a flat toolbar toggle that follows the same setting in other open editors
+fn create_editor_toggle(key: &'static str, icon_name: &str, tooltip: &str) -> gtk::ToggleButton {
+    let toggle = gtk::ToggleButton::builder()
+        .icon_name(icon_name)
+        .tooltip_text(tooltip)
+        .build();
+    toggle.add_css_class("flat");
+    EDITOR_TOGGLES.with(|toggles| {
+        toggles.borrow_mut().push((key, toggle.downgrade()));
+    });
+    toggle
+}
+
+/// Draws the numbers of the lines currently visible in a text view into its gutter
+fn draw_line_numbers(text_view: &TextView, area: &gtk::DrawingArea, cr: &gtk::cairo::Context, width: i32) {
+    // Size the numbers from the editor font so they follow text zoom
+    let layout = text_view.create_pango_layout(Some("0"));
+    let (_, digit_height) = layout.pixel_size();
+    let baseline = layout.baseline() as f64 / gtk::pango::SCALE as f64;
+
+    let color = area.style_context().color();
+    cr.set_source_rgba(color.red() as f64, color.green() as f64, color.blue() as f64, color.alpha() as f64 * 0.55);
+    cr.select_font_face("monospace", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Normal);
+    cr.set_font_size(digit_height as f64 * 0.8);
+
+    let visible = text_view.visible_rect();
+    let (mut iter, _) = text_view.line_at_y(visible.y());
+    loop {
+        let (y, _) = text_view.line_yrange(&iter);
+        if y > visible.y() + visible.height() {
+            break;
+        }
+        let (_, window_y) = text_view.buffer_to_window_coords(gtk::TextWindowType::Left, 0, y);
+        let number = (iter.line() + 1).to_string();
+        if let Ok(extents) = cr.text_extents(&number) {
+            cr.move_to(width as f64 - GUTTER_PADDING - extents.x_advance(), window_y as f64 + baseline);
+            let _ = cr.show_text(&number);
+        }
+        if !iter.forward_line() {
+            break;
+        }
+    }
+}
+
+/// Creates a line number gutter for a text view; attach it with `set_gutter`.
+///
+/// Must be called once the view is inside its ScrolledWindow, so it can follow scrolling.
+fn create_line_number_gutter(text_view: &TextView) -> gtk::DrawingArea {
+    let gutter = gtk::DrawingArea::new();
+    let text_view_weak = text_view.downgrade();
+    gutter.set_draw_func(move |area, cr, width, _| {
+        if let Some(text_view) = text_view_weak.upgrade() {
+            draw_line_numbers(&text_view, area, cr, width);
+        }
+    });
+
+    // Wide enough for the largest line number, and at least two digits
+    let update = {
+        let gutter_weak = gutter.downgrade();
+        let text_view_weak = text_view.downgrade();
+        move || {
+            let (Some(gutter), Some(text_view)) = (gutter_weak.upgrade(), text_view_weak.upgrade()) else {
+                return;
+            };
+            let digits = text_view.buffer().line_count().to_string().len().max(2);
+            let (digit_width, _) = text_view.create_pango_layout(Some("0")).pixel_size();
+            gutter.set_content_width(digit_width * digits as i32 + 2 * GUTTER_PADDING as i32);
+            gutter.queue_draw();
+        }
+    };
+    update();
+
+    let update = Rc::new(update);
+    let update_clone = Rc::clone(&update);
+    text_view.buffer().connect_changed(move |_| update_clone());
+    if let Some(adjustment) = text_view.vadjustment() {
+        // Scrolling moves the numbers; layout changes such as zoom or wrapping change the range
+        let gutter_weak = gutter.downgrade();
+        adjustment.connect_value_changed(move |_| {
+            if let Some(gutter) = gutter_weak.upgrade() {
+                gutter.queue_draw();
+            }
+        });
+        let update_clone2 = Rc::clone(&update);
+        adjustment.connect_changed(move |_| update_clone2());
+    }
+    gutter
+}
+
+/// Buffer of the first open editor for a notes file; changes to it go through its autosave
+pub fn open_notes_buffer(file_path: &Path) -> Option<gtk::TextBuffer> {
+    NOTES_VIEWS.with(|views| {
+        views
+            .borrow()
+            .iter()
+            .find(|notes| notes.path == file_path)
+            .map(|notes| notes.view.buffer())
+    })
+}
+
+/// Records that `text` is now in the file, for the notes views of `path` that show exactly it
+fn mark_notes_saved(path: &Path, text: &str) {
+    NOTES_VIEWS.with(|views| {
+        for notes in views.borrow_mut().iter_mut().filter(|notes| notes.path == path) {
+            if buffer_text(&notes.view.buffer()) == text {
+                notes.saved_text = text.to_string();
+            }
+        }
+    });
+}
+
+/// Reloads a notes view from its file, e.g. after another tab edited the same note.
+///
+/// Views with edits that aren't on disk yet (waiting for the autosave or after a failed
+/// save) are left alone so switching tabs never throws typing away.
+pub fn reload_notes_view(text_view: &TextView) {
+    let entry = NOTES_VIEWS.with(|views| {
+        views
+            .borrow()
+            .iter()
+            .find(|notes| notes.view == *text_view)
+            .map(|notes| (notes.path.clone(), notes.saved_text.clone()))
+    });
+    let Some((path, saved_text)) = entry else {
+        return;
+    };
+    // A background write would be read half-done, so try again once it has landed
+    if WRITES_IN_FLIGHT.with(|writes| writes.borrow().contains_key(&path)) {
+        let text_view = text_view.clone();
+        glib::timeout_add_local_once(std::time::Duration::from_millis(50), move || {
+            reload_notes_view(&text_view);
+        });
+        return;
+    }
+    let buffer = text_view.buffer();
+    if buffer_text(&buffer) != saved_text {
+        return;
+    }
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    if content != saved_text {
+        // Highlighted as it is inserted, like any other edit
+        buffer.set_text(&content);
+        mark_notes_saved(&path, &content);
+    }
+}
+
+/// Finishes background note writes and writes notes with unsaved edits right away.
+///
+/// Run before the window closes, since the autosave debounce and queued writes
+/// would otherwise never get to run.
+pub fn flush_notes() {
+    let context = glib::MainContext::default();
+    while WRITES_IN_FLIGHT.with(|writes| !writes.borrow().is_empty()) {
+        context.iteration(true);
+    }
+
+    let unsaved: Vec<(PathBuf, String)> = NOTES_VIEWS.with(|views| {
+        views
+            .borrow()
+            .iter()
+            .map(|notes| (notes.path.clone(), buffer_text(&notes.view.buffer()), &notes.saved_text))
+            .filter(|(_, text, saved_text)| text != *saved_text)
+            .map(|(path, text, _)| (path, text))
+            .collect()
+    });
+    for (path, text) in unsaved {
+        match atomic_write(&path, &text) {
+            Ok(()) => mark_notes_saved(&path, &text),
+            Err(e) => log::warn!("Failed to save {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Re-checks spelling in all notes views after the setting, language or personal words change
+pub fn refresh_notes_spelling() {
+    NOTES_VIEWS.with(|views| {
+        for notes in views.borrow().iter() {
+            check_spelling(&notes.view);
+        }
+    });
+}
+
+/// Collects all text views contained in a widget tree
+fn collect_text_views(widget: &gtk::Widget, views: &mut Vec<TextView>) {
+    if let Some(view) = widget.downcast_ref::<TextView>() {
+        views.push(view.clone());
+        return;
+    }
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        collect_text_views(&current, views);
+        child = current.next_sibling();
+    }
+}
+
+/// Stops tracking the text views of a closed tab for zoom and wrap updates
+pub fn unregister_text_views(page: &gtk::Widget) {
+    let mut closed = Vec::new();
+    collect_text_views(page, &mut closed);
+    if closed.is_empty() {
+        return;
+    }
+    TEXT_VIEWS.with(|views| {
+        views.borrow_mut().retain(|v| !closed.contains(v));
+    });
+    NOTES_VIEWS.with(|views| {
+        views.borrow_mut().retain(|notes| !closed.contains(&notes.view));
+    });
+    unregister_previews(&closed);
 }
 
 /// Adds Ctrl+scroll zoom functionality to a TextView
@@ -123,6 +577,414 @@ pub fn add_textview_scroll_zoom(text_view: &TextView) {
     text_view.add_controller(scroll_controller);
 }
 
+/// Highlights every case-insensitive match of `query` in the buffer, returning the match count
+fn highlight_search_matches(buffer: &gtk::TextBuffer, query: &str) -> usize {
+    let start = buffer.start_iter();
+    let end = buffer.end_iter();
+    buffer.remove_tag_by_name(SEARCH_MATCH_TAG, &start, &end);
+    if query.is_empty() {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut iter = buffer.start_iter();
+    while let Some((match_start, match_end)) = iter.forward_search(query, gtk::TextSearchFlags::CASE_INSENSITIVE, None) {
+        buffer.apply_tag_by_name(SEARCH_MATCH_TAG, &match_start, &match_end);
+        count += 1;
+        iter = match_end;
+    }
+    count
+}
+
+/// Formats the match counter shown in the find bar
+fn match_count_text(query: &str, count: usize) -> String {
+    if query.is_empty() {
+        String::new()
+    } else if count == 1 {
+        "1 match".to_string()
+    } else {
+        format!("{} matches", count)
+    }
+}
+
+/// Hides the find bar, clears match highlights and refocuses the editor
+fn hide_find_bar(bar: &GtkBox, replace_row: &GtkBox, text_view: &TextView) {
+    bar.set_visible(false);
+    replace_row.set_visible(false);
+    highlight_search_matches(&text_view.buffer(), "");
+    text_view.grab_focus();
+}
+
+/// Selects the next (or previous) match relative to the cursor, wrapping around
+fn select_search_match(text_view: &TextView, query: &str, forward: bool) {
+    if query.is_empty() {
+        return;
+    }
+    let buffer = text_view.buffer();
+    let flags = gtk::TextSearchFlags::CASE_INSENSITIVE;
+    let (sel_start, sel_end) = buffer.selection_bounds().unwrap_or_else(|| {
+        let cursor = buffer.iter_at_mark(&buffer.get_insert());
+        (cursor.clone(), cursor)
+    });
+
+    let found = if forward {
+        sel_end.forward_search(query, flags, None)
+            .or_else(|| buffer.start_iter().forward_search(query, flags, None))
+    } else {
+        sel_start.backward_search(query, flags, None)
+            .or_else(|| buffer.end_iter().backward_search(query, flags, None))
+    };
+
+    if let Some((match_start, match_end)) = found {
+        buffer.select_range(&match_start, &match_end);
+        text_view.scroll_to_mark(&buffer.get_insert(), 0.1, false, 0.0, 0.0);
+    }
+}
+
+/// Creates the find/replace bar for a text editor.
+///
+/// Returns the bar, its search entry and the replace row (hidden until Ctrl+H).
+fn create_find_replace_bar(text_view: &TextView) -> (GtkBox, gtk::SearchEntry, GtkBox) {
+    let buffer = text_view.buffer();
+    if buffer.tag_table().lookup(SEARCH_MATCH_TAG).is_none() {
+        buffer.create_tag(
+            Some(SEARCH_MATCH_TAG),
+            &[("background", &MarkdownPalette::current().search_match)],
+        );
+    }
+
+    let bar = GtkBox::new(Orientation::Vertical, 4);
+    bar.set_margin_bottom(6);
+    bar.set_visible(false);
+
+    // Find row
+    let find_row = GtkBox::new(Orientation::Horizontal, 4);
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Find..."));
+    search_entry.set_hexpand(true);
+
+    let match_label = Label::new(None);
+    match_label.add_css_class("dim-label");
+
+    let prev_btn = Button::builder()
+        .icon_name("go-up-symbolic")
+        .tooltip_text("Previous Match (Shift+Enter)")
+        .build();
+    prev_btn.add_css_class("flat");
+
+    let next_btn = Button::builder()
+        .icon_name("go-down-symbolic")
+        .tooltip_text("Next Match (Enter)")
+        .build();
+    next_btn.add_css_class("flat");
+
+    let close_btn = Button::builder()
+        .icon_name("window-close-symbolic")
+        .tooltip_text("Close (Escape)")
+        .build();
+    close_btn.add_css_class("flat");
+
+    find_row.append(&search_entry);
+    find_row.append(&match_label);
+    find_row.append(&prev_btn);
+    find_row.append(&next_btn);
+    find_row.append(&close_btn);
+    bar.append(&find_row);
+
+    // Replace row
+    let replace_row = GtkBox::new(Orientation::Horizontal, 4);
+    replace_row.set_visible(false);
+    let replace_entry = gtk::Entry::new();
+    replace_entry.set_placeholder_text(Some("Replace with..."));
+    replace_entry.set_hexpand(true);
+    let replace_btn = Button::with_label("Replace");
+    let replace_all_btn = Button::with_label("Replace All");
+    replace_row.append(&replace_entry);
+    replace_row.append(&replace_btn);
+    replace_row.append(&replace_all_btn);
+    bar.append(&replace_row);
+
+    // Re-highlight as the query changes
+    let buffer_clone = buffer.clone();
+    let match_label_clone = match_label.clone();
+    let text_view_clone = text_view.clone();
+    search_entry.connect_search_changed(move |entry| {
+        let query = entry.text().to_string();
+        let count = highlight_search_matches(&buffer_clone, &query);
+        match_label_clone.set_text(&match_count_text(&query, count));
+
+        // Search as you type from the start of the current selection
+        if let Some((sel_start, _)) = buffer_clone.selection_bounds() {
+            buffer_clone.place_cursor(&sel_start);
+        }
+        select_search_match(&text_view_clone, &query, true);
+    });
+
+    // Keep highlights in sync with edits while the bar is open, rescanning once typing pauses
+    let bar_clone = bar.clone();
+    let search_entry_clone = search_entry.clone();
+    let match_label_clone2 = match_label.clone();
+    let rescan_timeout_id: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+    buffer.connect_changed(move |buffer| {
+        if let Some(id) = rescan_timeout_id.borrow_mut().take() {
+            id.remove();
+        }
+        if !bar_clone.is_visible() || search_entry_clone.text().is_empty() {
+            return;
+        }
+
+        let rescan_timeout_inner = Rc::clone(&rescan_timeout_id);
+        let buffer = buffer.clone();
+        let bar = bar_clone.clone();
+        let search_entry = search_entry_clone.clone();
+        let match_label = match_label_clone2.clone();
+        let delay = std::time::Duration::from_millis(SEARCH_RESCAN_DELAY_MS);
+        let source_id = glib::timeout_add_local_once(delay, move || {
+            rescan_timeout_inner.borrow_mut().take();
+            if !bar.is_visible() {
+                return;
+            }
+            let query = search_entry.text().to_string();
+            let count = highlight_search_matches(&buffer, &query);
+            match_label.set_text(&match_count_text(&query, count));
+        });
+        *rescan_timeout_id.borrow_mut() = Some(source_id);
+    });
+
+    let text_view_clone2 = text_view.clone();
+    search_entry.connect_activate(move |entry| {
+        select_search_match(&text_view_clone2, &entry.text(), true);
+    });
+
+    let text_view_clone3 = text_view.clone();
+    search_entry.connect_next_match(move |entry| {
+        select_search_match(&text_view_clone3, &entry.text(), true);
+    });
+
+    let text_view_clone4 = text_view.clone();
+    search_entry.connect_previous_match(move |entry| {
+        select_search_match(&text_view_clone4, &entry.text(), false);
+    });
+
+    let text_view_clone5 = text_view.clone();
+    let search_entry_clone2 = search_entry.clone();
+    next_btn.connect_clicked(move |_| {
+        select_search_match(&text_view_clone5, &search_entry_clone2.text(), true);
+    });
+
+    let text_view_clone6 = text_view.clone();
+    let search_entry_clone3 = search_entry.clone();
+    prev_btn.connect_clicked(move |_| {
+        select_search_match(&text_view_clone6, &search_entry_clone3.text(), false);
+    });
+
+    // Shift+Enter in the entry goes to the previous match
+    let entry_keys = gtk::EventControllerKey::new();
+    entry_keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let text_view_clone7 = text_view.clone();
+    let search_entry_clone4 = search_entry.clone();
+    entry_keys.connect_key_pressed(move |_, keyval, _, modifier| {
+        if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK)
+            && (keyval == gtk::gdk::Key::Return || keyval == gtk::gdk::Key::KP_Enter)
+        {
+            select_search_match(&text_view_clone7, &search_entry_clone4.text(), false);
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    search_entry.add_controller(entry_keys);
+
+    // Closing clears highlights and returns focus to the editor
+    let bar_clone2 = bar.clone();
+    let replace_row_clone = replace_row.clone();
+    let text_view_clone8 = text_view.clone();
+    close_btn.connect_clicked(move |_| {
+        hide_find_bar(&bar_clone2, &replace_row_clone, &text_view_clone8);
+    });
+
+    let bar_clone3 = bar.clone();
+    let replace_row_clone2 = replace_row.clone();
+    let text_view_clone9 = text_view.clone();
+    search_entry.connect_stop_search(move |_| {
+        hide_find_bar(&bar_clone3, &replace_row_clone2, &text_view_clone9);
+    });
+
+    // Replace the current match, then move on to the next one
+    let text_view_clone10 = text_view.clone();
+    let search_entry_clone5 = search_entry.clone();
+    let replace_entry_clone = replace_entry.clone();
+    replace_btn.connect_clicked(move |_| {
+        let query = search_entry_clone5.text().to_string();
+        if query.is_empty() {
+            return;
+        }
+        let buffer = text_view_clone10.buffer();
+        if let Some((mut sel_start, mut sel_end)) = buffer.selection_bounds() {
+            let selected = buffer.text(&sel_start, &sel_end, false);
+            if selected.to_lowercase() == query.to_lowercase() {
+                buffer.begin_user_action();
+                buffer.delete(&mut sel_start, &mut sel_end);
+                buffer.insert(&mut sel_start, &replace_entry_clone.text());
+                buffer.end_user_action();
+                buffer.place_cursor(&sel_start);
+            }
+        }
+        select_search_match(&text_view_clone10, &query, true);
+    });
+
+    let buffer_clone2 = buffer.clone();
+    let search_entry_clone6 = search_entry.clone();
+    let replace_entry_clone2 = replace_entry.clone();
+    replace_all_btn.connect_clicked(move |_| {
+        let query = search_entry_clone6.text().to_string();
+        if query.is_empty() {
+            return;
+        }
+        let replacement = replace_entry_clone2.text().to_string();
+        buffer_clone2.begin_user_action();
+        let mut iter = buffer_clone2.start_iter();
+        while let Some((mut match_start, mut match_end)) =
+            iter.forward_search(&query, gtk::TextSearchFlags::CASE_INSENSITIVE, None)
+        {
+            buffer_clone2.delete(&mut match_start, &mut match_end);
+            buffer_clone2.insert(&mut match_start, &replacement);
+            iter = match_start;
+        }
+        buffer_clone2.end_user_action();
+    });
+
+    (bar, search_entry, replace_row)
+}
+
+/// Formats the editor status line from cursor position and buffer counts
+fn editor_status_text(line: i32, column: i32, words: usize, chars: i32) -> String {
+    format!("Ln {}, Col {}  ·  {} words, {} chars", line + 1, column + 1, words, chars)
+}
+
+/// Keeps a status label in sync with the cursor position and word/char counts
+///
+/// Counting words reads the whole buffer, so edits only refresh the position and
+/// char count; the returned closure recounts words from text the autosave already read.
+fn track_editor_status(buffer: &gtk::TextBuffer, status_label: &Label) -> impl Fn(&gtk::TextBuffer, &str) {
+    let word_count = Rc::new(Cell::new(0usize));
+
+    let update = {
+        let status_label = status_label.clone();
+        let word_count = Rc::clone(&word_count);
+        move |buffer: &gtk::TextBuffer| {
+            let cursor = buffer.iter_at_offset(buffer.cursor_position());
+            status_label.set_text(&editor_status_text(
+                cursor.line(),
+                cursor.line_offset(),
+                word_count.get(),
+                buffer.char_count(),
+            ));
+        }
+    };
+
+    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+    word_count.set(text.split_whitespace().count());
+    update(buffer);
+
+    let update_clone = update.clone();
+    buffer.connect_changed(move |buffer| {
+        update_clone(buffer);
+    });
+
+    let update_clone2 = update.clone();
+    buffer.connect_cursor_position_notify(move |buffer| {
+        update_clone2(buffer);
+    });
+
+    move |buffer: &gtk::TextBuffer, text: &str| {
+        word_count.set(text.split_whitespace().count());
+        update(buffer);
+    }
+}
+
+/// Maximum number of snapshots kept per note file
+const MAX_NOTE_BACKUPS: usize = 20;
+
+/// Timestamp format used in snapshot file names
+const NOTE_BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Directory holding note snapshots (`notes/backups` under the base dir)
+fn note_backups_dir() -> PathBuf {
+    get_file_path("notes").join("backups")
+}
+
+/// Parses the snapshot time from a backup file name belonging to `file_path`
+pub fn note_backup_time(file_path: &str, backup: &Path) -> Option<chrono::NaiveDateTime> {
+    let stem = Path::new(file_path).file_stem()?.to_string_lossy().to_string();
+    let name = backup.file_name()?.to_string_lossy().to_string();
+    let timestamp = name.strip_prefix(&format!("{}-", stem))?.strip_suffix(".md")?;
+    chrono::NaiveDateTime::parse_from_str(timestamp, NOTE_BACKUP_TIME_FORMAT).ok()
+}
+
+/// Lists snapshots of a note file, newest first
+pub fn list_note_backups(file_path: &str) -> Vec<PathBuf> {
+    let mut backups: Vec<(chrono::NaiveDateTime, PathBuf)> = fs::read_dir(note_backups_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter_map(|path| note_backup_time(file_path, &path).map(|time| (time, path)))
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    backups.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Writes a timestamped snapshot of a note and prunes old ones
+fn snapshot_note(file_path: &str, content: &str) -> Result<PathBuf, String> {
+    let dir = note_backups_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    let stem = Path::new(file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "notes".to_string());
+    let timestamp = chrono::Local::now().format(NOTE_BACKUP_TIME_FORMAT);
+    let backup_path = dir.join(format!("{}-{}.md", stem, timestamp));
+    fs::write(&backup_path, content).map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    for old in list_note_backups(file_path).iter().skip(MAX_NOTE_BACKUPS) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(backup_path)
+}
+
+/// Snapshots the note every `notes_backup_interval_minutes` while its content keeps changing
+fn start_note_snapshots(text_view: &TextView, file_path: &str, last_snapshot: Rc<RefCell<String>>) {
+    let text_view_weak = text_view.downgrade();
+    let file_path = file_path.to_string();
+    let minutes_elapsed = Cell::new(0u32);
+
+    glib::timeout_add_seconds_local(60, move || {
+        let Some(text_view) = text_view_weak.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
+
+        let interval = get_notes_backup_interval();
+        minutes_elapsed.set(minutes_elapsed.get() + 1);
+        if interval == 0 || minutes_elapsed.get() < interval {
+            return glib::ControlFlow::Continue;
+        }
+        minutes_elapsed.set(0);
+
+        let buffer = text_view.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string();
+        if text != *last_snapshot.borrow() {
+            match snapshot_note(&file_path, &text) {
+                Ok(_) => *last_snapshot.borrow_mut() = text,
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
 /// Creates a text editor for targets or notes
 pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> GtkBox {
     let container = GtkBox::new(Orientation::Vertical, 0);
@@ -131,7 +993,14 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
     container.set_margin_start(6);
     container.set_margin_end(6);
 
-    let is_notes = file_path == get_file_path("notes.md").to_string_lossy().to_string();
+    // Any markdown file is a notes editor (notes.md or extra note tabs)
+    let is_notes = file_path.ends_with(".md");
+    // targets.txt autosaves like notes unless turned off in settings
+    let is_targets = file_path == get_file_path("targets.txt").to_string_lossy();
+    let is_autosave_file = is_notes || (is_targets && is_targets_autosave_enabled());
+    if is_notes {
+        container.set_widget_name(&format!("note:{}", file_path));
+    }
 
     // Add target selector for notes tab
     let target_combo_opt = if is_notes {
@@ -171,48 +1040,147 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
         .build();
 
     // Load file content
-    if let Ok(content) = fs::read_to_string(file_path) {
-        text_view.buffer().set_text(&content);
-    }
+    let initial_content = fs::read_to_string(file_path).unwrap_or_default();
+    text_view.buffer().set_text(&initial_content);
 
+    let last_snapshot = Rc::new(RefCell::new(initial_content));
     if is_notes {
         apply_markdown_highlighting(&text_view);
-        track_notes_view(&text_view);
+        track_notes_view(&text_view, Path::new(file_path));
+        start_note_snapshots(&text_view, file_path, Rc::clone(&last_snapshot));
     }
 
     add_textview_scroll_zoom(&text_view);
     scrolled.set_child(Some(&text_view));
 
-    // Auto-save for notes.md with debounce
+    // Rendered preview pane for notes, hidden until toggled on
+    let preview_view = create_preview_view();
+    let preview_scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&preview_view)
+        .visible(false)
+        .build();
+    let editor_paned = gtk::Paned::new(Orientation::Horizontal);
+    editor_paned.set_vexpand(true);
+    editor_paned.set_hexpand(true);
+
+    // Heading outline sidebar for notes, hidden until toggled on
+    let outline_list = gtk::ListBox::new();
+    outline_list.add_css_class("navigation-sidebar");
+    let outline_scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .width_request(200)
+        .child(&outline_list)
+        .build();
+    let outline_revealer = gtk::Revealer::builder()
+        .transition_type(gtk::RevealerTransitionType::SlideRight)
+        .child(&outline_scrolled)
+        .build();
+    let notes_body = GtkBox::new(Orientation::Horizontal, 0);
+    notes_body.set_widget_name("notes_body");
+    notes_body.set_vexpand(true);
     if is_notes {
-        let file_path_owned = file_path.to_string();
         let text_view_clone = text_view.clone();
+        outline_list.connect_row_activated(move |_, row| {
+            let Some(line) = row.widget_name().strip_prefix("line_").and_then(|l| l.parse::<i32>().ok()) else {
+                return;
+            };
+            let buffer = text_view_clone.buffer();
+            if let Some(mut iter) = buffer.iter_at_line(line) {
+                buffer.place_cursor(&iter);
+                text_view_clone.scroll_to_iter(&mut iter, 0.0, true, 0.0, 0.0);
+                text_view_clone.grab_focus();
+            }
+        });
+        notes_body.append(&outline_revealer);
+        notes_body.append(&editor_paned);
+
+        add_textview_scroll_zoom(&preview_view);
+        editor_paned.set_start_child(Some(&scrolled));
+        editor_paned.set_end_child(Some(&preview_scrolled));
+        editor_paned.set_resize_start_child(true);
+        editor_paned.set_shrink_start_child(false);
+        editor_paned.set_shrink_end_child(false);
+    }
+
+    let (find_bar, find_entry, replace_row) = create_find_replace_bar(&text_view);
+    container.append(&find_bar);
+
+    let save_state_label = create_save_state_label();
+
+    let status_label = Label::new(None);
+    status_label.add_css_class("dim-label");
+    status_label.add_css_class("caption");
+    status_label.set_margin_end(6);
+    let recount_words = is_notes.then(|| Rc::new(track_editor_status(&text_view.buffer(), &status_label)));
+
+    // Auto-save for notes and targets with debounce
+    if is_autosave_file {
+        let file_path_owned = file_path.to_string();
+        let notebook_clone = notebook.clone();
+        let preview_view_clone = preview_view.clone();
+        let preview_scrolled_clone = preview_scrolled.clone();
+        let outline_list_clone = outline_list.clone();
+        let outline_revealer_clone = outline_revealer.clone();
+        let save_state_clone = save_state_label.clone();
         let save_timeout_id: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
         let save_timeout_clone = Rc::clone(&save_timeout_id);
+        let recount_words_clone = recount_words.clone();
 
         text_view.buffer().connect_changed(move |buffer| {
             let file_path = file_path_owned.clone();
-            let text_view_ref = text_view_clone.clone();
 
             if let Some(id) = save_timeout_clone.borrow_mut().take() {
                 id.remove();
             }
 
-            apply_markdown_highlighting(&text_view_ref);
+            set_save_state(&save_state_clone, false);
 
             let save_timeout_inner = Rc::clone(&save_timeout_clone);
             let buffer_clone = buffer.clone();
-            let source_id = glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+            let preview_view_ref = preview_view_clone.clone();
+            let preview_scrolled_ref = preview_scrolled_clone.clone();
+            let outline_list_ref = outline_list_clone.clone();
+            let outline_revealer_ref = outline_revealer_clone.clone();
+            let save_state_ref = save_state_clone.clone();
+            let notebook_ref = notebook_clone.clone();
+            let recount_words_ref = recount_words_clone.clone();
+            let delay = std::time::Duration::from_millis(get_autosave_debounce_ms() as u64);
+            let source_id = glib::timeout_add_local(delay, move || {
                 let start = buffer_clone.start_iter();
                 let end = buffer_clone.end_iter();
                 let text = buffer_clone.text(&start, &end, false);
-                let _ = fs::write(&file_path, text.as_str());
+                // Targets are re-read by the shells right after saving, so they are written in place
+                if is_targets {
+                    save_editor_text(&file_path, &text, &save_state_ref);
+                } else {
+                    save_editor_text_async(&file_path, &text, &save_state_ref);
+                }
+                if let Some(ref recount) = recount_words_ref {
+                    recount(&buffer_clone, &text);
+                }
+                if preview_scrolled_ref.is_visible() {
+                    render_markdown_preview(&preview_view_ref, &text);
+                }
+                if outline_revealer_ref.reveals_child() {
+                    populate_outline(&outline_list_ref, &text);
+                }
+                if is_targets {
+                    if let Some(ref nb) = notebook_ref {
+                        reload_targets_in_shells(nb);
+                    }
+                }
                 *save_timeout_inner.borrow_mut() = None;
                 glib::ControlFlow::Break
             });
             *save_timeout_clone.borrow_mut() = Some(source_id);
         });
+    }
 
+    if is_notes {
         // Add insert target button for notes
         if let Some((target_box, target_combo)) = target_combo_opt {
             let insert_target_btn = Button::builder()
@@ -221,15 +1189,79 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
                 .build();
             insert_target_btn.add_css_class("flat");
 
+            let target_combo_clone = target_combo.clone();
             let text_view_clone2 = text_view.clone();
             insert_target_btn.connect_clicked(move |_| {
-                if let Some(target) = target_combo.active_text() {
+                if let Some(target) = target_combo_clone.active_text() {
                     let buffer = text_view_clone2.buffer();
                     buffer.insert_at_cursor(&target.to_string());
                     text_view_clone2.grab_focus();
                 }
             });
             target_box.append(&insert_target_btn);
+
+            let host_section_btn = Button::builder()
+                .icon_name("insert-text-symbolic")
+                .tooltip_text("New Host Section")
+                .build();
+            host_section_btn.add_css_class("flat");
+            let text_view_clone6 = text_view.clone();
+            host_section_btn.connect_clicked(move |_| {
+                insert_host_section(&text_view_clone6, target_combo.active_text().as_deref());
+            });
+            target_box.append(&host_section_btn);
+
+            let insert_table_btn = Button::builder()
+                .icon_name("view-grid-symbolic")
+                .tooltip_text("Insert Table")
+                .build();
+            insert_table_btn.add_css_class("flat");
+            let text_view_clone4 = text_view.clone();
+            insert_table_btn.connect_clicked(move |_| {
+                show_insert_table_prompt(&text_view_clone4);
+            });
+            target_box.append(&insert_table_btn);
+
+            let preview_toggle = gtk::ToggleButton::builder()
+                .icon_name("view-dual-symbolic")
+                .tooltip_text("Toggle Preview")
+                .build();
+            preview_toggle.add_css_class("flat");
+
+            let text_view_clone3 = text_view.clone();
+            let preview_view_clone2 = preview_view.clone();
+            let preview_scrolled_clone2 = preview_scrolled.clone();
+            let editor_paned_clone = editor_paned.clone();
+            preview_toggle.connect_toggled(move |toggle| {
+                let show = toggle.is_active();
+                if show {
+                    let buffer = text_view_clone3.buffer();
+                    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+                    render_markdown_preview(&preview_view_clone2, &text);
+                    editor_paned_clone.set_position(editor_paned_clone.width() / 2);
+                }
+                preview_scrolled_clone2.set_visible(show);
+            });
+            target_box.append(&preview_toggle);
+
+            let outline_toggle = gtk::ToggleButton::builder()
+                .icon_name("sidebar-show-symbolic")
+                .tooltip_text("Toggle Outline")
+                .build();
+            outline_toggle.add_css_class("flat");
+
+            let text_view_clone5 = text_view.clone();
+            let outline_list_clone2 = outline_list.clone();
+            let outline_revealer_clone2 = outline_revealer.clone();
+            outline_toggle.connect_toggled(move |toggle| {
+                if toggle.is_active() {
+                    let buffer = text_view_clone5.buffer();
+                    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+                    populate_outline(&outline_list_clone2, &text);
+                }
+                outline_revealer_clone2.set_reveal_child(toggle.is_active());
+            });
+            target_box.append(&outline_toggle);
         }
     }
 
@@ -246,12 +1278,13 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
     let file_path_owned = file_path.to_string();
     let text_view_clone = text_view.clone();
     let notebook_clone = notebook.clone();
+    let save_state_clone = save_state_label.clone();
     save_btn.connect_clicked(move |_| {
         let buffer = text_view_clone.buffer();
         let start = buffer.start_iter();
         let end = buffer.end_iter();
         let text = buffer.text(&start, &end, false);
-        let _ = fs::write(&file_path_owned, text.as_str());
+        save_editor_text(&file_path_owned, &text, &save_state_clone);
 
         if file_path_owned == get_file_path("targets.txt").to_string_lossy().to_string() {
             if let Some(ref nb) = notebook_clone {
@@ -266,24 +1299,134 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
     file_label.set_halign(gtk::Align::Start);
 
     button_box.append(&save_btn);
+
+    if is_notes {
+        let restore_btn = Button::builder()
+            .icon_name("document-revert-symbolic")
+            .tooltip_text("Restore from Backup")
+            .build();
+        restore_btn.add_css_class("flat");
+
+        let file_path_owned = file_path.to_string();
+        let text_view_clone = text_view.clone();
+        restore_btn.connect_clicked(move |btn| {
+            let parent = btn.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+            let file_path = file_path_owned.clone();
+            let text_view_ref = text_view_clone.clone();
+            let last_snapshot = Rc::clone(&last_snapshot);
+            crate::ui::dialogs::show_restore_backup_dialog(
+                parent.as_ref(),
+                &file_path_owned,
+                move |backup| {
+                    let content = match fs::read_to_string(&backup) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            log::warn!("Failed to read backup {}: {}", backup.display(), e);
+                            return;
+                        }
+                    };
+
+                    // Snapshot the current text first so the restore can be undone
+                    let buffer = text_view_ref.buffer();
+                    let current = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string();
+                    if current != *last_snapshot.borrow() {
+                        match snapshot_note(&file_path, &current) {
+                            Ok(_) => *last_snapshot.borrow_mut() = current,
+                            Err(e) => log::warn!("{}", e),
+                        }
+                    }
+                    buffer.set_text(&content);
+                },
+            );
+        });
+        button_box.append(&restore_btn);
+    }
+
+    // Line numbers and word wrap, shared by all editors and remembered in settings
+    let gutter = create_line_number_gutter(&text_view);
+    let line_numbers_toggle = create_editor_toggle("line_numbers", "view-list-ordered-symbolic", "Toggle Line Numbers");
+    let text_view_clone6 = text_view.clone();
+    line_numbers_toggle.connect_toggled(move |toggle| {
+        let active = toggle.is_active();
+        text_view_clone6.set_gutter(gtk::TextWindowType::Left, active.then_some(&gutter));
+        if active != is_editor_line_numbers_enabled() {
+            let mut settings = get_app_settings();
+            settings.editor_line_numbers = active;
+            report_error(save_app_settings(&settings));
+            sync_editor_toggles("line_numbers", active);
+        }
+    });
+    line_numbers_toggle.set_active(is_editor_line_numbers_enabled());
+    button_box.append(&line_numbers_toggle);
+
+    let wrap_key = if is_notes { "notes_wrap" } else { "editor_wrap" };
+    let wrap_toggle = create_editor_toggle(wrap_key, "format-justify-fill-symbolic", "Toggle Word Wrap");
+    let text_view_clone7 = text_view.clone();
+    wrap_toggle.connect_toggled(move |toggle| {
+        let active = toggle.is_active();
+        if is_notes {
+            // Notes share one wrap setting with Settings → Notes Settings
+            if active != is_notes_wrap_text_enabled() {
+                set_notes_wrap_mode(active);
+            }
+            return;
+        }
+        let wrap_mode = if active { gtk::WrapMode::WordChar } else { gtk::WrapMode::None };
+        text_view_clone7.set_wrap_mode(wrap_mode);
+        if active != is_editor_wrap_text_enabled() {
+            let mut settings = get_app_settings();
+            settings.editor_wrap_text = active;
+            report_error(save_app_settings(&settings));
+            sync_editor_toggles("editor_wrap", active);
+        }
+    });
+    wrap_toggle.set_active(if is_notes { is_notes_wrap_text_enabled() } else { is_editor_wrap_text_enabled() });
+    button_box.append(&wrap_toggle);
+
     button_box.append(&file_label);
 
+    if is_autosave_file {
+        button_box.append(&save_state_label);
+    }
+
+    if is_notes {
+        button_box.append(&status_label);
+    }
+
     // Add Ctrl+S keyboard shortcut
     let key_controller = gtk::EventControllerKey::new();
     let file_path_owned2 = file_path.to_string();
     let text_view_clone2 = text_view.clone();
     let notebook_clone2 = notebook.clone();
+    let save_state_clone2 = save_state_label.clone();
     let text_view_clone3 = text_view.clone();
     let text_view_clone4 = text_view.clone();
+    let text_view_clone5 = text_view.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
         if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+            // Ctrl+F opens find, Ctrl+H opens find & replace
+            if keyval == gtk::gdk::Key::f || keyval == gtk::gdk::Key::h {
+                find_bar.set_visible(true);
+                replace_row.set_visible(keyval == gtk::gdk::Key::h);
+                let buffer = text_view_clone5.buffer();
+                if let Some((sel_start, sel_end)) = buffer.selection_bounds() {
+                    let selected = buffer.text(&sel_start, &sel_end, false);
+                    if !selected.contains('\n') {
+                        find_entry.set_text(&selected);
+                    }
+                }
+                find_entry.grab_focus();
+                find_entry.select_region(0, -1);
+                return gtk::glib::Propagation::Stop;
+            }
+
             if keyval == gtk::gdk::Key::s {
                 let buffer = text_view_clone2.buffer();
                 let start = buffer.start_iter();
                 let end = buffer.end_iter();
                 let text = buffer.text(&start, &end, false);
-                let _ = fs::write(&file_path_owned2, text.as_str());
+                save_editor_text(&file_path_owned2, &text, &save_state_clone2);
 
                 if file_path_owned2 == get_file_path("targets.txt").to_string_lossy().to_string() {
                     if let Some(ref nb) = notebook_clone2 {
@@ -292,29 +1435,376 @@ pub fn create_text_editor(file_path: &str, notebook: Option<gtk::Notebook>) -> G
                 }
                 return gtk::glib::Propagation::Stop;
             }
+        }
+
+        let shortcuts = get_keyboard_shortcuts();
+        if shortcuts.matches("insert_target", keyval, modifier) {
+            show_target_selector_for_textview(&text_view_clone3);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if shortcuts.matches("insert_timestamp", keyval, modifier) {
+            let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
+            let buffer = text_view_clone4.buffer();
+            buffer.insert_at_cursor(&timestamp);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if is_notes && shortcuts.matches("insert_table", keyval, modifier) {
+            show_insert_table_prompt(&text_view_clone4);
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    text_view.add_controller(key_controller);
+
+    if is_notes {
+        container.append(&notes_body);
+    } else {
+        container.append(&scrolled);
+    }
+    container.append(&button_box);
+
+    container
+}
+
+/// Inserts the configured host section template at the cursor, starting on a fresh line
+fn insert_host_section(text_view: &TextView, target: Option<&str>) {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let mut section = fill_host_section(&get_host_section_template(), target.unwrap_or("New Host"), &timestamp);
+
+    let buffer = text_view.buffer();
+    if !buffer.iter_at_mark(&buffer.get_insert()).starts_line() {
+        section.insert(0, '\n');
+    }
+    buffer.insert_at_cursor(&section);
+    text_view.grab_focus();
+}
+
+/// Markdown level of a `#` heading line, or None if the line isn't a heading
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    ((1..=6).contains(&level) && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// Headings of a markdown text as (line, level, title), skipping fenced code blocks
+pub fn markdown_outline(text: &str) -> Vec<(i32, usize, String)> {
+    let mut outline = Vec::new();
+    let mut in_code_block = false;
+    for (line_index, line) in text.split('\n').enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            if let Some(level) = heading_level(line) {
+                let title = line[level..].trim();
+                if !title.is_empty() {
+                    outline.push((line_index as i32, level, title.to_string()));
+                }
+            }
+        }
+    }
+    outline
+}
+
+/// Fills the outline sidebar with one indented row per heading
+fn populate_outline(list_box: &gtk::ListBox, text: &str) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let outline = markdown_outline(text);
+    if outline.is_empty() {
+        let empty = Label::new(Some("No headings"));
+        empty.add_css_class("dim-label");
+        empty.set_margin_top(12);
+        list_box.append(&empty);
+        if let Some(row) = list_box.row_at_index(0) {
+            row.set_activatable(false);
+            row.set_selectable(false);
+        }
+        return;
+    }
+
+    for (line, level, title) in outline {
+        let label = Label::new(Some(&title));
+        label.set_xalign(0.0);
+        label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        label.set_tooltip_text(Some(&title));
+        label.set_margin_start(6 + (level as i32 - 1) * 12);
+        if level == 1 {
+            label.add_css_class("heading");
+        }
+        let row = gtk::ListBoxRow::new();
+        row.set_child(Some(&label));
+        row.set_widget_name(&format!("line_{}", line));
+        list_box.append(&row);
+    }
+}
+
+/// Finds the editing TextView inside a container built by `create_text_editor`
+pub fn editor_text_view(container: &GtkBox) -> Option<TextView> {
+    let mut child = container.first_child();
+    while let Some(current) = child {
+        // Notes editors sit in a Paned next to the preview pane, after the outline sidebar
+        let editor = if current.widget_name() == "notes_body" {
+            current.last_child()
+        } else {
+            Some(current.clone())
+        };
+        let scrolled = match editor.as_ref().and_then(|e| e.downcast_ref::<gtk::Paned>()) {
+            Some(paned) => paned.start_child(),
+            None => editor,
+        };
+        if let Some(text_view) = scrolled
+            .and_downcast::<ScrolledWindow>()
+            .and_then(|sw| sw.child())
+            .and_downcast::<TextView>()
+        {
+            return Some(text_view);
+        }
+        child = current.next_sibling();
+    }
+    None
+}
+
+/// Collapses consecutive identical commands (ignoring timestamps) into one line with an `(xN)` suffix
+fn dedup_log_lines(content: &str) -> String {
+    fn command_of(line: &str) -> &str {
+        if line.starts_with('[') {
+            if let Some(end) = line.find("] ") {
+                return &line[end + 2..];
+            }
+        }
+        line
+    }
+
+    let mut output: Vec<String> = Vec::new();
+    let mut last_command: Option<&str> = None;
+    let mut repeat_count = 1;
+
+    for line in content.lines() {
+        let command = command_of(line);
+        if last_command == Some(command) {
+            repeat_count += 1;
+            continue;
+        }
+        if repeat_count > 1 {
+            if let Some(last) = output.last_mut() {
+                last.push_str(&format!(" (x{})", repeat_count));
+            }
+        }
+        output.push(line.to_string());
+        last_command = Some(command);
+        repeat_count = 1;
+    }
+    if repeat_count > 1 {
+        if let Some(last) = output.last_mut() {
+            last.push_str(&format!(" (x{})", repeat_count));
+        }
+    }
+
+    let mut result = output.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Masks every match of the given patterns with `***`, or only the captured groups if present
+fn redact_log_lines(content: &str, patterns: &[Regex]) -> String {
+    let mut redacted = content.to_string();
+    for regex in patterns {
+        if regex.captures_len() > 1 {
+            let mut output = String::with_capacity(redacted.len());
+            let mut last = 0;
+            for caps in regex.captures_iter(&redacted) {
+                for group in caps.iter().skip(1).flatten() {
+                    if group.start() < last {
+                        continue;
+                    }
+                    output.push_str(&redacted[last..group.start()]);
+                    output.push_str("***");
+                    last = group.end();
+                }
+            }
+            output.push_str(&redacted[last..]);
+            redacted = output;
+        } else {
+            redacted = regex.replace_all(&redacted, "***").into_owned();
+        }
+    }
+    redacted
+}
+
+/// Compiles the configured redaction patterns, skipping invalid ones
+fn compile_redaction_patterns(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .filter_map(|p| match RegexBuilder::new(p).multi_line(true).build() {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::warn!("Ignoring invalid redaction pattern '{}': {}", p, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Masks secrets in arbitrary text with the configured redaction patterns.
+/// The patterns are compiled once and again only when the settings change.
+pub fn redact_secrets(content: &str) -> String {
+    let patterns = get_redaction_patterns();
+    REDACTION_REGEXES.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.as_ref().is_none_or(|(source, _)| *source != patterns) {
+            let compiled = compile_redaction_patterns(&patterns);
+            *cache = Some((patterns, compiled));
+        }
+        let (_, regexes) = cache.as_ref().expect("redaction cache was just filled");
+        redact_log_lines(content, regexes)
+    })
+}
+
+/// Prepares command log content for display, masking secrets and de-duplicating repeats if enabled
+pub fn format_log_for_display(content: &str) -> String {
+    let redacted = redact_secrets(content);
+    if is_log_dedup_enabled() {
+        dedup_log_lines(&redacted)
+    } else {
+        redacted
+    }
+}
+
+/// Returns the zero-based index of the selected match, counting matches from the buffer start
+fn selected_match_index(buffer: &gtk::TextBuffer, query: &str) -> Option<usize> {
+    let (sel_start, _) = buffer.selection_bounds()?;
+    let mut index = 0;
+    let mut iter = buffer.start_iter();
+    while let Some((match_start, match_end)) = iter.forward_search(query, gtk::TextSearchFlags::CASE_INSENSITIVE, None) {
+        if match_start.offset() >= sel_start.offset() {
+            return Some(index);
+        }
+        index += 1;
+        iter = match_end;
+    }
+    None
+}
+
+/// Selects and scrolls to the nth match of `query`
+fn select_nth_match(text_view: &TextView, query: &str, n: usize) {
+    let buffer = text_view.buffer();
+    let mut iter = buffer.start_iter();
+    let mut index = 0;
+    while let Some((match_start, match_end)) = iter.forward_search(query, gtk::TextSearchFlags::CASE_INSENSITIVE, None) {
+        if index == n {
+            buffer.select_range(&match_start, &match_end);
+            text_view.scroll_to_mark(&buffer.get_insert(), 0.1, false, 0.0, 0.0);
+            return;
+        }
+        index += 1;
+        iter = match_end;
+    }
+}
+
+/// Formats the log search counter as "current of total"
+fn log_match_text(query: &str, index: Option<usize>, count: usize) -> String {
+    match index {
+        Some(i) if count > 0 => format!("{} of {}", i + 1, count),
+        _ => match_count_text(query, count),
+    }
+}
+
+/// Moves to the next (or previous) log match and updates the counter
+fn step_log_search(text_view: &TextView, match_label: &Label, query: &str, forward: bool) {
+    select_search_match(text_view, query, forward);
+    let buffer = text_view.buffer();
+    let count = highlight_search_matches(&buffer, query);
+    match_label.set_text(&log_match_text(query, selected_match_index(&buffer, query), count));
+}
+
+/// Replaces the log viewer's text, keeping the current search match or scrolling to the end
+fn set_log_text(text_view: &TextView, search_entry: &gtk::SearchEntry, match_label: &Label, content: &str) {
+    let buffer = text_view.buffer();
+    let query = search_entry.text().to_string();
+    let previous_index = selected_match_index(&buffer, &query).unwrap_or(0);
+    // Follow new lines only when already at the end, so reading older entries isn't interrupted
+    let at_bottom = text_view
+        .vadjustment()
+        .is_none_or(|adj| adj.value() + adj.page_size() >= adj.upper() - 1.0);
+
+    buffer.set_text(&format_log_for_display(content));
+
+    if query.is_empty() {
+        match_label.set_text("");
+        if at_bottom {
+            let mut end_iter = buffer.end_iter();
+            text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
+        }
+        return;
+    }
 
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
-            if key_name == shortcuts.insert_target {
-                show_target_selector_for_textview(&text_view_clone3);
-                return gtk::glib::Propagation::Stop;
-            }
+    let count = highlight_search_matches(&buffer, &query);
+    if count > 0 {
+        select_nth_match(text_view, &query, previous_index.min(count - 1));
+    }
+    match_label.set_text(&log_match_text(&query, selected_match_index(&buffer, &query), count));
+}
 
-            if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) && key_name == shortcuts.insert_timestamp {
-                let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
-                let buffer = text_view_clone4.buffer();
-                buffer.insert_at_cursor(&timestamp);
-                return gtk::glib::Propagation::Stop;
-            }
-        }
-        gtk::glib::Propagation::Proceed
+/// Reloads a log viewer created by `create_readonly_viewer` from disk
+pub fn reload_log_viewer(log_box: &GtkBox, file_path: &str) {
+    let Some(search_row) = log_box.first_child().and_downcast::<GtkBox>() else { return };
+    let Some(search_entry) = search_row.first_child().and_downcast::<gtk::SearchEntry>() else { return };
+    let Some(match_label) = search_entry.next_sibling().and_downcast::<Label>() else { return };
+    let Some(scrolled) = search_row.next_sibling().and_downcast::<ScrolledWindow>() else { return };
+    let Some(text_view) = scrolled.child().and_downcast::<TextView>() else { return };
+    let include_archives = log_box
+        .last_child()
+        .and_then(|button_box| button_box.first_child())
+        .and_then(|refresh_btn| refresh_btn.next_sibling())
+        .and_downcast::<gtk::ToggleButton>()
+        .is_some_and(|toggle| toggle.is_active());
+
+    remember_log_stamp(file_path);
+    if let Some(content) = read_log(file_path, include_archives) {
+        set_log_text(&text_view, &search_entry, &match_label, &content);
+    }
+}
+
+/// Modification time and size of a log file, None if it doesn't exist
+fn log_stamp(file_path: &str) -> Option<(Option<std::time::SystemTime>, u64)> {
+    fs::metadata(file_path).ok().map(|metadata| (metadata.modified().ok(), metadata.len()))
+}
+
+/// Notes the state of a log file as it is loaded into a viewer
+fn remember_log_stamp(file_path: &str) {
+    let stamp = log_stamp(file_path);
+    LOG_STAMPS.with(|stamps| {
+        stamps.borrow_mut().insert(file_path.to_string(), stamp);
     });
-    text_view.add_controller(key_controller);
+}
 
-    container.append(&scrolled);
-    container.append(&button_box);
+/// Checks if a log file was modified, grown, rotated or removed since a viewer last loaded it
+pub fn log_file_changed(file_path: &str) -> bool {
+    let stamp = log_stamp(file_path);
+    LOG_STAMPS.with(|stamps| stamps.borrow().get(file_path) != Some(&stamp))
+}
 
-    container
+/// Reads a log for the viewer, preceded by its rotated archives when `include_archives` is set
+fn read_log(file_path: &str, include_archives: bool) -> Option<String> {
+    let current = fs::read_to_string(file_path);
+    if !include_archives {
+        return current.ok();
+    }
+    let mut content = String::new();
+    for archive in log_archives(Path::new(file_path)) {
+        match fs::read_to_string(&archive) {
+            Ok(text) => content.push_str(&text),
+            Err(e) => log::warn!("Failed to read {}: {}", archive.display(), e),
+        }
+    }
+    content.push_str(&current.unwrap_or_default());
+    Some(content)
 }
 
 /// Creates a read-only viewer for command logs
@@ -325,6 +1815,34 @@ pub fn create_readonly_viewer(file_path: &str) -> GtkBox {
     container.set_margin_start(6);
     container.set_margin_end(6);
 
+    // Search row
+    let search_row = GtkBox::new(Orientation::Horizontal, 4);
+    search_row.set_margin_bottom(6);
+
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Search log..."));
+    search_entry.set_hexpand(true);
+
+    let match_label = Label::new(None);
+    match_label.add_css_class("dim-label");
+
+    let prev_btn = Button::builder()
+        .icon_name("go-up-symbolic")
+        .tooltip_text("Previous Match (Shift+Enter)")
+        .build();
+    prev_btn.add_css_class("flat");
+
+    let next_btn = Button::builder()
+        .icon_name("go-down-symbolic")
+        .tooltip_text("Next Match (Enter)")
+        .build();
+    next_btn.add_css_class("flat");
+
+    search_row.append(&search_entry);
+    search_row.append(&match_label);
+    search_row.append(&prev_btn);
+    search_row.append(&next_btn);
+
     let scrolled = ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Automatic)
         .vscrollbar_policy(gtk::PolicyType::Automatic)
@@ -340,17 +1858,88 @@ pub fn create_readonly_viewer(file_path: &str) -> GtkBox {
         .top_margin(8)
         .bottom_margin(8)
         .build();
+    text_view.buffer().create_tag(
+        Some(SEARCH_MATCH_TAG),
+        &[("background", &MarkdownPalette::current().search_match)],
+    );
 
+    remember_log_stamp(file_path);
     if let Ok(content) = fs::read_to_string(file_path) {
-        text_view.buffer().set_text(&content);
-        let buffer = text_view.buffer();
-        let mut end_iter = buffer.end_iter();
-        text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
+        set_log_text(&text_view, &search_entry, &match_label, &content);
     }
 
     add_textview_scroll_zoom(&text_view);
     scrolled.set_child(Some(&text_view));
 
+    // Highlight as the query changes and jump to the first match
+    let text_view_clone = text_view.clone();
+    let match_label_clone = match_label.clone();
+    search_entry.connect_search_changed(move |entry| {
+        let query = entry.text().to_string();
+        let buffer = text_view_clone.buffer();
+        if query.is_empty() {
+            highlight_search_matches(&buffer, "");
+            match_label_clone.set_text("");
+            let mut end_iter = buffer.end_iter();
+            text_view_clone.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
+            return;
+        }
+        let count = highlight_search_matches(&buffer, &query);
+        if count > 0 {
+            select_nth_match(&text_view_clone, &query, 0);
+        }
+        match_label_clone.set_text(&log_match_text(&query, selected_match_index(&buffer, &query), count));
+    });
+
+    let text_view_clone2 = text_view.clone();
+    let match_label_clone2 = match_label.clone();
+    search_entry.connect_activate(move |entry| {
+        step_log_search(&text_view_clone2, &match_label_clone2, &entry.text(), true);
+    });
+
+    let text_view_clone3 = text_view.clone();
+    let match_label_clone3 = match_label.clone();
+    search_entry.connect_next_match(move |entry| {
+        step_log_search(&text_view_clone3, &match_label_clone3, &entry.text(), true);
+    });
+
+    let text_view_clone4 = text_view.clone();
+    let match_label_clone4 = match_label.clone();
+    search_entry.connect_previous_match(move |entry| {
+        step_log_search(&text_view_clone4, &match_label_clone4, &entry.text(), false);
+    });
+
+    let text_view_clone5 = text_view.clone();
+    let match_label_clone5 = match_label.clone();
+    let search_entry_clone = search_entry.clone();
+    next_btn.connect_clicked(move |_| {
+        step_log_search(&text_view_clone5, &match_label_clone5, &search_entry_clone.text(), true);
+    });
+
+    let text_view_clone6 = text_view.clone();
+    let match_label_clone6 = match_label.clone();
+    let search_entry_clone2 = search_entry.clone();
+    prev_btn.connect_clicked(move |_| {
+        step_log_search(&text_view_clone6, &match_label_clone6, &search_entry_clone2.text(), false);
+    });
+
+    // Shift+Enter in the entry goes to the previous match
+    let entry_keys = gtk::EventControllerKey::new();
+    entry_keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let text_view_clone7 = text_view.clone();
+    let match_label_clone7 = match_label.clone();
+    let search_entry_clone3 = search_entry.clone();
+    entry_keys.connect_key_pressed(move |_, keyval, _, modifier| {
+        if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK)
+            && (keyval == gtk::gdk::Key::Return || keyval == gtk::gdk::Key::KP_Enter)
+        {
+            step_log_search(&text_view_clone7, &match_label_clone7, &search_entry_clone3.text(), false);
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    search_entry.add_controller(entry_keys);
+
     let button_box = GtkBox::new(Orientation::Horizontal, 6);
     button_box.set_margin_top(6);
 
@@ -361,14 +1950,22 @@ pub fn create_readonly_viewer(file_path: &str) -> GtkBox {
     refresh_btn.add_css_class("flat");
 
     let file_path_owned = file_path.to_string();
-    let text_view_clone = text_view.clone();
+    let container_clone = container.clone();
     refresh_btn.connect_clicked(move |_| {
-        if let Ok(content) = fs::read_to_string(&file_path_owned) {
-            text_view_clone.buffer().set_text(&content);
-            let buffer = text_view_clone.buffer();
-            let mut end_iter = buffer.end_iter();
-            text_view_clone.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
-        }
+        reload_log_viewer(&container_clone, &file_path_owned);
+    });
+
+    // Rotated segments (commands.log.1, .2, ...) are only read when asked for
+    let archives_toggle = gtk::ToggleButton::builder()
+        .icon_name("document-open-recent-symbolic")
+        .tooltip_text("Include Archived Logs")
+        .build();
+    archives_toggle.add_css_class("flat");
+
+    let file_path_owned2 = file_path.to_string();
+    let container_clone2 = container.clone();
+    archives_toggle.connect_toggled(move |_| {
+        reload_log_viewer(&container_clone2, &file_path_owned2);
     });
 
     let file_label = Label::new(Some(file_path));
@@ -377,19 +1974,60 @@ pub fn create_readonly_viewer(file_path: &str) -> GtkBox {
     file_label.set_halign(gtk::Align::Start);
 
     button_box.append(&refresh_btn);
+    button_box.append(&archives_toggle);
     button_box.append(&file_label);
 
+    container.append(&search_row);
     container.append(&scrolled);
     container.append(&button_box);
 
     container
 }
 
+/// Builds an empty, column-aligned markdown table with a header row
+fn markdown_table_scaffold(rows: usize, columns: usize) -> String {
+    let headers: Vec<String> = (1..=columns).map(|i| format!("Column {}", i)).collect();
+    let widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+
+    let mut table = row(headers);
+    table.push_str(&row(widths.iter().map(|w| "-".repeat(*w)).collect()));
+    for _ in 0..rows {
+        table.push_str(&row(widths.iter().map(|w| " ".repeat(*w)).collect()));
+    }
+    table
+}
+
+/// Prompts for a table size and inserts a markdown table scaffold at the cursor
+pub fn show_insert_table_prompt(text_view: &TextView) {
+    let parent = text_view.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+    let text_view_clone = text_view.clone();
+    crate::ui::dialogs::show_insert_table_dialog(parent.as_ref(), move |rows, columns| {
+        let buffer = text_view_clone.buffer();
+        let cursor = buffer.iter_at_mark(&buffer.get_insert());
+        let mut table = markdown_table_scaffold(rows, columns);
+        // Tables must start on their own line
+        if !cursor.starts_line() {
+            table.insert(0, '\n');
+        }
+        buffer.insert_at_cursor(&table);
+        text_view_clone.grab_focus();
+    });
+}
+
+/// Puts a target on the system clipboard for pasting into other tools
+pub fn copy_target_to_clipboard(target: &str) {
+    if let Some(display) = gtk::gdk::Display::default() {
+        display.clipboard().set_text(target);
+    }
+}
+
 /// Shows a target selector popup for TextView
 pub fn show_target_selector_for_textview(text_view: &TextView) {
     let targets = load_targets();
 
     if targets.is_empty() {
+        show_no_targets_toast();
         return;
     }
 
@@ -460,6 +2098,18 @@ pub fn show_target_selector_for_textview(text_view: &TextView) {
         popup_clone2.close();
     });
 
+    let copy_btn = Button::with_label("Copy");
+    copy_btn.set_tooltip_text(Some("Copy the selected target to the clipboard (Ctrl+C)"));
+    let popup_clone5 = popup.clone();
+    let list_box_clone3 = list_box.clone();
+    let targets_clone4 = targets.clone();
+    copy_btn.connect_clicked(move |_| {
+        if let Some(target) = list_box_clone3.selected_row().and_then(|row| targets_clone4.get(row.index() as usize)) {
+            copy_target_to_clipboard(target);
+        }
+        popup_clone5.close();
+    });
+
     // Handle double-click/activation
     let popup_clone3 = popup.clone();
     let text_view_clone2 = text_view.clone();
@@ -480,10 +2130,18 @@ pub fn show_target_selector_for_textview(text_view: &TextView) {
     let text_view_clone3 = text_view.clone();
     let list_box_clone2 = list_box.clone();
     let targets_clone3 = targets.clone();
-    key_controller.connect_key_pressed(move |_, keyval, _, _| {
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
         if keyval == gtk::gdk::Key::Escape {
             popup_clone4.close();
             return gtk::glib::Propagation::Stop;
+        } else if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK)
+            && (keyval == gtk::gdk::Key::c || keyval == gtk::gdk::Key::C)
+        {
+            if let Some(target) = list_box_clone2.selected_row().and_then(|row| targets_clone3.get(row.index() as usize)) {
+                copy_target_to_clipboard(target);
+            }
+            popup_clone4.close();
+            return gtk::glib::Propagation::Stop;
         } else if keyval == gtk::gdk::Key::Return || keyval == gtk::gdk::Key::KP_Enter {
             if let Some(row) = list_box_clone2.selected_row() {
                 let index = row.index() as usize;
@@ -501,6 +2159,7 @@ pub fn show_target_selector_for_textview(text_view: &TextView) {
     popup.add_controller(key_controller);
 
     button_box.append(&cancel_btn);
+    button_box.append(&copy_btn);
     button_box.append(&insert_btn);
 
     popup_box.append(&scrolled);
@@ -511,16 +2170,252 @@ pub fn show_target_selector_for_textview(text_view: &TextView) {
     popup.present();
 }
 
-/// Applies markdown syntax highlighting to a text view
-pub fn apply_markdown_highlighting(text_view: &TextView) {
-    let buffer = text_view.buffer();
-    let start = buffer.start_iter();
-    let end = buffer.end_iter();
-    let text = buffer.text(&start, &end, false);
+/// Creates the label showing whether the latest edits have been written to disk
+pub fn create_save_state_label() -> Label {
+    let label = Label::new(Some("Saved"));
+    label.add_css_class("caption");
+    label.add_css_class("dim-label");
+    label.set_margin_end(6);
+    label
+}
+
+/// Save state shown while a background write is running
+const SAVING_TEXT: &str = "Saving…";
+
+/// Marks the save state label as saved (with the time of the write) or as having pending edits
+pub fn set_save_state(label: &Label, saved: bool) {
+    label.remove_css_class("error");
+    if saved {
+        label.set_text(&format!("Saved {}", chrono::Local::now().format("%H:%M:%S")));
+        label.set_tooltip_text(Some("All changes are written to disk"));
+        label.remove_css_class("warning");
+        label.add_css_class("dim-label");
+    } else {
+        label.set_text("Unsaved");
+        label.set_tooltip_text(Some("Changes are saved shortly after you stop typing"));
+        label.remove_css_class("dim-label");
+        label.add_css_class("warning");
+    }
+}
+
+/// Writes editor text to disk and updates the save state label to match
+pub fn save_editor_text<P: AsRef<Path>>(path: P, text: &str, save_state: &Label) {
+    let path = path.as_ref();
+    // Queue behind a background write of the same file so the older text can't land last
+    if WRITES_IN_FLIGHT.with(|writes| writes.borrow().contains_key(path)) {
+        save_editor_text_async(path, text, save_state);
+        return;
+    }
+    match atomic_write(path, text) {
+        Ok(()) => {
+            mark_notes_saved(path, text);
+            set_save_state(save_state, true);
+        }
+        Err(e) => show_save_error(path, &e.to_string(), save_state),
+    }
+}
+
+/// Writes notes in the background so a slow or network filesystem never stalls typing
+pub fn save_editor_text_async<P: AsRef<Path>>(path: P, text: &str, save_state: &Label) {
+    let path = path.as_ref().to_path_buf();
+    save_state.set_text(SAVING_TEXT);
+
+    // One write per file at a time; only the newest waiting text is kept
+    let queued = WRITES_IN_FLIGHT.with(|writes| {
+        let mut writes = writes.borrow_mut();
+        match writes.get_mut(&path) {
+            Some(pending) => {
+                *pending = Some((text.to_string(), save_state.clone()));
+                true
+            }
+            None => {
+                writes.insert(path.clone(), None);
+                false
+            }
+        }
+    });
+    if queued {
+        return;
+    }
+
+    let save_state = save_state.clone();
+    let written = text.to_string();
+    let file = gtk::gio::File::for_path(&path);
+    file.replace_contents_async(
+        text.to_string(),
+        None,
+        false,
+        gtk::gio::FileCreateFlags::NONE,
+        gtk::gio::Cancellable::NONE,
+        move |result| {
+            let pending = WRITES_IN_FLIGHT.with(|writes| writes.borrow_mut().remove(&path).flatten());
+            if result.is_ok() {
+                mark_notes_saved(&path, &written);
+            }
+            match result {
+                // Edits made while writing have already marked the label unsaved
+                Ok(_) if save_state.text().as_str() == SAVING_TEXT => set_save_state(&save_state, true),
+                Ok(_) => {}
+                Err((_, e)) => show_save_error(&path, &e.to_string(), &save_state),
+            }
+            if let Some((text, save_state)) = pending {
+                save_editor_text_async(&path, &text, &save_state);
+            }
+        },
+    );
+}
+
+fn show_save_error(path: &Path, error: &str, save_state: &Label) {
+    log::warn!("Failed to save {}: {}", path.display(), error);
+    save_state.set_text("Save failed");
+    save_state.set_tooltip_text(Some(&format!("Failed to save {}: {}", path.display(), error)));
+    save_state.remove_css_class("dim-label");
+    save_state.remove_css_class("warning");
+    save_state.add_css_class("error");
+}
+
+/// Tag used for find-bar match highlights; preserved across markdown re-highlighting
+const SEARCH_MATCH_TAG: &str = "search_match";
+
+/// Pause after an edit before find bar matches are highlighted again
+const SEARCH_RESCAN_DELAY_MS: u64 = 300;
+
+/// Colors used by the markdown highlighting tags
+pub(crate) struct MarkdownPalette {
+    pub(crate) heading: &'static str,
+    pub(crate) code: &'static str,
+    pub(crate) code_background: &'static str,
+    pub(crate) code_block: &'static str,
+    pub(crate) code_block_background: &'static str,
+    pub(crate) link: &'static str,
+    pub(crate) list: &'static str,
+    pub(crate) blockquote: &'static str,
+    pub(crate) muted: &'static str,
+    pub(crate) search_match: &'static str,
+    pub(crate) table_background: &'static str,
+}
+
+impl MarkdownPalette {
+    const DARK: Self = Self {
+        heading: "#4EC9B0",
+        code: "#CE9178",
+        code_background: "#2D2D2D",
+        code_block: "#D4D4D4",
+        code_block_background: "#1E1E1E",
+        link: "#569CD6",
+        list: "#DCDCAA",
+        blockquote: "#6A9955",
+        muted: "#808080",
+        search_match: "#613214",
+        table_background: "#252526",
+    };
+
+    const LIGHT: Self = Self {
+        heading: "#267F99",
+        code: "#A31515",
+        code_background: "#F0F0F0",
+        code_block: "#1E1E1E",
+        code_block_background: "#F5F5F5",
+        link: "#0066CC",
+        list: "#795E26",
+        blockquote: "#008000",
+        muted: "#8A8A8A",
+        search_match: "#FFE082",
+        table_background: "#F7F7F7",
+    };
+
+    /// Picks the palette matching the active libadwaita color scheme
+    pub(crate) fn current() -> &'static Self {
+        if adw::StyleManager::default().is_dark() {
+            &Self::DARK
+        } else {
+            &Self::LIGHT
+        }
+    }
+}
+
+/// Updates the colors of existing markdown tags to match the active palette
+fn sync_markdown_tag_colors(buffer: &gtk::TextBuffer) {
+    let palette = MarkdownPalette::current();
+    let tag_table = buffer.tag_table();
+
+    for level in 1..=6 {
+        if let Some(tag) = tag_table.lookup(&format!("h{}", level)) {
+            tag.set_foreground(Some(palette.heading));
+        }
+    }
+    if let Some(tag) = tag_table.lookup("code") {
+        tag.set_foreground(Some(palette.code));
+        tag.set_background(Some(palette.code_background));
+    }
+    if let Some(tag) = tag_table.lookup("code_block") {
+        tag.set_foreground(Some(palette.code_block));
+        tag.set_background(Some(palette.code_block_background));
+        tag.set_paragraph_background(Some(palette.code_block_background));
+    }
+    if let Some(tag) = tag_table.lookup("link") {
+        tag.set_foreground(Some(palette.link));
+    }
+    if let Some(tag) = tag_table.lookup("list") {
+        tag.set_foreground(Some(palette.list));
+    }
+    if let Some(tag) = tag_table.lookup("blockquote") {
+        tag.set_foreground(Some(palette.blockquote));
+    }
+    if let Some(tag) = tag_table.lookup("task_box") {
+        tag.set_foreground(Some(palette.list));
+    }
+    if let Some(tag) = tag_table.lookup(SEARCH_MATCH_TAG) {
+        tag.set_background(Some(palette.search_match));
+    }
+    if let Some(tag) = tag_table.lookup("table") {
+        tag.set_paragraph_background(Some(palette.table_background));
+    }
+    for name in ["table_border", "task_done"] {
+        if let Some(tag) = tag_table.lookup(name) {
+            tag.set_foreground(Some(palette.muted));
+        }
+    }
+}
 
-    buffer.remove_all_tags(&start, &end);
+/// Recolors markdown highlighting in all notes views and previews after a color scheme change.
+///
+/// Plain text views are included too, since the targets editor and log viewer carry
+/// find-bar highlight tags that would otherwise keep the previous theme's color.
+pub fn refresh_notes_colors() {
+    TEXT_VIEWS.with(|views| {
+        for view in views.borrow().iter() {
+            sync_markdown_tag_colors(&view.buffer());
+        }
+    });
+    NOTES_VIEWS.with(|views| {
+        for notes in views.borrow().iter() {
+            sync_markdown_tag_colors(&notes.view.buffer());
+        }
+    });
+    refresh_preview_colors();
+}
+
+/// Whether a tag is markdown highlighting, as opposed to a find-bar match or spelling mark
+fn is_markdown_tag(tag: &gtk::TextTag) -> bool {
+    !matches!(tag.name().as_deref(), Some(SEARCH_MATCH_TAG) | Some(MISSPELLED_TAG))
+}
+
+/// Removes markdown highlighting between two offsets
+fn clear_markdown_tags(buffer: &gtk::TextBuffer, start: i32, end: i32) {
+    let start = buffer.iter_at_offset(start);
+    let end = buffer.iter_at_offset(end);
+    buffer.tag_table().foreach(|tag| {
+        if is_markdown_tag(tag) {
+            buffer.remove_tag(tag, &start, &end);
+        }
+    });
+}
 
+/// Creates the markdown tags in a buffer's tag table if they don't exist yet
+fn ensure_markdown_tags(buffer: &gtk::TextBuffer) {
     let tag_table = buffer.tag_table();
+    let palette = MarkdownPalette::current();
 
     // Create tags if they don't exist
     for level in 1..=6 {
@@ -529,7 +2424,7 @@ pub fn apply_markdown_highlighting(text_view: &TextView) {
             buffer.create_tag(
                 Some(&tag_name),
                 &[
-                    ("foreground", &"#4EC9B0"),
+                    ("foreground", &palette.heading),
                     ("weight", &700),
                     ("scale", &(1.5 - (level as f64 * 0.1))),
                 ],
@@ -549,9 +2444,9 @@ pub fn apply_markdown_highlighting(text_view: &TextView) {
         buffer.create_tag(
             Some("code"),
             &[
-                ("foreground", &"#CE9178"),
+                ("foreground", &palette.code),
                 ("family", &"monospace"),
-                ("background", &"#2D2D2D"),
+                ("background", &palette.code_background),
             ],
         );
     }
@@ -560,10 +2455,10 @@ pub fn apply_markdown_highlighting(text_view: &TextView) {
         buffer.create_tag(
             Some("code_block"),
             &[
-                ("foreground", &"#D4D4D4"),
+                ("foreground", &palette.code_block),
                 ("family", &"monospace"),
-                ("background", &"#1E1E1E"),
-                ("paragraph-background", &"#1E1E1E"),
+                ("background", &palette.code_block_background),
+                ("paragraph-background", &palette.code_block_background),
             ],
         );
     }
@@ -572,115 +2467,330 @@ pub fn apply_markdown_highlighting(text_view: &TextView) {
         buffer.create_tag(
             Some("link"),
             &[
-                ("foreground", &"#569CD6"),
+                ("foreground", &palette.link),
                 ("underline", &gtk::pango::Underline::Single),
             ],
         );
     }
 
     if tag_table.lookup("list").is_none() {
-        buffer.create_tag(Some("list"), &[("foreground", &"#DCDCAA")]);
+        buffer.create_tag(Some("list"), &[("foreground", &palette.list)]);
     }
 
     if tag_table.lookup("blockquote").is_none() {
         buffer.create_tag(
             Some("blockquote"),
             &[
-                ("foreground", &"#6A9955"),
+                ("foreground", &palette.blockquote),
                 ("style", &gtk::pango::Style::Italic),
             ],
         );
     }
 
-    // Apply tags
-    let lines: Vec<&str> = text.split('\n').collect();
-    let mut current_pos = 0i32;
-    let mut in_code_block = false;
+    if tag_table.lookup("strikethrough").is_none() {
+        buffer.create_tag(Some("strikethrough"), &[("strikethrough", &true)]);
+    }
 
-    for line in lines {
-        let line_start = current_pos;
-        let line_end = current_pos + line.len() as i32;
+    if tag_table.lookup("table").is_none() {
+        buffer.create_tag(
+            Some("table"),
+            &[
+                ("family", &"monospace"),
+                ("paragraph-background", &palette.table_background),
+            ],
+        );
+    }
 
-        if line.trim_start().starts_with("```") {
-            in_code_block = !in_code_block;
-            let mut start_iter = buffer.iter_at_offset(line_start);
-            let mut end_iter = buffer.iter_at_offset(line_end);
-            buffer.apply_tag_by_name("code_block", &mut start_iter, &mut end_iter);
-        } else if in_code_block {
+    if tag_table.lookup("table_border").is_none() {
+        buffer.create_tag(Some("table_border"), &[("foreground", &palette.muted)]);
+    }
+
+    if tag_table.lookup("task_box").is_none() {
+        buffer.create_tag(
+            Some("task_box"),
+            &[
+                ("foreground", &palette.list),
+                ("family", &"monospace"),
+                ("weight", &700),
+            ],
+        );
+    }
+
+    if tag_table.lookup("task_done").is_none() {
+        buffer.create_tag(
+            Some("task_done"),
+            &[
+                ("foreground", &palette.muted),
+                ("strikethrough", &true),
+            ],
+        );
+    }
+}
+
+/// Whether a line opens or closes a fenced code block
+fn is_code_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Char index of the first occurrence of `pattern` in `chars`
+fn find_char_seq(chars: &[char], pattern: &[char]) -> Option<usize> {
+    chars.windows(pattern.len()).position(|window| window == pattern)
+}
+
+/// Highlights a single line starting at char offset `line_start`.
+///
+/// Returns whether the next line is inside a fenced code block.
+fn highlight_markdown_line(buffer: &gtk::TextBuffer, line_start: i32, line: &str, mut in_code_block: bool, in_table: bool) -> bool {
+    let line_end = line_start + line.chars().count() as i32;
+
+    if is_code_fence(line) {
+        in_code_block = !in_code_block;
+        let mut start_iter = buffer.iter_at_offset(line_start);
+        let mut end_iter = buffer.iter_at_offset(line_end);
+        buffer.apply_tag_by_name("code_block", &mut start_iter, &mut end_iter);
+    } else if in_code_block {
+        let mut start_iter = buffer.iter_at_offset(line_start);
+        let mut end_iter = buffer.iter_at_offset(line_end);
+        buffer.apply_tag_by_name("code_block", &mut start_iter, &mut end_iter);
+    } else {
+        // Headers
+        if line.starts_with('#') {
+            if let Some(level) = heading_level(line) {
+                let mut start_iter = buffer.iter_at_offset(line_start);
+                let mut end_iter = buffer.iter_at_offset(line_end);
+                buffer.apply_tag_by_name(&format!("h{}", level), &mut start_iter, &mut end_iter);
+            }
+        } else if in_table {
+            // Pipe tables: monospace the block so columns line up, dim the borders
             let mut start_iter = buffer.iter_at_offset(line_start);
             let mut end_iter = buffer.iter_at_offset(line_end);
-            buffer.apply_tag_by_name("code_block", &mut start_iter, &mut end_iter);
-        } else {
-            // Headers
-            if line.starts_with('#') {
-                let level = line.chars().take_while(|&c| c == '#').count();
-                if level <= 6 && line.len() > level && line.chars().nth(level) == Some(' ') {
-                    let mut start_iter = buffer.iter_at_offset(line_start);
-                    let mut end_iter = buffer.iter_at_offset(line_end);
-                    buffer.apply_tag_by_name(&format!("h{}", level), &mut start_iter, &mut end_iter);
-                }
-            } else if line.trim_start().starts_with('>') {
+            buffer.apply_tag_by_name("table", &mut start_iter, &mut end_iter);
+            if is_table_separator(line) {
                 let mut start_iter = buffer.iter_at_offset(line_start);
                 let mut end_iter = buffer.iter_at_offset(line_end);
-                buffer.apply_tag_by_name("blockquote", &mut start_iter, &mut end_iter);
-            } else if line.trim_start().starts_with('-') || line.trim_start().starts_with('*') || line.trim_start().starts_with('+') {
-                if let Some(marker_pos) = line.find(|c| c == '-' || c == '*' || c == '+') {
-                    let mut start_iter = buffer.iter_at_offset(line_start + marker_pos as i32);
-                    let mut end_iter = buffer.iter_at_offset(line_start + marker_pos as i32 + 1);
-                    buffer.apply_tag_by_name("list", &mut start_iter, &mut end_iter);
+                buffer.apply_tag_by_name("table_border", &mut start_iter, &mut end_iter);
+            } else {
+                for (pos, _) in line.chars().enumerate().filter(|&(_, c)| c == '|') {
+                    let mut start_iter = buffer.iter_at_offset(line_start + pos as i32);
+                    let mut end_iter = buffer.iter_at_offset(line_start + pos as i32 + 1);
+                    buffer.apply_tag_by_name("table_border", &mut start_iter, &mut end_iter);
                 }
             }
+        } else if let Some((bracket_byte, checked)) = find_task_checkbox(line) {
+            let bracket_pos = line[..bracket_byte].chars().count();
+            let mut start_iter = buffer.iter_at_offset(line_start + bracket_pos as i32);
+            let mut end_iter = buffer.iter_at_offset(line_start + bracket_pos as i32 + 3);
+            buffer.apply_tag_by_name("task_box", &mut start_iter, &mut end_iter);
+            if checked {
+                let mut start_iter = buffer.iter_at_offset(line_start + bracket_pos as i32 + 3);
+                let mut end_iter = buffer.iter_at_offset(line_end);
+                buffer.apply_tag_by_name("task_done", &mut start_iter, &mut end_iter);
+            }
+        } else if line.trim_start().starts_with('>') {
+            let mut start_iter = buffer.iter_at_offset(line_start);
+            let mut end_iter = buffer.iter_at_offset(line_end);
+            buffer.apply_tag_by_name("blockquote", &mut start_iter, &mut end_iter);
+        } else if line.trim_start().starts_with('-') || line.trim_start().starts_with('*') || line.trim_start().starts_with('+') {
+            if let Some(marker_pos) = line.chars().position(|c| c == '-' || c == '*' || c == '+') {
+                let mut start_iter = buffer.iter_at_offset(line_start + marker_pos as i32);
+                let mut end_iter = buffer.iter_at_offset(line_start + marker_pos as i32 + 1);
+                buffer.apply_tag_by_name("list", &mut start_iter, &mut end_iter);
+            }
+        }
 
-            // Inline formatting
-            let mut i = 0;
-            let chars: Vec<char> = line.chars().collect();
-            while i < chars.len() {
-                // Bold
-                if i + 4 < chars.len() && ((chars[i] == '*' && chars[i+1] == '*') || (chars[i] == '_' && chars[i+1] == '_')) {
-                    if let Some(end_pos) = line[i+2..].find(if chars[i] == '*' { "**" } else { "__" }) {
-                        let mut start_iter = buffer.iter_at_offset(line_start + (i + 2) as i32);
-                        let mut end_iter = buffer.iter_at_offset(line_start + (i + 2 + end_pos) as i32);
-                        buffer.apply_tag_by_name("bold", &mut start_iter, &mut end_iter);
-                        i += end_pos + 4;
-                        continue;
-                    }
+        // Inline formatting
+        let mut i = 0;
+        let chars: Vec<char> = line.chars().collect();
+        while i < chars.len() {
+            // Strikethrough
+            if i + 4 < chars.len() && chars[i] == '~' && chars[i+1] == '~' {
+                if let Some(end_pos) = find_char_seq(&chars[i+2..], &['~', '~']) {
+                    let mut start_iter = buffer.iter_at_offset(line_start + (i + 2) as i32);
+                    let mut end_iter = buffer.iter_at_offset(line_start + (i + 2 + end_pos) as i32);
+                    buffer.apply_tag_by_name("strikethrough", &mut start_iter, &mut end_iter);
+                    i += end_pos + 4;
+                    continue;
                 }
-                // Italic
-                else if i + 2 < chars.len() && (chars[i] == '*' || chars[i] == '_') && chars[i+1] != chars[i] {
-                    if let Some(end_pos) = line[i+1..].find(chars[i]) {
-                        let mut start_iter = buffer.iter_at_offset(line_start + (i + 1) as i32);
-                        let mut end_iter = buffer.iter_at_offset(line_start + (i + 1 + end_pos) as i32);
-                        buffer.apply_tag_by_name("italic", &mut start_iter, &mut end_iter);
-                        i += end_pos + 2;
-                        continue;
-                    }
+            }
+            // Bold
+            if i + 4 < chars.len() && ((chars[i] == '*' && chars[i+1] == '*') || (chars[i] == '_' && chars[i+1] == '_')) {
+                if let Some(end_pos) = find_char_seq(&chars[i+2..], &[chars[i], chars[i]]) {
+                    let mut start_iter = buffer.iter_at_offset(line_start + (i + 2) as i32);
+                    let mut end_iter = buffer.iter_at_offset(line_start + (i + 2 + end_pos) as i32);
+                    buffer.apply_tag_by_name("bold", &mut start_iter, &mut end_iter);
+                    i += end_pos + 4;
+                    continue;
                 }
-                // Inline code
-                else if chars[i] == '`' {
-                    if let Some(end_pos) = line[i+1..].find('`') {
-                        let mut start_iter = buffer.iter_at_offset(line_start + (i + 1) as i32);
-                        let mut end_iter = buffer.iter_at_offset(line_start + (i + 1 + end_pos) as i32);
-                        buffer.apply_tag_by_name("code", &mut start_iter, &mut end_iter);
-                        i += end_pos + 2;
-                        continue;
-                    }
+            }
+            // Italic
+            else if i + 2 < chars.len() && (chars[i] == '*' || chars[i] == '_') && chars[i+1] != chars[i] {
+                if let Some(end_pos) = chars[i+1..].iter().position(|&c| c == chars[i]) {
+                    let mut start_iter = buffer.iter_at_offset(line_start + (i + 1) as i32);
+                    let mut end_iter = buffer.iter_at_offset(line_start + (i + 1 + end_pos) as i32);
+                    buffer.apply_tag_by_name("italic", &mut start_iter, &mut end_iter);
+                    i += end_pos + 2;
+                    continue;
                 }
-                // Links
-                else if chars[i] == '[' {
-                    if let Some(bracket_end) = line[i..].find("](") {
-                        if let Some(paren_end) = line[i+bracket_end..].find(')') {
-                            let mut start_iter = buffer.iter_at_offset(line_start + i as i32);
-                            let mut end_iter = buffer.iter_at_offset(line_start + (i + bracket_end + paren_end + 1) as i32);
-                            buffer.apply_tag_by_name("link", &mut start_iter, &mut end_iter);
-                            i += bracket_end + paren_end + 1;
-                            continue;
-                        }
+            }
+            // Inline code
+            else if chars[i] == '`' {
+                if let Some(end_pos) = chars[i+1..].iter().position(|&c| c == '`') {
+                    let mut start_iter = buffer.iter_at_offset(line_start + (i + 1) as i32);
+                    let mut end_iter = buffer.iter_at_offset(line_start + (i + 1 + end_pos) as i32);
+                    buffer.apply_tag_by_name("code", &mut start_iter, &mut end_iter);
+                    i += end_pos + 2;
+                    continue;
+                }
+            }
+            // Links
+            else if chars[i] == '[' {
+                if let Some(bracket_end) = find_char_seq(&chars[i..], &[']', '(']) {
+                    if let Some(paren_end) = chars[i+bracket_end..].iter().position(|&c| c == ')') {
+                        let mut start_iter = buffer.iter_at_offset(line_start + i as i32);
+                        let mut end_iter = buffer.iter_at_offset(line_start + (i + bracket_end + paren_end + 1) as i32);
+                        buffer.apply_tag_by_name("link", &mut start_iter, &mut end_iter);
+                        i += bracket_end + paren_end + 1;
+                        continue;
                     }
                 }
-                i += 1;
             }
+            i += 1;
+        }
+    }
+
+    in_code_block
+}
+
+/// Applies markdown syntax highlighting to a whole text view, e.g. after loading a file.
+///
+/// Edits to notes views are re-highlighted line by line by `add_incremental_highlighting`.
+pub fn apply_markdown_highlighting(text_view: &TextView) {
+    let buffer = text_view.buffer();
+    ensure_markdown_tags(&buffer);
+    clear_markdown_tags(&buffer, 0, buffer.char_count());
+
+    let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+    let lines: Vec<&str> = text.split('\n').collect();
+    let table_lines = table_block_lines(&lines);
+    let mut line_start = 0i32;
+    let mut in_code_block = false;
+
+    for (line_index, line) in lines.iter().enumerate() {
+        in_code_block = highlight_markdown_line(&buffer, line_start, line, in_code_block, table_lines[line_index]);
+        line_start += line.chars().count() as i32 + 1;
+    }
+}
+
+/// Re-highlights lines `first..=last` after an edit.
+///
+/// The range grows to cover a whole pipe table around the edit, and continues past it
+/// while the edit changed which of the following lines are inside a fenced code block.
+fn highlight_markdown_lines(buffer: &gtk::TextBuffer, first: i32, last: i32) {
+    ensure_markdown_tags(buffer);
+
+    let line_count = buffer.line_count();
+    let mut first = first.clamp(0, line_count - 1);
+    let mut last = last.clamp(0, line_count - 1);
+
+    // Whether a row is part of a table depends on its neighbours
+    let is_row = |line: &str| line.trim_start().starts_with('|');
+    while first > 0 && is_row(&buffer_line_text(buffer, first - 1)) {
+        first -= 1;
+    }
+    while last < line_count - 1 && is_row(&buffer_line_text(buffer, last + 1)) {
+        last += 1;
+    }
+
+    let code_block_tag = buffer.tag_table().lookup("code_block");
+    let mut in_code_block = code_block_tag
+        .as_ref()
+        .is_some_and(|tag| code_block_after_line(buffer, tag, first - 1));
+    // The line before `first` is never a table row, so no table is open yet
+    let mut in_table = false;
+    let mut line = buffer_line_text(buffer, first);
+
+    for line_index in first..line_count {
+        let Some(line_iter) = buffer.iter_at_line(line_index) else {
+            break;
+        };
+        if line_index > last && !line.is_empty() && !is_code_fence(&line) {
+            // The old highlighting is still right from the first line that agrees on the code block state
+            let was_code = code_block_tag.as_ref().is_some_and(|tag| line_iter.has_tag(tag));
+            if was_code == in_code_block {
+                break;
+            }
+        }
+
+        let next = (line_index + 1 < line_count).then(|| buffer_line_text(buffer, line_index + 1));
+        in_table = is_row(&line)
+            && (in_table || (!is_table_separator(&line) && next.as_deref().is_some_and(is_table_separator)));
+
+        let line_start = line_iter.offset();
+        let line_len = line.chars().count() as i32;
+        clear_markdown_tags(buffer, line_start, line_start + line_len + 1);
+        in_code_block = highlight_markdown_line(buffer, line_start, &line, in_code_block, in_table);
+
+        match next {
+            Some(next) => line = next,
+            None => break,
         }
+    }
+}
+
+/// Text of a buffer line without its line break
+fn buffer_line_text(buffer: &gtk::TextBuffer, line: i32) -> String {
+    let Some(line_start) = buffer.iter_at_line(line) else {
+        return String::new();
+    };
+    let mut line_end = line_start;
+    if !line_end.ends_line() {
+        line_end.forward_to_line_end();
+    }
+    buffer.text(&line_start, &line_end, false).to_string()
+}
 
-        current_pos = line_end + 1;
+/// Whether the line after `line` is inside a fenced code block, judging by the existing tags.
+///
+/// Fences are tagged whether they open or close a block and empty lines carry no tag,
+/// so those are skipped back to the nearest content line and the fences in between counted.
+fn code_block_after_line(buffer: &gtk::TextBuffer, code_block_tag: &gtk::TextTag, mut line: i32) -> bool {
+    let mut fences = 0;
+    while line >= 0 {
+        let text = buffer_line_text(buffer, line);
+        if is_code_fence(&text) {
+            fences += 1;
+        } else if !text.is_empty() {
+            let was_code = buffer.iter_at_line(line).is_some_and(|iter| iter.has_tag(code_block_tag));
+            return was_code != (fences % 2 == 1);
+        }
+        line -= 1;
     }
+    fences % 2 == 1
+}
+
+/// Re-highlights only the lines each edit of a notes view touches.
+///
+/// insert-text and delete-range run before the buffer changes and record which lines
+/// the edit will cover; the changed signal that follows highlights just those.
+fn add_incremental_highlighting(text_view: &TextView) {
+    let buffer = text_view.buffer();
+    let dirty: Rc<Cell<Option<(i32, i32)>>> = Rc::new(Cell::new(None));
+
+    let dirty_clone = Rc::clone(&dirty);
+    buffer.connect_insert_text(move |_, location, text| {
+        let first = location.line();
+        dirty_clone.set(Some((first, first + text.matches('\n').count() as i32)));
+    });
+
+    // A deleted range collapses into the line it started on
+    let dirty_clone2 = Rc::clone(&dirty);
+    buffer.connect_delete_range(move |_, start, _| {
+        dirty_clone2.set(Some((start.line(), start.line())));
+    });
+
+    buffer.connect_changed(move |buffer| {
+        if let Some((first, last)) = dirty.take() {
+            highlight_markdown_lines(buffer, first, last);
+        }
+    });
 }
@@ -0,0 +1,207 @@
+//! Findings tab for PenEnv
+//!
+//! Lists the findings from `findings.yaml` with add, edit and delete actions.
+
+use gtk4::prelude::*;
+use gtk4::{self as gtk, Box as GtkBox, Button, Label, ListBox, Orientation, ScrolledWindow};
+use libadwaita::{self as adw, prelude::*};
+
+use crate::findings::{delete_finding, load_findings, Finding, FindingStatus, Severity};
+use crate::ui::dialogs::show_finding_dialog;
+use crate::ui::window::show_error_toast;
+
+/// CSS class used to color a severity badge
+fn severity_css_class(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "accent",
+        Severity::Info => "dim-label",
+    }
+}
+
+/// Summary shown above the list, e.g. "4 findings · 2 open"
+fn findings_summary(findings: &[Finding]) -> String {
+    let open = findings.iter().filter(|f| f.status != FindingStatus::Remediated).count();
+    match findings.len() {
+        0 => "No findings yet".to_string(),
+        1 => format!("1 finding · {} unresolved", open),
+        n => format!("{} findings · {} unresolved", n, open),
+    }
+}
+
+/// Rebuilds the list from findings.yaml
+fn populate_findings(list_box: &ListBox, summary_label: &Label) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let findings = load_findings();
+    summary_label.set_text(&findings_summary(&findings));
+
+    if findings.is_empty() {
+        let empty_row = gtk::ListBoxRow::new();
+        let empty_label = Label::new(Some("Add a finding to start tracking issues for the report"));
+        empty_label.add_css_class("dim-label");
+        empty_label.set_margin_top(12);
+        empty_label.set_margin_bottom(12);
+        empty_row.set_child(Some(&empty_label));
+        list_box.append(&empty_row);
+        return;
+    }
+
+    for (idx, finding) in findings.into_iter().enumerate() {
+        let description = finding.description.lines().next().unwrap_or_default().to_string();
+        let row = adw::ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&finding.title))
+            .subtitle(gtk::glib::markup_escape_text(&description))
+            .subtitle_lines(1)
+            .build();
+
+        let severity_label = Label::new(Some(finding.severity.label()));
+        severity_label.add_css_class("heading");
+        severity_label.add_css_class(severity_css_class(finding.severity));
+        severity_label.set_width_chars(8);
+        severity_label.set_xalign(0.0);
+        row.add_prefix(&severity_label);
+
+        let status_label = Label::new(Some(finding.status.label()));
+        status_label.add_css_class("dim-label");
+        status_label.add_css_class("caption");
+        row.add_suffix(&status_label);
+
+        let edit_btn = Button::builder()
+            .icon_name("document-edit-symbolic")
+            .tooltip_text("Edit")
+            .valign(gtk::Align::Center)
+            .build();
+        edit_btn.add_css_class("flat");
+
+        let list_box_clone = list_box.clone();
+        let summary_clone = summary_label.clone();
+        let finding_clone = finding.clone();
+        edit_btn.connect_clicked(move |btn| {
+            let parent = btn.root().and_downcast::<gtk::Window>();
+            let list_box_ref = list_box_clone.clone();
+            let summary_ref = summary_clone.clone();
+            show_finding_dialog(parent.as_ref(), Some((idx, finding_clone.clone())), move || {
+                populate_findings(&list_box_ref, &summary_ref);
+            });
+        });
+
+        let delete_btn = Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text("Delete")
+            .valign(gtk::Align::Center)
+            .build();
+        delete_btn.add_css_class("flat");
+        delete_btn.add_css_class("error");
+
+        let list_box_clone2 = list_box.clone();
+        let summary_clone2 = summary_label.clone();
+        let title = finding.title.clone();
+        delete_btn.connect_clicked(move |btn| {
+            confirm_delete_finding(btn, idx, &title, &list_box_clone2, &summary_clone2);
+        });
+
+        row.add_suffix(&edit_btn);
+        row.add_suffix(&delete_btn);
+        list_box.append(&row);
+    }
+}
+
+/// Asks before deleting a finding, then refreshes the list
+fn confirm_delete_finding(button: &Button, index: usize, title: &str, list_box: &ListBox, summary_label: &Label) {
+    let mut builder = gtk::MessageDialog::builder()
+        .modal(true)
+        .buttons(gtk::ButtonsType::None)
+        .text("Delete Finding?")
+        .secondary_text(&format!("\"{}\" will be removed from findings.yaml.", title));
+    if let Some(window) = button.root().and_downcast::<gtk::Window>() {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
+
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    let delete_btn = dialog.add_button("Delete", gtk::ResponseType::Accept);
+    delete_btn.add_css_class("destructive-action");
+
+    let list_box_clone = list_box.clone();
+    let summary_clone = summary_label.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response == gtk::ResponseType::Accept {
+            match delete_finding(index) {
+                Ok(()) => populate_findings(&list_box_clone, &summary_clone),
+                Err(e) => show_error_toast(&format!("Failed to delete finding: {}", e)),
+            }
+        }
+        dialog.close();
+    });
+
+    dialog.show();
+}
+
+/// Creates the findings tab; the list is reloaded whenever the tab is shown
+pub fn create_findings_tab() -> GtkBox {
+    let container = GtkBox::new(Orientation::Vertical, 0);
+    container.set_margin_top(6);
+    container.set_margin_bottom(6);
+    container.set_margin_start(6);
+    container.set_margin_end(6);
+    container.set_widget_name("findings");
+
+    // Toolbar with summary and add button
+    let toolbar = GtkBox::new(Orientation::Horizontal, 6);
+    toolbar.set_margin_bottom(6);
+
+    let summary_label = Label::new(None);
+    summary_label.add_css_class("dim-label");
+    summary_label.set_hexpand(true);
+    summary_label.set_halign(gtk::Align::Start);
+    summary_label.set_margin_start(6);
+
+    let add_btn = Button::builder()
+        .icon_name("list-add-symbolic")
+        .tooltip_text("Add Finding")
+        .build();
+    add_btn.add_css_class("flat");
+
+    toolbar.append(&summary_label);
+    toolbar.append(&add_btn);
+
+    let list_box = ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::None);
+    list_box.add_css_class("boxed-list");
+    list_box.set_valign(gtk::Align::Start);
+    list_box.set_margin_start(6);
+    list_box.set_margin_end(6);
+
+    let scrolled = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .vexpand(true)
+        .child(&list_box)
+        .build();
+
+    let list_box_clone = list_box.clone();
+    let summary_clone = summary_label.clone();
+    add_btn.connect_clicked(move |btn| {
+        let parent = btn.root().and_downcast::<gtk::Window>();
+        let list_box_ref = list_box_clone.clone();
+        let summary_ref = summary_clone.clone();
+        show_finding_dialog(parent.as_ref(), None, move || {
+            populate_findings(&list_box_ref, &summary_ref);
+        });
+    });
+
+    // Pick up edits made outside PenEnv each time the tab is shown
+    let list_box_clone2 = list_box.clone();
+    let summary_clone2 = summary_label.clone();
+    container.connect_map(move |_| {
+        populate_findings(&list_box_clone2, &summary_clone2);
+    });
+
+    container.append(&toolbar);
+    container.append(&scrolled);
+    container
+}
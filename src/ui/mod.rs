@@ -4,8 +4,12 @@
 
 pub mod dialogs;
 pub mod editor;
+pub mod findings;
+pub mod preview;
+pub mod spellcheck;
 pub mod terminal;
 pub mod drawer;
+pub mod palette;
 pub mod window;
 pub mod browser;
 pub mod container;
@@ -0,0 +1,317 @@
+//! Command palette for PenEnv
+//!
+//! A searchable popover (Ctrl+Shift+P) listing built-in actions, open tabs,
+//! command templates and targets, so everything is reachable from the keyboard.
+//! Queries are fuzzy-matched, so "nmp" finds "Nmap Scan", and the best matches
+//! are listed first.
+
+use gtk4::prelude::*;
+use gtk4::{self as gtk, Box as GtkBox, Label, Notebook, Orientation};
+use libadwaita as adw;
+use vte4::TerminalExt;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::commands::{load_command_templates, CommandTemplate};
+use crate::config::{load_targets, shell_quote};
+use crate::ui::terminal::{find_terminal_in, run_command_template};
+
+/// A built-in action offered in the palette, dispatched through the same handler as its button
+#[derive(Clone)]
+pub struct PaletteAction {
+    pub name: String,
+    pub run: Rc<dyn Fn()>,
+}
+
+impl PaletteAction {
+    pub fn new(name: &str, run: impl Fn() + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            run: Rc::new(run),
+        }
+    }
+}
+
+/// An entry in the palette list
+enum PaletteEntry {
+    Action(PaletteAction),
+    Tab(u32, String),
+    Command(CommandTemplate),
+    Target(String),
+}
+
+impl PaletteEntry {
+    fn title(&self) -> String {
+        match self {
+            Self::Action(action) => action.name.clone(),
+            Self::Tab(_, label) => format!("Go to {}", label),
+            Self::Command(cmd) => cmd.name.clone(),
+            Self::Target(target) => target.clone(),
+        }
+    }
+
+    fn subtitle(&self) -> String {
+        match self {
+            Self::Action(_) => "Action".to_string(),
+            Self::Tab(..) => "Tab".to_string(),
+            Self::Command(cmd) => format!("Command · {} · {}", cmd.category, cmd.command),
+            Self::Target(_) => "Insert target".to_string(),
+        }
+    }
+}
+
+/// Scores one query word against text: contiguous matches beat scattered ones
+///
+/// Returns None when the word's letters don't appear in order.
+fn fuzzy_word_score(text: &str, word: &str) -> Option<i32> {
+    if let Some(pos) = text.find(word) {
+        // Prefer matches at the start of the text or of a word
+        let at_word_start = pos == 0 || !text[..pos].ends_with(|c: char| c.is_alphanumeric());
+        return Some(if at_word_start { 100 } else { 60 });
+    }
+
+    let mut chars = text.chars();
+    let mut gaps = 0;
+    for wanted in word.chars() {
+        let mut skipped = 0;
+        loop {
+            match chars.next() {
+                Some(c) if c == wanted => break,
+                Some(_) => skipped += 1,
+                None => return None,
+            }
+        }
+        gaps += skipped;
+    }
+    Some((40 - gaps).max(1))
+}
+
+/// Fuzzy-matches every word of the query, weighting title matches over the subtitle
+///
+/// Returns None if any word doesn't match, so the row is hidden.
+fn entry_score(entry: &PaletteEntry, query: &str) -> Option<i32> {
+    let title = entry.title().to_lowercase();
+    let subtitle = entry.subtitle().to_lowercase();
+    query.split_whitespace().try_fold(0, |total, word| {
+        let score = match (fuzzy_word_score(&title, word), fuzzy_word_score(&subtitle, word)) {
+            (Some(t), Some(s)) => (t * 2).max(s),
+            (Some(t), None) => t * 2,
+            (None, Some(s)) => s,
+            (None, None) => return None,
+        };
+        Some(total + score)
+    })
+}
+
+/// Gets the entry index stored in a palette row's widget name
+fn row_entry_index(row: &gtk::ListBoxRow) -> Option<usize> {
+    row.widget_name()
+        .strip_prefix("palette_")
+        .and_then(|idx| idx.parse::<usize>().ok())
+}
+
+/// Reads the text of a notebook tab label (the first child of the label box)
+fn tab_title(notebook: &Notebook, page: &gtk::Widget) -> String {
+    notebook
+        .tab_label(page)
+        .and_then(|tab| tab.first_child())
+        .and_then(|child| child.downcast::<Label>().ok())
+        .map(|label| label.text().to_string())
+        .unwrap_or_default()
+}
+
+/// Collects actions, open tabs, command templates and targets in display order
+fn collect_entries(notebook: &Notebook, actions: &[PaletteAction]) -> Vec<PaletteEntry> {
+    let mut entries: Vec<PaletteEntry> = actions.iter().cloned().map(PaletteEntry::Action).collect();
+
+    for page_num in 0..notebook.n_pages() {
+        if let Some(page) = notebook.nth_page(Some(page_num)) {
+            let title = tab_title(notebook, &page);
+            if !title.is_empty() {
+                entries.push(PaletteEntry::Tab(page_num, title));
+            }
+        }
+    }
+
+    entries.extend(load_command_templates().into_iter().map(PaletteEntry::Command));
+    entries.extend(load_targets().into_iter().map(PaletteEntry::Target));
+    entries
+}
+
+/// Runs the chosen entry; commands and targets go to the shell in the current tab
+fn activate_entry(entry: &PaletteEntry, notebook: &Notebook, toast: &adw::ToastOverlay) {
+    match entry {
+        PaletteEntry::Action(action) => (action.run)(),
+        PaletteEntry::Tab(page_num, _) => notebook.set_current_page(Some(*page_num)),
+        PaletteEntry::Command(_) | PaletteEntry::Target(_) => {
+            let terminal = notebook
+                .nth_page(notebook.current_page())
+                .and_then(|page| find_terminal_in(&page));
+            let Some(terminal) = terminal else {
+                toast.add_toast(adw::Toast::new("Switch to a shell tab to use commands and targets"));
+                return;
+            };
+            match entry {
+                PaletteEntry::Command(cmd) => run_command_template(&terminal, cmd, cmd.autorun),
+                PaletteEntry::Target(target) => {
+                    terminal.feed_child(shell_quote(&target).as_bytes());
+                    terminal.grab_focus();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Shows the first visible row as selected so Enter has an obvious target
+fn select_first_visible(list_box: &gtk::ListBox) {
+    let mut index = 0;
+    while let Some(row) = list_box.row_at_index(index) {
+        if row.is_visible() {
+            list_box.select_row(Some(&row));
+            return;
+        }
+        index += 1;
+    }
+    list_box.unselect_all();
+}
+
+/// Scrolls just enough to keep the selected row in view while focus stays in the search entry
+fn scroll_to_row(scrolled: &gtk::ScrolledWindow, list_box: &gtk::ListBox, row: &gtk::ListBoxRow) {
+    let Some(point) = row.compute_point(list_box, &gtk::graphene::Point::new(0.0, 0.0)) else {
+        return;
+    };
+    let adjustment = scrolled.vadjustment();
+    let top = point.y() as f64;
+    let bottom = top + row.height() as f64;
+    if top < adjustment.value() {
+        adjustment.set_value(top);
+    } else if bottom > adjustment.value() + adjustment.page_size() {
+        adjustment.set_value(bottom - adjustment.page_size());
+    }
+}
+
+/// Opens the command palette over the notebook
+pub fn show_command_palette(notebook: &Notebook, actions: &[PaletteAction], toast: &adw::ToastOverlay) {
+    let entries = Rc::new(collect_entries(notebook, actions));
+
+    let popover = gtk::Popover::new();
+    popover.set_has_arrow(false);
+    popover.set_parent(notebook);
+    popover.set_position(gtk::PositionType::Bottom);
+    popover.set_pointing_to(Some(&gtk::gdk::Rectangle::new(notebook.width() / 2, 0, 1, 1)));
+
+    let content = GtkBox::new(Orientation::Vertical, 6);
+    content.set_width_request(520);
+
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Search actions, tabs, commands and targets"));
+    content.append(&search_entry);
+
+    let list_box = gtk::ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::Single);
+    list_box.add_css_class("boxed-list");
+    for (idx, entry) in entries.iter().enumerate() {
+        let row = adw::ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&entry.title()))
+            .subtitle(gtk::glib::markup_escape_text(&entry.subtitle()))
+            .subtitle_lines(1)
+            .activatable(true)
+            .build();
+        row.set_widget_name(&format!("palette_{}", idx));
+        list_box.append(&row);
+    }
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(360)
+        .max_content_height(360)
+        .child(&list_box)
+        .build();
+    content.append(&scrolled);
+    popover.set_child(Some(&content));
+
+    // Best matches first; ties keep the original order
+    let scores: Rc<RefCell<Vec<i32>>> = Rc::new(RefCell::new(vec![0; entries.len()]));
+    let scores_clone = Rc::clone(&scores);
+    list_box.set_sort_func(move |a, b| {
+        let scores = scores_clone.borrow();
+        let (Some(a), Some(b)) = (row_entry_index(a), row_entry_index(b)) else {
+            return gtk::Ordering::Equal;
+        };
+        scores[b].cmp(&scores[a]).then(a.cmp(&b)).into()
+    });
+
+    // Filter and rank rows as the query changes
+    let list_box_clone = list_box.clone();
+    let entries_clone = Rc::clone(&entries);
+    let scores_clone2 = Rc::clone(&scores);
+    search_entry.connect_search_changed(move |entry| {
+        let query = entry.text().to_lowercase();
+        let mut index = 0;
+        while let Some(row) = list_box_clone.row_at_index(index) {
+            if let Some(idx) = row_entry_index(&row) {
+                let score = entries_clone.get(idx).and_then(|entry| entry_score(entry, &query));
+                row.set_visible(score.is_some());
+                scores_clone2.borrow_mut()[idx] = score.unwrap_or(0);
+            }
+            index += 1;
+        }
+        list_box_clone.invalidate_sort();
+        select_first_visible(&list_box_clone);
+    });
+
+    // Row activation runs the entry after the popover is gone
+    let popover_clone = popover.clone();
+    let entries_clone2 = Rc::clone(&entries);
+    let notebook_clone = notebook.clone();
+    let toast_clone = toast.clone();
+    list_box.connect_row_activated(move |_, row| {
+        let Some(idx) = row_entry_index(row) else {
+            return;
+        };
+        popover_clone.popdown();
+        if let Some(entry) = entries_clone2.get(idx) {
+            activate_entry(entry, &notebook_clone, &toast_clone);
+        }
+    });
+
+    // Enter runs the selected row, arrow keys move the selection
+    let list_box_clone2 = list_box.clone();
+    search_entry.connect_activate(move |_| {
+        if let Some(row) = list_box_clone2.selected_row() {
+            row.activate();
+        }
+    });
+
+    let key_controller = gtk::EventControllerKey::new();
+    let list_box_clone3 = list_box.clone();
+    let scrolled_clone = scrolled.clone();
+    key_controller.connect_key_pressed(move |_, keyval, _, _| {
+        let step: i32 = match keyval {
+            gtk::gdk::Key::Down => 1,
+            gtk::gdk::Key::Up => -1,
+            _ => return gtk::glib::Propagation::Proceed,
+        };
+        let current = list_box_clone3.selected_row().map(|row| row.index()).unwrap_or(-1);
+        let mut index = current + step;
+        while let Some(row) = list_box_clone3.row_at_index(index) {
+            if row.is_visible() {
+                list_box_clone3.select_row(Some(&row));
+                scroll_to_row(&scrolled_clone, &list_box_clone3, &row);
+                break;
+            }
+            index += step;
+        }
+        gtk::glib::Propagation::Stop
+    });
+    search_entry.add_controller(key_controller);
+
+    popover.connect_closed(|popover| {
+        popover.unparent();
+    });
+
+    select_first_visible(&list_box);
+    popover.popup();
+    search_entry.grab_focus();
+}
@@ -0,0 +1,316 @@
+//! Rendered markdown preview for notes editors
+//!
+//! Converts markdown text into a read-only TextView using text tags, so the
+//! preview follows the same palette and zoom as the editor.
+
+use gtk4::prelude::*;
+use gtk4::{self as gtk, TextView};
+use std::cell::RefCell;
+
+use crate::ui::editor::{find_task_checkbox, is_table_separator, MarkdownPalette};
+
+/// Inline styles recognised inside a line, paired with their delimiters
+const INLINE_STYLES: &[(&str, &str)] = &[
+    ("**", "preview_bold"),
+    ("__", "preview_bold"),
+    ("~~", "preview_strike"),
+    ("`", "preview_code"),
+    ("*", "preview_italic"),
+    ("_", "preview_italic"),
+];
+
+// Track preview views so they can be recolored on theme changes
+thread_local! {
+    static PREVIEW_VIEWS: RefCell<Vec<TextView>> = RefCell::new(Vec::new());
+}
+
+/// Creates a read-only TextView used as the rendered preview pane
+pub fn create_preview_view() -> TextView {
+    let preview = TextView::builder()
+        .editable(false)
+        .cursor_visible(false)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .left_margin(12)
+        .right_margin(12)
+        .top_margin(8)
+        .bottom_margin(8)
+        .build();
+    PREVIEW_VIEWS.with(|views| views.borrow_mut().push(preview.clone()));
+    preview
+}
+
+/// Updates the preview tag colors of all previews to the active palette
+pub fn refresh_preview_colors() {
+    PREVIEW_VIEWS.with(|views| {
+        for view in views.borrow().iter() {
+            ensure_preview_tags(&view.buffer());
+        }
+    });
+}
+
+/// Stops tracking previews that belonged to a closed tab
+pub fn unregister_previews(closed: &[TextView]) {
+    PREVIEW_VIEWS.with(|views| {
+        views.borrow_mut().retain(|v| !closed.contains(v));
+    });
+}
+
+/// Creates the preview tags, or updates their colors to the active palette
+fn ensure_preview_tags(buffer: &gtk::TextBuffer) {
+    let palette = MarkdownPalette::current();
+    let tag_table = buffer.tag_table();
+    let tag = |name: &str| -> gtk::TextTag {
+        tag_table.lookup(name).unwrap_or_else(|| {
+            let tag = gtk::TextTag::new(Some(name));
+            tag_table.add(&tag);
+            tag
+        })
+    };
+
+    for (level, scale) in [(1, 1.8), (2, 1.5), (3, 1.25), (4, 1.1)] {
+        let heading = tag(&format!("preview_h{}", level));
+        heading.set_scale(scale);
+        heading.set_weight(700);
+        heading.set_foreground(Some(palette.heading));
+        heading.set_pixels_above_lines(8);
+        heading.set_pixels_below_lines(4);
+    }
+
+    tag("preview_bold").set_weight(700);
+    tag("preview_italic").set_style(gtk::pango::Style::Italic);
+    tag("preview_strike").set_strikethrough(true);
+
+    let code = tag("preview_code");
+    code.set_family(Some("monospace"));
+    code.set_foreground(Some(palette.code));
+    code.set_background(Some(palette.code_background));
+
+    let code_block = tag("preview_code_block");
+    code_block.set_family(Some("monospace"));
+    code_block.set_foreground(Some(palette.code_block));
+    code_block.set_paragraph_background(Some(palette.code_block_background));
+    code_block.set_left_margin(24);
+
+    let table = tag("preview_table");
+    table.set_family(Some("monospace"));
+
+    let quote = tag("preview_quote");
+    quote.set_left_margin(28);
+    quote.set_style(gtk::pango::Style::Italic);
+    quote.set_foreground(Some(palette.blockquote));
+
+    let list = tag("preview_list");
+    list.set_left_margin(20);
+
+    let bullet = tag("preview_bullet");
+    bullet.set_foreground(Some(palette.list));
+
+    let link = tag("preview_link");
+    link.set_foreground(Some(palette.link));
+    link.set_underline(gtk::pango::Underline::Single);
+
+    let rule = tag("preview_rule");
+    rule.set_foreground(Some(palette.muted));
+}
+
+/// Returns true if an inline delimiter may open or close at this position
+fn is_flanking(before: Option<char>, after: Option<char>, delimiter: &str) -> bool {
+    let after_ok = after.is_some_and(|c| !c.is_whitespace());
+    // Underscores inside words (snake_case, file names) are not emphasis
+    let before_ok = !delimiter.starts_with('_') || before.is_none_or(|c| !c.is_alphanumeric());
+    after_ok && before_ok
+}
+
+/// Splits a line into plain and styled segments for inline markdown
+fn inline_segments(line: &str) -> Vec<(String, Option<&'static str>)> {
+    let mut segments: Vec<(String, Option<&'static str>)> = Vec::new();
+    let mut plain = String::new();
+    let mut previous: Option<char> = None;
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        let styled = INLINE_STYLES.iter().find_map(|(delimiter, tag)| {
+            let inner = rest.strip_prefix(delimiter)?;
+            if !is_flanking(previous, inner.chars().next(), delimiter) {
+                return None;
+            }
+            let end = inner.find(delimiter)?;
+            let text = &inner[..end];
+            if text.is_empty() || text.ends_with(char::is_whitespace) {
+                return None;
+            }
+            Some((text.to_string(), *tag, delimiter.len() * 2 + end))
+        });
+
+        // [text](url) links show only their text
+        let link = if styled.is_none() && rest.starts_with('[') {
+            rest.find("](").and_then(|close| {
+                let paren = rest[close + 2..].find(')')?;
+                Some((rest[1..close].to_string(), "preview_link", close + 3 + paren))
+            })
+        } else {
+            None
+        };
+
+        if let Some((text, tag, consumed)) = styled.or(link) {
+            if !plain.is_empty() {
+                segments.push((std::mem::take(&mut plain), None));
+            }
+            previous = text.chars().last();
+            segments.push((text, Some(tag)));
+            rest = &rest[consumed..];
+        } else {
+            let ch = rest.chars().next().unwrap_or_default();
+            plain.push(ch);
+            previous = Some(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+
+    if !plain.is_empty() {
+        segments.push((plain, None));
+    }
+    segments
+}
+
+/// Inserts a line's inline segments, combining their tags with the block tags
+fn insert_inline(buffer: &gtk::TextBuffer, text: &str, block_tags: &[&str]) {
+    for (segment, tag) in inline_segments(text) {
+        let mut tags: Vec<&str> = block_tags.to_vec();
+        if let Some(tag) = tag {
+            tags.push(tag);
+        }
+        insert_tagged(buffer, &segment, &tags);
+    }
+}
+
+/// Appends text to the end of the buffer with the named tags applied
+fn insert_tagged(buffer: &gtk::TextBuffer, text: &str, tags: &[&str]) {
+    let start_offset = buffer.end_iter().offset();
+    buffer.insert(&mut buffer.end_iter(), text);
+    let start = buffer.iter_at_offset(start_offset);
+    let end = buffer.end_iter();
+    for tag in tags {
+        buffer.apply_tag_by_name(tag, &start, &end);
+    }
+}
+
+/// Renders a block of pipe table rows with aligned columns
+fn render_table(buffer: &gtk::TextBuffer, rows: &[&str]) {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.trim()
+                .trim_matches('|')
+                .split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        })
+        .collect();
+    let columns = cells.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            cells
+                .iter()
+                .zip(rows)
+                .filter(|(_, raw)| !is_table_separator(raw))
+                .filter_map(|(row, _)| row.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (index, (row, raw)) in cells.iter().zip(rows).enumerate() {
+        let line = if is_table_separator(raw) {
+            widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<_>>()
+                .join("┼")
+        } else {
+            widths
+                .iter()
+                .enumerate()
+                .map(|(col, width)| {
+                    let cell = row.get(col).map(String::as_str).unwrap_or("");
+                    let padding = width.saturating_sub(cell.chars().count());
+                    format!(" {}{} ", cell, " ".repeat(padding))
+                })
+                .collect::<Vec<_>>()
+                .join("│")
+        };
+        // The first row is the header
+        let tags: &[&str] = if index == 0 { &["preview_table", "preview_bold"] } else { &["preview_table"] };
+        insert_tagged(buffer, &format!("{}\n", line), tags);
+    }
+}
+
+/// Renders markdown into the preview TextView
+pub fn render_markdown_preview(preview: &TextView, markdown: &str) {
+    let buffer = preview.buffer();
+    ensure_preview_tags(&buffer);
+    buffer.set_text("");
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut in_code_block = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            i += 1;
+            continue;
+        }
+
+        if in_code_block {
+            insert_tagged(&buffer, &format!("{}\n", line), &["preview_code_block"]);
+            i += 1;
+            continue;
+        }
+
+        // Consecutive pipe rows form one table
+        if trimmed.starts_with('|') {
+            let start = i;
+            while i < lines.len() && lines[i].trim_start().starts_with('|') {
+                i += 1;
+            }
+            render_table(&buffer, &lines[start..i]);
+            continue;
+        }
+
+        let heading_level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ') {
+            let tag = format!("preview_h{}", heading_level.min(4));
+            insert_inline(&buffer, trimmed[heading_level..].trim(), &[tag.as_str()]);
+            insert_tagged(&buffer, "\n", &[]);
+        } else if matches!(trimmed.trim_end(), "---" | "***" | "___") {
+            insert_tagged(&buffer, &format!("{}\n", "─".repeat(40)), &["preview_rule"]);
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            insert_inline(&buffer, quote.trim_start(), &["preview_quote"]);
+            insert_tagged(&buffer, "\n", &["preview_quote"]);
+        } else if let Some((bracket, checked)) = find_task_checkbox(line) {
+            let marker = if checked { "☑ " } else { "☐ " };
+            insert_tagged(&buffer, marker, &["preview_list", "preview_bullet"]);
+            let task = line[bracket + 3..].trim_start();
+            let tags: &[&str] = if checked { &["preview_list", "preview_strike"] } else { &["preview_list"] };
+            insert_inline(&buffer, task, tags);
+            insert_tagged(&buffer, "\n", &["preview_list"]);
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .or_else(|| trimmed.strip_prefix("+ "))
+        {
+            insert_tagged(&buffer, "• ", &["preview_list", "preview_bullet"]);
+            insert_inline(&buffer, item, &["preview_list"]);
+            insert_tagged(&buffer, "\n", &["preview_list"]);
+        } else {
+            insert_inline(&buffer, line, &[]);
+            insert_tagged(&buffer, "\n", &[]);
+        }
+        i += 1;
+    }
+}
@@ -0,0 +1,311 @@
+//! Spell checking for notes editors
+//!
+//! Words are checked against the Hunspell dictionaries installed on the system.
+//! Fenced code blocks, inline code and path- or URL-like tokens are skipped so
+//! command snippets in notes aren't flagged.
+
+use gtk4::prelude::*;
+use gtk4::{self as gtk, gio, glib, TextView};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::config::{get_app_settings, save_app_settings, is_spell_check_enabled, get_spell_check_language,
+                    get_spell_check_words};
+use crate::ui::window::report_error;
+
+/// Tag marking misspelled words; kept when markdown highlighting is reapplied
+pub const MISSPELLED_TAG: &str = "misspelled";
+
+/// Where distributions and Flatpak runtimes install Hunspell dictionaries
+const DICTIONARY_DIRS: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell/dicts",
+    "/usr/share/myspell",
+    "/app/share/hunspell",
+];
+
+/// Delay after the last edit before a view is checked again
+const RECHECK_DELAY_MS: u64 = 400;
+
+/// Most suggestions offered in the context menu
+const MAX_SUGGESTIONS: usize = 5;
+
+thread_local! {
+    /// Loaded dictionary with its language; None when the language has no usable dictionary
+    static DICTIONARY: RefCell<Option<(String, Option<Rc<spellbook::Dictionary>>)>> = RefCell::new(None);
+}
+
+/// Finds the .aff and .dic files of a language such as en_US
+fn dictionary_paths(language: &str) -> Option<(PathBuf, PathBuf)> {
+    DICTIONARY_DIRS.iter().find_map(|dir| {
+        let aff = PathBuf::from(dir).join(format!("{}.aff", language));
+        let dic = PathBuf::from(dir).join(format!("{}.dic", language));
+        (aff.exists() && dic.exists()).then_some((aff, dic))
+    })
+}
+
+/// Languages with an installed Hunspell dictionary, sorted
+pub fn available_languages() -> Vec<String> {
+    let mut languages: Vec<String> = DICTIONARY_DIRS
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "dic" || !path.with_extension("aff").exists() {
+                return None;
+            }
+            path.file_stem()?.to_str().map(String::from)
+        })
+        .collect();
+    languages.sort();
+    languages.dedup();
+    languages
+}
+
+fn load_dictionary(language: &str) -> Result<spellbook::Dictionary, String> {
+    let (aff_path, dic_path) = dictionary_paths(language)
+        .ok_or_else(|| format!("No Hunspell dictionary installed for {}", language))?;
+    let aff = fs::read_to_string(&aff_path)
+        .map_err(|e| format!("Failed to read {}: {}", aff_path.display(), e))?;
+    let dic = fs::read_to_string(&dic_path)
+        .map_err(|e| format!("Failed to read {}: {}", dic_path.display(), e))?;
+    spellbook::Dictionary::new(&aff, &dic)
+        .map_err(|e| format!("Failed to load {} dictionary: {}", language, e))
+}
+
+/// The dictionary for the configured language, loaded once per language
+fn dictionary() -> Option<Rc<spellbook::Dictionary>> {
+    let language = get_spell_check_language();
+    DICTIONARY.with(|cache| {
+        if let Some((loaded, dictionary)) = cache.borrow().as_ref() {
+            if *loaded == language {
+                return dictionary.clone();
+            }
+        }
+        let dictionary = match load_dictionary(&language) {
+            Ok(dictionary) => Some(Rc::new(dictionary)),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        };
+        *cache.borrow_mut() = Some((language, dictionary.clone()));
+        dictionary
+    })
+}
+
+/// Whether a whitespace-separated token looks like a command, path, URL or address
+fn is_code_like(token: &str) -> bool {
+    token.contains("://")
+        || token.chars().any(|c| {
+            c.is_ascii_digit() || matches!(c, '/' | '\\' | '@' | '_' | '=' | '$' | '{' | '}' | '<' | '>' | '|')
+        })
+}
+
+/// Words worth checking in a line, as (start, end, word) with char offsets from `offset`
+fn collect_line_words(line: &str, offset: i32, words: &mut Vec<(i32, i32, String)>) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut in_inline_code = false;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            in_inline_code = !in_inline_code;
+            i += 1;
+            continue;
+        }
+        if in_inline_code || chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token_start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '`' {
+            i += 1;
+        }
+        let token: String = chars[token_start..i].iter().collect();
+        if is_code_like(&token) {
+            continue;
+        }
+
+        // Letters, with apostrophes allowed between them (don't, host's)
+        let mut j = token_start;
+        while j < i {
+            if !chars[j].is_alphabetic() {
+                j += 1;
+                continue;
+            }
+            let word_start = j;
+            while j < i && (chars[j].is_alphabetic() || (chars[j] == '\'' && j + 1 < i && chars[j + 1].is_alphabetic())) {
+                j += 1;
+            }
+            let word: String = chars[word_start..j].iter().collect();
+            // Acronyms and CamelCase tool names are left alone
+            if word.chars().count() > 1 && !word.chars().skip(1).any(|c| c.is_uppercase()) {
+                words.push((offset + word_start as i32, offset + j as i32, word));
+            }
+        }
+    }
+}
+
+/// Words worth checking in a markdown text, skipping fenced code blocks
+fn checkable_words(text: &str) -> Vec<(i32, i32, String)> {
+    let mut words = Vec::new();
+    let mut in_code_block = false;
+    let mut offset = 0i32;
+    for line in text.split('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block {
+            collect_line_words(line, offset, &mut words);
+        }
+        offset += line.chars().count() as i32 + 1;
+    }
+    words
+}
+
+/// Underlines unknown words in a text view, or clears the marks if spell checking is off
+pub fn check_spelling(text_view: &TextView) {
+    let buffer = text_view.buffer();
+    let tag = match buffer.tag_table().lookup(MISSPELLED_TAG) {
+        Some(tag) => tag,
+        None => match buffer.create_tag(Some(MISSPELLED_TAG), &[("underline", &gtk::pango::Underline::Error)]) {
+            Some(tag) => tag,
+            None => return,
+        },
+    };
+
+    let (start, end) = buffer.bounds();
+    buffer.remove_tag(&tag, &start, &end);
+    if !is_spell_check_enabled() {
+        return;
+    }
+    let Some(dictionary) = dictionary() else {
+        return;
+    };
+
+    let personal: HashSet<String> = get_spell_check_words().into_iter().collect();
+    let text = buffer.text(&start, &end, false);
+    for (word_start, word_end, word) in checkable_words(&text) {
+        if !personal.contains(&word) && !dictionary.check(&word) {
+            buffer.apply_tag(&tag, &buffer.iter_at_offset(word_start), &buffer.iter_at_offset(word_end));
+        }
+    }
+}
+
+/// Builds the suggestions menu for a misspelled word under the pointer, remembering its range
+fn spelling_menu(text_view: &TextView, x: f64, y: f64, range: &Cell<(i32, i32)>) -> Option<gio::Menu> {
+    let buffer = text_view.buffer();
+    let tag = buffer.tag_table().lookup(MISSPELLED_TAG)?;
+    let (buffer_x, buffer_y) = text_view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+    let iter = text_view.iter_at_location(buffer_x, buffer_y)?;
+    if !iter.has_tag(&tag) {
+        return None;
+    }
+
+    let mut start = iter.clone();
+    if !start.starts_tag(Some(&tag)) {
+        start.backward_to_tag_toggle(Some(&tag));
+    }
+    let mut end = iter;
+    end.forward_to_tag_toggle(Some(&tag));
+    let word = buffer.text(&start, &end, false).to_string();
+    range.set((start.offset(), end.offset()));
+
+    let mut suggestions = Vec::new();
+    dictionary()?.suggest(&word, &mut suggestions);
+
+    let section = gio::Menu::new();
+    if suggestions.is_empty() {
+        section.append(Some("No Suggestions"), None);
+    }
+    for suggestion in suggestions.iter().take(MAX_SUGGESTIONS) {
+        let item = gio::MenuItem::new(Some(suggestion), None);
+        item.set_action_and_target_value(Some("spell.replace"), Some(&suggestion.to_variant()));
+        section.append_item(&item);
+    }
+    let add_item = gio::MenuItem::new(Some(&format!("Add \"{}\" to Dictionary", word)), None);
+    add_item.set_action_and_target_value(Some("spell.add"), Some(&word.to_variant()));
+    section.append_item(&add_item);
+
+    let menu = gio::Menu::new();
+    menu.append_section(None, &section);
+    Some(menu)
+}
+
+/// Checks a notes view as it is edited and offers suggestions in its context menu
+pub fn add_spell_check(text_view: &TextView) {
+    check_spelling(text_view);
+
+    // Re-check shortly after the last edit instead of on every keystroke
+    let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+    let text_view_weak = text_view.downgrade();
+    text_view.buffer().connect_changed(move |_| {
+        if let Some(id) = pending.borrow_mut().take() {
+            id.remove();
+        }
+        let pending_inner = Rc::clone(&pending);
+        let text_view_weak = text_view_weak.clone();
+        let delay = std::time::Duration::from_millis(RECHECK_DELAY_MS);
+        let source_id = glib::timeout_add_local_once(delay, move || {
+            *pending_inner.borrow_mut() = None;
+            if let Some(text_view) = text_view_weak.upgrade() {
+                check_spelling(&text_view);
+            }
+        });
+        *pending.borrow_mut() = Some(source_id);
+    });
+
+    // Range of the word the context menu was opened on
+    let range: Rc<Cell<(i32, i32)>> = Rc::new(Cell::new((0, 0)));
+
+    let actions = gio::SimpleActionGroup::new();
+    let replace_action = gio::SimpleAction::new("replace", Some(glib::VariantTy::STRING));
+    let text_view_weak = text_view.downgrade();
+    let range_clone = Rc::clone(&range);
+    replace_action.connect_activate(move |_, parameter| {
+        let (Some(text_view), Some(word)) = (text_view_weak.upgrade(), parameter.and_then(|p| p.str())) else {
+            return;
+        };
+        let buffer = text_view.buffer();
+        let (start, end) = range_clone.get();
+        let mut start_iter = buffer.iter_at_offset(start);
+        let mut end_iter = buffer.iter_at_offset(end);
+        buffer.begin_user_action();
+        buffer.delete(&mut start_iter, &mut end_iter);
+        buffer.insert(&mut start_iter, word);
+        buffer.end_user_action();
+    });
+    actions.add_action(&replace_action);
+
+    let add_action = gio::SimpleAction::new("add", Some(glib::VariantTy::STRING));
+    add_action.connect_activate(|_, parameter| {
+        let Some(word) = parameter.and_then(|p| p.str()) else {
+            return;
+        };
+        let mut settings = get_app_settings();
+        if !settings.spell_check_words.iter().any(|w| w == word) {
+            settings.spell_check_words.push(word.to_string());
+            report_error(save_app_settings(&settings));
+        }
+        crate::ui::editor::refresh_notes_spelling();
+    });
+    actions.add_action(&add_action);
+    text_view.insert_action_group("spell", Some(&actions));
+
+    // Capture phase, so the menu is in place before the view builds its popover
+    let gesture = gtk::GestureClick::new();
+    gesture.set_button(gtk::gdk::BUTTON_SECONDARY);
+    gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+    gesture.connect_pressed(move |gesture, _, x, y| {
+        let Some(text_view) = gesture.widget().and_downcast::<TextView>() else {
+            return;
+        };
+        let menu = if is_spell_check_enabled() { spelling_menu(&text_view, x, y, &range) } else { None };
+        text_view.set_extra_menu(menu.as_ref());
+    });
+    text_view.add_controller(gesture);
+}
@@ -7,22 +7,743 @@ use gtk4::{self as gtk, Box as GtkBox, Button, Label, Notebook, Orientation, Scr
 use gtk4::glib;
 use libadwaita::{self as adw, prelude::*};
 use vte4::{Terminal, TerminalExt, TerminalExtManual};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
 use std::collections::{HashMap, HashSet};
 
 use crate::config::{
     get_file_path, get_app_settings, save_app_settings, get_keyboard_shortcuts,
     get_terminal_zoom_scale, set_terminal_zoom_scale_raw, load_targets,
-    is_command_logging_enabled, zoom, tabs, get_base_dir, is_flatpak,
+    is_command_logging_enabled, zoom, tabs, resolve_shell_start_dir, is_flatpak, get_terminal_theme,
+    get_autosave_debounce_ms, get_base_dir, append_target, is_protected_tab, first_closable_tab,
+    is_target_autocomplete_enabled, shell_quote, get_collapsed_categories, shortcut_hint,
+    get_sort_commands_alphabetically, get_split_position, get_drawer_width, AppSettings,
+    is_command_preview_enabled, get_custom_commands_path, atomic_write, rotate_command_log,
+    get_terminal_timestamp_prefix, load_target_entries, validate_targets,
 };
-use crate::commands::load_command_templates;
-use crate::ui::editor::{apply_markdown_highlighting, track_notes_view};
+use crate::targets::expand_targets;
+use crate::commands::{load_command_templates, extract_placeholders, fill_placeholders, command_categories, category_key,
+                      category_command_indices, CommandOs,
+                      resolve_command_variables, detect_default_interface, is_dangerous_command,
+                      load_recent_commands, record_recent_command, read_custom_commands};
+use crate::ui::window::{report_error, show_error_toast, show_config_error, show_no_targets_toast};
+use crate::ui::editor::{apply_markdown_highlighting, track_notes_view, open_notes_buffer, reload_log_viewer, log_file_changed,
+                        unregister_text_views, create_save_state_label, set_save_state, save_editor_text, save_editor_text_async,
+                        copy_target_to_clipboard};
 
 // Track all terminals for global zoom
 thread_local! {
     static TERMINALS: RefCell<Vec<Terminal>> = RefCell::new(Vec::new());
+    static SHELL_PIDS: RefCell<Vec<(Terminal, i32)>> = RefCell::new(Vec::new());
+    static CLOSED_TABS: RefCell<Vec<ClosedTab>> = RefCell::new(Vec::new());
+    /// Pending save of a dragged divider, so a drag writes settings once
+    static LAYOUT_SAVE: RefCell<Option<glib::SourceId>> = RefCell::new(None);
+    /// Reload callbacks of open command drawers, keyed by their command list
+    static COMMAND_DRAWERS: RefCell<Vec<(glib::WeakRef<gtk::ListBox>, Rc<dyn Fn()>)>> = RefCell::new(Vec::new());
+    static CUSTOM_COMMANDS_MONITOR: RefCell<Option<gtk::gio::FileMonitor>> = RefCell::new(None);
+    /// Names shown on shell tab labels by stable tab id, following renames
+    static TAB_NAMES: RefCell<HashMap<u32, String>> = RefCell::new(HashMap::new());
+    static NEXT_TAB_ID: Cell<u32> = Cell::new(1);
+    /// Expanded targets offered by autocomplete, loaded on first use and dropped when targets.txt reloads
+    static COMPLETION_TARGETS: RefCell<Option<Rc<Vec<String>>>> = const { RefCell::new(None) };
+}
+
+/// Widget name prefix that carries a shell terminal's stable tab id
+const TAB_ID_PREFIX: &str = "tab:";
+
+/// Divider position that pushes a closed command drawer out of view
+pub const DRAWER_CLOSED_POSITION: i32 = 10000;
+
+/// Maximum number of closed tabs remembered for reopening
+const MAX_CLOSED_TABS: usize = 10;
+
+/// Kind of tab that can be reopened after closing
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClosedTabKind {
+    Shell { logging: bool },
+    SplitView,
+    Ssh { profile: String },
+}
+
+/// Descriptor of a recently closed shell or split view tab
+#[derive(Debug, Clone)]
+pub struct ClosedTab {
+    pub kind: ClosedTabKind,
+    pub label: String,
+}
+
+/// Pops the most recently closed tab, if any
+pub fn pop_closed_tab() -> Option<ClosedTab> {
+    CLOSED_TABS.with(|tabs| tabs.borrow_mut().pop())
+}
+
+/// Records a shell, SSH or split view page in the closed tab stack before it is removed
+fn remember_closed_tab(notebook: &Notebook, page: &gtk::Widget) {
+    let page_name = page.widget_name();
+    let kind = match page_name.as_str() {
+        "shell" => ClosedTabKind::Shell { logging: true },
+        "shell_nolog" => ClosedTabKind::Shell { logging: false },
+        "split_view" => ClosedTabKind::SplitView,
+        name => match name.strip_prefix("ssh:") {
+            Some(profile) => ClosedTabKind::Ssh { profile: profile.to_string() },
+            None => return,
+        },
+    };
+    let label = notebook
+        .tab_label(page)
+        .and_then(|tab| tab.first_child())
+        .and_then(|child| child.downcast::<Label>().ok())
+        .map(|label| label.text().to_string())
+        .unwrap_or_default();
+
+    CLOSED_TABS.with(|tabs| {
+        let mut tabs = tabs.borrow_mut();
+        tabs.push(ClosedTab { kind, label });
+        if tabs.len() > MAX_CLOSED_TABS {
+            tabs.remove(0);
+        }
+    });
+}
+
+/// Remembers the child process spawned for a terminal
+pub fn track_shell_pid(terminal: &Terminal, pid: i32) {
+    SHELL_PIDS.with(|pids| {
+        pids.borrow_mut().push((terminal.clone(), pid));
+    });
+}
+
+/// Prompt command that appends the last exit status to the shell's status file.
+/// `$EPOCHSECONDS` needs bash 5, older shells fall back to `date +%s`.
+const STATUS_PROMPT_COMMAND: &str = r#"__penenv_status=$?; echo "exit $__penenv_status ${EPOCHSECONDS:-$(date +%s)}" >> "$PENENV_STATUS_FILE""#;
+
+/// PS0 is expanded when a command starts running, appending its start to the status file
+const STATUS_PS0: &str = r#"$(echo "start ${EPOCHSECONDS:-$(date +%s)}" >> "$PENENV_STATUS_FILE")"#;
+
+/// Commands running at least this long show their duration and highlight the tab when done
+const LONG_COMMAND_SECS: u64 = 10;
+
+/// Output this soon after a tab is hidden is the prompt repainting, not new activity
+const ACTIVITY_GRACE_MS: u128 = 1000;
+
+/// Tab marker for a terminal that rang its bell in the background
+const BELL_MARKER: &str = "🔔";
+
+/// Formats a command duration compactly, e.g. `45s` or `2m 5s`
+fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Finds the tab label box of the notebook page containing a widget
+fn tab_label_for_widget(notebook: &Notebook, widget: &gtk::Widget) -> Option<GtkBox> {
+    let mut current = Some(widget.clone());
+    while let Some(candidate) = current {
+        if notebook.page_num(&candidate).is_some() {
+            return notebook.tab_label(&candidate).and_downcast::<GtkBox>();
+        }
+        current = candidate.parent();
+    }
+    None
+}
+
+/// Updates the colored status dot after the tab name, creating it on first use
+fn set_tab_exit_status(tab_box: &GtkBox, success: bool, text: &str, tooltip: &str) {
+    let dot = match find_child_by_name(tab_box, "exit_status") {
+        Some(dot) => dot,
+        None => {
+            let dot = Label::new(None);
+            dot.set_widget_name("exit_status");
+            dot.add_css_class("caption");
+            tab_box.insert_child_after(&dot, tab_box.first_child().as_ref());
+            dot
+        }
+    };
+    dot.remove_css_class(if success { "error" } else { "success" });
+    dot.add_css_class(if success { "success" } else { "error" });
+    dot.set_text(text);
+    dot.set_tooltip_text(Some(tooltip));
+}
+
+/// Marks a tab that printed output (a dot) or rang the bell while not shown
+fn set_tab_activity(tab_box: &GtkBox, bell: bool) {
+    let marker = match find_child_by_name(tab_box, "activity") {
+        Some(marker) => marker,
+        None => {
+            let marker = Label::new(None);
+            marker.set_widget_name("activity");
+            marker.add_css_class("caption");
+            marker.add_css_class("accent");
+            tab_box.insert_child_after(&marker, tab_box.first_child().as_ref());
+            marker
+        }
+    };
+    // A bell outranks plain output until the tab is looked at
+    if bell {
+        marker.set_text(BELL_MARKER);
+        marker.set_tooltip_text(Some("The terminal rang its bell"));
+    } else if marker.text().is_empty() {
+        marker.set_text("•");
+        marker.set_tooltip_text(Some("New output"));
+    }
+}
+
+/// Finds a direct child label by widget name
+fn find_child_by_name(container: &GtkBox, name: &str) -> Option<Label> {
+    let mut child = container.first_child();
+    while let Some(current) = child {
+        if current.widget_name() == name {
+            return current.downcast::<Label>().ok();
+        }
+        child = current.next_sibling();
+    }
+    None
+}
+
+/// A line of a shell's status file, with epoch seconds from `$EPOCHSECONDS` or `date +%s`
+#[derive(Debug, PartialEq)]
+enum ShellStatus {
+    Start(u64),
+    Exit(i32, u64),
+}
+
+/// Parses a `start <secs>` or `exit <code> <secs>` status line
+fn parse_shell_status(line: &str) -> Option<ShellStatus> {
+    let mut parts = line.split_whitespace();
+    match parts.next()? {
+        "start" => Some(ShellStatus::Start(parts.next()?.parse().ok()?)),
+        "exit" => {
+            let code = parts.next()?.parse().ok()?;
+            Some(ShellStatus::Exit(code, parts.next()?.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Directory for shell status files; under Flatpak it is the app's runtime directory,
+/// which host shells can write to as well
+fn shell_status_dir() -> PathBuf {
+    let runtime_dir = glib::user_runtime_dir();
+    match std::env::var("FLATPAK_ID") {
+        Ok(app_id) if is_flatpak() => runtime_dir.join("app").join(app_id),
+        _ => runtime_dir.join("penenv"),
+    }
+}
+
+/// Creates an empty status file for a new shell, returning its path
+fn create_shell_status_file(terminal: &Terminal) -> Option<PathBuf> {
+    let dir = shell_status_dir();
+    let path = dir.join(format!(
+        "status-{}-{}",
+        std::process::id(),
+        terminal_tab_id(terminal).unwrap_or_default()
+    ));
+    match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, "")) {
+        Ok(()) => Some(path),
+        Err(e) => {
+            log::warn!("Failed to create shell status file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Reads the complete lines appended to a status file since `offset`, advancing it
+fn read_new_status_lines(path: &std::path::Path, offset: &Cell<u64>) -> Vec<String> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut text = String::new();
+    let read = fs::File::open(path).and_then(|mut file| {
+        file.seek(SeekFrom::Start(offset.get()))?;
+        file.read_to_string(&mut text)
+    });
+    if let Err(e) = read {
+        log::warn!("Failed to read {}: {}", path.display(), e);
+        return Vec::new();
+    }
+    // A line the shell is still writing is picked up by the next change event
+    let Some(end) = text.rfind('\n') else { return Vec::new() };
+    offset.set(offset.get() + end as u64 + 1);
+    text[..end].lines().map(str::to_string).collect()
+}
+
+/// Shows the last command's exit status and duration on the shell's tab
+///
+/// The shell's PS0 and PROMPT_COMMAND append command starts and exit statuses to
+/// `status_path`, which is watched here. Long commands that finish while the tab or
+/// window is in the background also highlight the tab name until the terminal is
+/// focused again.
+fn watch_shell_status(terminal: &Terminal, notebook: &Notebook, status_path: PathBuf) {
+    let monitor = match gtk::gio::File::for_path(&status_path)
+        .monitor_file(gtk::gio::FileMonitorFlags::NONE, None::<&gtk::gio::Cancellable>)
+    {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            log::warn!("Failed to watch {}: {}", status_path.display(), e);
+            return;
+        }
+    };
+
+    let started: Rc<Cell<Option<u64>>> = Rc::new(Cell::new(None));
+    let offset = Rc::new(Cell::new(0u64));
+    let notebook_clone = notebook.clone();
+    let terminal_weak = terminal.downgrade();
+    let path_clone = status_path.clone();
+    monitor.connect_changed(move |_, _, _, event| {
+        if !matches!(event, gtk::gio::FileMonitorEvent::Changed | gtk::gio::FileMonitorEvent::ChangesDoneHint) {
+            return;
+        }
+        let Some(terminal) = terminal_weak.upgrade() else { return };
+        for line in read_new_status_lines(&path_clone, &offset) {
+            match parse_shell_status(&line) {
+                Some(ShellStatus::Start(at)) => started.set(Some(at)),
+                // Only report prompts that follow a command, not empty lines or the first prompt
+                Some(ShellStatus::Exit(code, at)) => {
+                    if let Some(start) = started.take() {
+                        show_command_status(&terminal, &notebook_clone, code, at.saturating_sub(start));
+                    }
+                }
+                None => {}
+            }
+        }
+    });
+
+    // The handler owns the monitor, keeping it alive for as long as the terminal
+    terminal.connect_destroy(move |_| {
+        monitor.cancel();
+        let _ = fs::remove_file(&status_path);
+    });
+}
+
+/// Updates the tab's status dot after a command finished
+fn show_command_status(terminal: &Terminal, notebook: &Notebook, code: i32, secs: u64) {
+    let Some(tab_box) = tab_label_for_widget(notebook, terminal.upcast_ref()) else { return };
+
+    let elapsed = std::time::Duration::from_secs(secs);
+    let is_long = secs >= LONG_COMMAND_SECS;
+    let text = if is_long { format!("● {}", format_elapsed(elapsed)) } else { "●".to_string() };
+    set_tab_exit_status(&tab_box, code == 0, &text, &format!("Exit status {} after {}", code, format_elapsed(elapsed)));
+
+    let window_active = terminal.root().and_downcast::<gtk::Window>().is_some_and(|w| w.is_active());
+    if is_long && (!window_active || !terminal.has_focus()) {
+        if let Some(name_label) = tab_box.first_child() {
+            name_label.add_css_class("accent");
+        }
+    }
+}
+
+/// Reports a shell exiting on its tab and clears the long-command highlight on focus
+fn setup_exit_status_indicator(terminal: &Terminal, notebook: &Notebook) {
+    // The shell itself exited, e.g. `exit` or a dropped ssh connection
+    let notebook_clone = notebook.clone();
+    terminal.connect_child_exited(move |terminal, status| {
+        let code = if status & 0x7f == 0 { (status >> 8) & 0xff } else { 128 + (status & 0x7f) };
+        if let Some(tab_box) = tab_label_for_widget(&notebook_clone, terminal.upcast_ref()) {
+            set_tab_exit_status(&tab_box, code == 0, "●", &format!("Shell exited with status {}", code));
+        }
+    });
+
+    // Focusing the terminal acknowledges a highlighted tab
+    let focus_controller = gtk::EventControllerFocus::new();
+    let notebook_clone2 = notebook.clone();
+    let terminal_clone = terminal.clone();
+    focus_controller.connect_enter(move |_| {
+        if let Some(name_label) = tab_label_for_widget(&notebook_clone2, terminal_clone.upcast_ref())
+            .and_then(|tab_box| tab_box.first_child())
+        {
+            name_label.remove_css_class("accent");
+        }
+    });
+    terminal.add_controller(focus_controller);
+}
+
+/// Marks the shell's tab when it prints output or rings the bell while another tab is shown
+///
+/// The marker is cleared when the tab is switched to.
+fn setup_activity_indicator(terminal: &Terminal, notebook: &Notebook) {
+    // Set while the terminal is off screen, i.e. its tab isn't the current page
+    let hidden_since: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+
+    let hidden_clone = Rc::clone(&hidden_since);
+    terminal.connect_unmap(move |_| {
+        hidden_clone.set(Some(Instant::now()));
+    });
+
+    let notebook_clone = notebook.clone();
+    let hidden_clone2 = Rc::clone(&hidden_since);
+    terminal.connect_map(move |terminal| {
+        hidden_clone2.set(None);
+        let marker = tab_label_for_widget(&notebook_clone, terminal.upcast_ref())
+            .and_then(|tab_box| find_child_by_name(&tab_box, "activity").map(|marker| (tab_box, marker)));
+        if let Some((tab_box, marker)) = marker {
+            tab_box.remove(&marker);
+        }
+    });
+
+    let notebook_clone2 = notebook.clone();
+    terminal.connect_contents_changed(move |terminal| {
+        let Some(since) = hidden_since.get() else { return };
+        if since.elapsed().as_millis() < ACTIVITY_GRACE_MS {
+            return;
+        }
+        if let Some(tab_box) = tab_label_for_widget(&notebook_clone2, terminal.upcast_ref()) {
+            set_tab_activity(&tab_box, false);
+        }
+    });
+
+    let notebook_clone3 = notebook.clone();
+    terminal.connect_bell(move |terminal| {
+        if terminal.is_mapped() {
+            return;
+        }
+        if let Some(tab_box) = tab_label_for_widget(&notebook_clone3, terminal.upcast_ref()) {
+            set_tab_activity(&tab_box, true);
+        }
+    });
+}
+
+/// Extracts the process group and terminal foreground process group from /proc/<pid>/stat
+fn parse_stat_pgrp_tpgid(stat: &str) -> Option<(i32, i32)> {
+    // The command name may contain spaces, so start after its closing parenthesis
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // Fields: state ppid pgrp session tty_nr tpgid ...
+    let pgrp = fields.get(2)?.parse().ok()?;
+    let tpgid = fields.get(5)?.parse().ok()?;
+    Some((pgrp, tpgid))
+}
+
+/// Returns the name of the foreground process in a terminal if it is not the shell itself
+fn foreground_process_name(terminal: &Terminal) -> Option<String> {
+    let pid = SHELL_PIDS.with(|pids| {
+        pids.borrow()
+            .iter()
+            .find(|(t, _)| t == terminal)
+            .map(|(_, pid)| *pid)
+    })?;
+
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (pgrp, tpgid) = parse_stat_pgrp_tpgid(&stat)?;
+    if tpgid <= 0 || tpgid == pgrp {
+        return None;
+    }
+
+    let name = fs::read_to_string(format!("/proc/{}/comm", tpgid))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_else(|_| format!("process {}", tpgid));
+    Some(name)
+}
+
+/// Gets the current working directory of a terminal's shell
+///
+/// Uses the directory the shell reports via OSC 7 when available, otherwise
+/// reads it from /proc for shells spawned directly on the host.
+pub fn terminal_working_dir(terminal: &Terminal) -> Option<PathBuf> {
+    if let Some(path) = terminal
+        .current_directory_uri()
+        .and_then(|uri| gtk::gio::File::for_uri(&uri).path())
+    {
+        return Some(path);
+    }
+    if is_flatpak() {
+        return None;
+    }
+    let pid = SHELL_PIDS.with(|pids| {
+        pids.borrow()
+            .iter()
+            .find(|(t, _)| t == terminal)
+            .map(|(_, pid)| *pid)
+    })?;
+    fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+}
+
+/// Collects all terminals contained in a widget tree
+fn collect_terminals(widget: &gtk::Widget, terminals: &mut Vec<Terminal>) {
+    if let Some(terminal) = widget.downcast_ref::<Terminal>() {
+        terminals.push(terminal.clone());
+        return;
+    }
+    let mut child = widget.first_child();
+    while let Some(current) = child {
+        collect_terminals(&current, terminals);
+        child = current.next_sibling();
+    }
+}
+
+/// Finds the first terminal inside a tab page, such as the shell half of a split view
+pub fn find_terminal_in(widget: &gtk::Widget) -> Option<Terminal> {
+    let mut terminals = Vec::new();
+    collect_terminals(widget, &mut terminals);
+    terminals.into_iter().next()
+}
+
+/// Removes a tab page and forgets the shell pids of its terminals
+fn remove_tab_page(notebook: &Notebook, page: &gtk::Widget) {
+    remember_closed_tab(notebook, page);
+    forget_page_widgets(page);
+
+    if let Some(page_num) = notebook.page_num(page) {
+        notebook.remove_page(Some(page_num));
+    }
+}
+
+/// Gives a shell's terminal an id that stays the same when its tab is renamed or detached
+fn assign_tab_id(terminal: &Terminal) {
+    let tab_id = NEXT_TAB_ID.with(|next| {
+        let tab_id = next.get();
+        next.set(tab_id + 1);
+        tab_id
+    });
+    terminal.set_widget_name(&format!("{}{}", TAB_ID_PREFIX, tab_id));
+}
+
+/// Stable id of the shell tab a terminal belongs to
+pub fn terminal_tab_id(terminal: &Terminal) -> Option<u32> {
+    terminal.widget_name().strip_prefix(TAB_ID_PREFIX)?.parse().ok()
+}
+
+/// Name the user gave a shell tab, by stable id
+pub fn tab_name(tab_id: u32) -> Option<String> {
+    TAB_NAMES.with(|names| names.borrow().get(&tab_id).cloned())
+}
+
+/// Name of the shell tab a page holds, even while the page is detached from the notebook
+fn page_tab_name(page: &gtk::Widget) -> Option<String> {
+    find_terminal_in(page).and_then(|terminal| terminal_tab_id(&terminal)).and_then(tab_name)
+}
+
+/// Records the text of a tab label under the id of the shell tab it belongs to
+fn record_tab_name(notebook: &Notebook, tab_box: &gtk::Widget) {
+    let Some((_, page)) = page_for_tab_label(notebook, tab_box) else { return };
+    let Some(tab_id) = find_terminal_in(&page).and_then(|terminal| terminal_tab_id(&terminal)) else { return };
+    let Some(label) = tab_box.first_child().and_downcast::<Label>() else { return };
+    TAB_NAMES.with(|names| {
+        names.borrow_mut().insert(tab_id, label.text().to_string());
+    });
+}
+
+/// Forgets the terminals, shell pids and text views of a page that is going away
+fn forget_page_widgets(page: &gtk::Widget) {
+    let mut terminals = Vec::new();
+    collect_terminals(page, &mut terminals);
+    SHELL_PIDS.with(|pids| {
+        pids.borrow_mut().retain(|(t, _)| !terminals.contains(t));
+    });
+    TAB_NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        for tab_id in terminals.iter().filter_map(terminal_tab_id) {
+            names.remove(&tab_id);
+        }
+    });
+    for terminal in &terminals {
+        unregister_terminal(terminal);
+    }
+    unregister_text_views(page);
+}
+
+/// Finds the page whose tab label is the given widget
+fn page_for_tab_label(notebook: &Notebook, tab_label: &gtk::Widget) -> Option<(u32, gtk::Widget)> {
+    (0..notebook.n_pages()).find_map(|i| {
+        let page = notebook.nth_page(Some(i))?;
+        (notebook.tab_label(&page).as_ref() == Some(tab_label)).then_some((i, page))
+    })
+}
+
+/// Moves a tab page into its own window, keeping its terminal and shell running
+///
+/// The window's Reattach button puts the page back at the end of the notebook.
+/// Closing the window closes the tab, asking first if a command is still running.
+pub fn detach_tab(notebook: &Notebook, page: &gtk::Widget) {
+    let Some(page_num) = notebook.page_num(page) else {
+        return;
+    };
+    let title = page_tab_name(page)
+        .or_else(|| {
+            notebook
+                .tab_label(page)
+                .and_then(|tab| tab.first_child())
+                .and_downcast::<Label>()
+                .map(|label| label.text().to_string())
+        })
+        .unwrap_or_else(|| "Shell".to_string());
+
+    // Hold a reference so removing the page doesn't destroy the terminal
+    let page = page.clone();
+    notebook.remove_page(Some(page_num));
+
+    let window = gtk::Window::builder()
+        .title(&title)
+        .default_width(900)
+        .default_height(600)
+        .build();
+    if let Some(app) = notebook.root().and_downcast::<gtk::Window>().and_then(|w| w.application()) {
+        window.set_application(Some(&app));
+    }
+
+    let header = adw::HeaderBar::new();
+    let reattach_btn = Button::builder()
+        .icon_name("view-restore-symbolic")
+        .tooltip_text("Reattach to Main Window")
+        .build();
+    reattach_btn.add_css_class("flat");
+    header.pack_start(&reattach_btn);
+    window.set_titlebar(Some(&header));
+    window.set_child(Some(&page));
+
+    let window_clone = window.clone();
+    let notebook_clone = notebook.clone();
+    let page_clone = page.clone();
+    reattach_btn.connect_clicked(move |_| {
+        window_clone.set_child(None::<&gtk::Widget>);
+        let name = page_tab_name(&page_clone)
+            .unwrap_or_else(|| window_clone.title().unwrap_or_default().to_string());
+        let tab_label = create_editable_tab_label(&name, &notebook_clone);
+        let page_num = notebook_clone.append_page(&page_clone, Some(&tab_label));
+        notebook_clone.set_current_page(Some(page_num));
+        focus_terminal_in_page(&page_clone);
+        window_clone.destroy();
+    });
+
+    // Close Tab closes this window rather than the main window's current tab
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let window_clone2 = window.clone();
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        if get_keyboard_shortcuts().matches("close_tab", keyval, modifier) {
+            window_clone2.close();
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    window.add_controller(key_controller);
+
+    let page_clone = page.clone();
+    window.connect_close_request(move |window| {
+        let page = &page_clone;
+        let mut terminals = Vec::new();
+        collect_terminals(page, &mut terminals);
+        let Some(process_name) = terminals.iter().find_map(foreground_process_name) else {
+            forget_page_widgets(page);
+            return gtk::glib::Propagation::Proceed;
+        };
+
+        let dialog = gtk::MessageDialog::builder()
+            .transient_for(window)
+            .modal(true)
+            .buttons(gtk::ButtonsType::None)
+            .text("Close Window?")
+            .secondary_text(&format!(
+                "'{}' is still running in this window. Closing it will terminate the process.",
+                process_name
+            ))
+            .build();
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        let close_btn = dialog.add_button("Close Window", gtk::ResponseType::Accept);
+        close_btn.add_css_class("destructive-action");
+
+        let window_clone = window.clone();
+        let page_clone = page.clone();
+        dialog.connect_response(move |dialog, response| {
+            if response == gtk::ResponseType::Accept {
+                forget_page_widgets(&page_clone);
+                window_clone.destroy();
+            }
+            dialog.close();
+        });
+        dialog.show();
+        gtk::glib::Propagation::Stop
+    });
+
+    window.present();
+    focus_terminal_in_page(&page);
+}
+
+/// Closes the current tab unless it is one of the fixed tabs
+pub fn close_current_tab(notebook: &Notebook) {
+    let Some(page_num) = notebook.current_page() else {
+        return;
+    };
+    if is_protected_tab(page_num) {
+        return;
+    }
+    if let Some(page) = notebook.nth_page(Some(page_num)) {
+        close_tab_with_confirmation(notebook, &page);
+    }
+}
+
+/// Moves to the next or previous tab, wrapping around at either end
+pub fn cycle_tab(notebook: &Notebook, forward: bool) {
+    let n_pages = notebook.n_pages();
+    if n_pages == 0 {
+        return;
+    }
+    let current = notebook.current_page().unwrap_or(0);
+    let next = if forward { (current + 1) % n_pages } else { (current + n_pages - 1) % n_pages };
+    notebook.set_current_page(Some(next));
+}
+
+/// Handles next/previous tab keys: the configurable shortcuts plus Ctrl+Page Up/Down
+///
+/// Returns true if the key switched tabs.
+pub fn handle_tab_cycle_keys(notebook: &Notebook, keyval: gtk::gdk::Key, modifier: gtk::gdk::ModifierType) -> bool {
+    let shortcuts = get_keyboard_shortcuts();
+    let ctrl_only = modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK)
+        && !modifier.intersects(
+            gtk::gdk::ModifierType::SHIFT_MASK | gtk::gdk::ModifierType::ALT_MASK | gtk::gdk::ModifierType::SUPER_MASK,
+        );
+    if shortcuts.matches("next_tab", keyval, modifier) || (ctrl_only && keyval == gtk::gdk::Key::Page_Down) {
+        cycle_tab(notebook, true);
+        true
+    } else if shortcuts.matches("prev_tab", keyval, modifier) || (ctrl_only && keyval == gtk::gdk::Key::Page_Up) {
+        cycle_tab(notebook, false);
+        true
+    } else {
+        false
+    }
+}
+
+/// Closes a tab, asking for confirmation first if one of its terminals is running a command
+pub fn close_tab_with_confirmation(notebook: &Notebook, page: &gtk::Widget) {
+    let mut terminals = Vec::new();
+    collect_terminals(page, &mut terminals);
+    let running = terminals.iter().find_map(foreground_process_name);
+
+    let Some(process_name) = running else {
+        remove_tab_page(notebook, page);
+        return;
+    };
+
+    let mut builder = gtk::MessageDialog::builder()
+        .modal(true)
+        .buttons(gtk::ButtonsType::None)
+        .text("Close Tab?")
+        .secondary_text(&format!(
+            "'{}' is still running in this tab. Closing it will terminate the process.",
+            process_name
+        ));
+    if let Some(window) = notebook.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
+
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    let close_btn = dialog.add_button("Close Tab", gtk::ResponseType::Accept);
+    close_btn.add_css_class("destructive-action");
+
+    let notebook_clone = notebook.clone();
+    let page_clone = page.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response == gtk::ResponseType::Accept {
+            remove_tab_page(&notebook_clone, &page_clone);
+        }
+        dialog.close();
+    });
+
+    dialog.show();
 }
 
 /// Sets the terminal zoom scale and updates all terminals
@@ -39,7 +760,122 @@ pub fn set_terminal_zoom_scale(scale: f64) {
 
     let mut settings = get_app_settings();
     settings.terminal_zoom_scale = Some(clamped);
-    let _ = save_app_settings(&settings);
+    report_error(save_app_settings(&settings));
+}
+
+/// Built-in terminal color theme
+pub struct TerminalTheme {
+    pub name: &'static str,
+    foreground: &'static str,
+    background: &'static str,
+    palette: [&'static str; 16],
+}
+
+/// Built-in terminal themes; "System" follows the app color scheme instead
+pub const TERMINAL_THEMES: [TerminalTheme; 4] = [
+    TerminalTheme {
+        name: "Solarized Dark",
+        foreground: "#839496",
+        background: "#002B36",
+        palette: [
+            "#073642", "#DC322F", "#859900", "#B58900", "#268BD2", "#D33682", "#2AA198", "#EEE8D5",
+            "#002B36", "#CB4B16", "#586E75", "#657B83", "#839496", "#6C71C4", "#93A1A1", "#FDF6E3",
+        ],
+    },
+    TerminalTheme {
+        name: "Gruvbox Dark",
+        foreground: "#EBDBB2",
+        background: "#282828",
+        palette: [
+            "#282828", "#CC241D", "#98971A", "#D79921", "#458588", "#B16286", "#689D6A", "#A89984",
+            "#928374", "#FB4934", "#B8BB26", "#FABD2F", "#83A598", "#D3869B", "#8EC07C", "#EBDBB2",
+        ],
+    },
+    TerminalTheme {
+        name: "Tango",
+        foreground: "#D3D7CF",
+        background: "#2E3436",
+        palette: [
+            "#2E3436", "#CC0000", "#4E9A06", "#C4A000", "#3465A4", "#75507B", "#06989A", "#D3D7CF",
+            "#555753", "#EF2929", "#8AE234", "#FCE94F", "#729FCF", "#AD7FA8", "#34E2E2", "#EEEEEC",
+        ],
+    },
+    TerminalTheme {
+        name: "Dracula",
+        foreground: "#F8F8F2",
+        background: "#282A36",
+        palette: [
+            "#21222C", "#FF5555", "#50FA7B", "#F1FA8C", "#BD93F9", "#FF79C6", "#8BE9FD", "#F8F8F2",
+            "#6272A4", "#FF6E6E", "#69FF94", "#FFFFA5", "#D6ACFF", "#FF92DF", "#A4FFFF", "#FFFFFF",
+        ],
+    },
+];
+
+/// Applies the selected terminal theme, falling back to colors matching the app color scheme
+pub fn apply_terminal_colors(terminal: &Terminal) {
+    let theme_name = get_terminal_theme();
+    if let Some(theme) = TERMINAL_THEMES.iter().find(|t| t.name == theme_name) {
+        let foreground = gtk::gdk::RGBA::parse(theme.foreground).ok();
+        let background = gtk::gdk::RGBA::parse(theme.background).ok();
+        let palette: Vec<gtk::gdk::RGBA> = theme.palette
+            .iter()
+            .filter_map(|c| gtk::gdk::RGBA::parse(*c).ok())
+            .collect();
+        let palette_refs: Vec<&gtk::gdk::RGBA> = palette.iter().collect();
+        terminal.set_colors(foreground.as_ref(), background.as_ref(), &palette_refs);
+        return;
+    }
+
+    let (foreground, background) = if adw::StyleManager::default().is_dark() {
+        ("#D4D4D4", "#1E1E1E")
+    } else {
+        ("#1E1E1E", "#FAFAFA")
+    };
+    let foreground = gtk::gdk::RGBA::parse(foreground).ok();
+    let background = gtk::gdk::RGBA::parse(background).ok();
+    terminal.set_colors(foreground.as_ref(), background.as_ref(), &[]);
+}
+
+/// Sets the terminal theme and re-applies it to all terminals
+pub fn set_terminal_theme(name: &str) {
+    let mut settings = get_app_settings();
+    settings.terminal_theme = name.to_string();
+    report_error(save_app_settings(&settings));
+
+    refresh_terminal_colors();
+}
+
+/// Recolors all tracked terminals after a color scheme change
+pub fn refresh_terminal_colors() {
+    TERMINALS.with(|terminals| {
+        let terminals = terminals.borrow();
+        for terminal in terminals.iter() {
+            apply_terminal_colors(terminal);
+        }
+    });
+}
+
+/// Stops tracking a terminal for zoom and color updates once its tab is closed
+pub fn unregister_terminal(terminal: &Terminal) {
+    TERMINALS.with(|terminals| {
+        terminals.borrow_mut().retain(|t| t != terminal);
+    });
+}
+
+/// Returns the number of open terminals that broadcast input would reach
+pub fn terminal_count() -> usize {
+    TERMINALS.with(|terminals| terminals.borrow().len())
+}
+
+/// Feeds the same input to every open terminal, returning how many received it
+pub fn broadcast_to_terminals(text: &str) -> usize {
+    TERMINALS.with(|terminals| {
+        let terminals = terminals.borrow();
+        for terminal in terminals.iter() {
+            terminal.feed_child(text.as_bytes());
+        }
+        terminals.len()
+    })
 }
 
 /// Adds Ctrl+scroll zoom functionality to a VTE Terminal
@@ -48,6 +884,8 @@ fn add_terminal_scroll_zoom(terminal: &Terminal) {
         terminals.borrow_mut().push(terminal.clone());
     });
 
+    apply_terminal_colors(terminal);
+
     let current_scale = get_terminal_zoom_scale();
     terminal.set_font_scale(current_scale);
 
@@ -73,7 +911,7 @@ fn add_terminal_scroll_zoom(terminal: &Terminal) {
 }
 
 /// Creates an editable tab label
-pub fn create_editable_tab_label(initial_text: &str, _notebook: &Notebook) -> GtkBox {
+pub fn create_editable_tab_label(initial_text: &str, notebook: &Notebook) -> GtkBox {
     let tab_box = GtkBox::new(Orientation::Horizontal, 4);
     let label = Label::new(Some(initial_text));
 
@@ -137,6 +975,18 @@ pub fn create_editable_tab_label(initial_text: &str, _notebook: &Notebook) -> Gt
     label.add_controller(gesture);
     tab_box.append(&label);
 
+    // Keep the shell's id-to-name mapping current, from when the tab appears and on every rename
+    let notebook_clone3 = notebook.clone();
+    label.connect_label_notify(move |label| {
+        if let Some(tab_box) = label.parent() {
+            record_tab_name(&notebook_clone3, &tab_box);
+        }
+    });
+    let notebook_clone4 = notebook.clone();
+    tab_box.connect_map(move |tab_box| {
+        record_tab_name(&notebook_clone4, tab_box.upcast_ref());
+    });
+
     // Add close button to tab
     let close_btn = Button::builder()
         .icon_name("window-close-symbolic")
@@ -146,29 +996,13 @@ pub fn create_editable_tab_label(initial_text: &str, _notebook: &Notebook) -> Gt
     close_btn.set_has_frame(false);
 
     let close_btn_clone = close_btn.clone();
-    let notebook_clone = _notebook.clone();
+    let notebook_clone = notebook.clone();
     close_btn.connect_clicked(move |_| {
         if let Some(tab_box) = close_btn_clone.parent() {
-            if let Some(tab_box) = tab_box.downcast_ref::<GtkBox>() {
-                let notebook = &notebook_clone;
-                // Find which page this tab belongs to
-                for i in 0..notebook.n_pages() {
-                    if let Some(page) = notebook.nth_page(Some(i)) {
-                        if let Some(tab_label) = notebook.tab_label(&page) {
-                            if tab_label == tab_box.clone().upcast::<gtk::Widget>() {
-                                // Don't close first 3 tabs (targets, notes, log)
-                                let min_tabs = if is_command_logging_enabled() {
-                                    tabs::FIRST_SHELL
-                                } else {
-                                    tabs::LOG
-                                };
-                                if i >= min_tabs {
-                                    notebook.remove_page(Some(i));
-                                }
-                                break;
-                            }
-                        }
-                    }
+            // Find which page this tab belongs to
+            if let Some((i, page)) = page_for_tab_label(&notebook_clone, &tab_box) {
+                if !is_protected_tab(i) {
+                    close_tab_with_confirmation(&notebook_clone, &page);
                 }
             }
         }
@@ -176,22 +1010,69 @@ pub fn create_editable_tab_label(initial_text: &str, _notebook: &Notebook) -> Gt
 
     tab_box.append(&close_btn);
 
-    tab_box
-}
+    // Right-click menu for tabs holding a terminal
+    let right_click = gtk::GestureClick::new();
+    right_click.set_button(3);
+    let tab_box_clone = tab_box.clone();
+    let notebook_clone2 = notebook.clone();
+    right_click.connect_pressed(move |_, _, x, y| {
+        let Some((page_num, page)) = page_for_tab_label(&notebook_clone2, tab_box_clone.upcast_ref()) else {
+            return;
+        };
+        if is_protected_tab(page_num) || find_terminal_in(&page).is_none() {
+            return;
+        }
 
-/// Creates a shell tab with terminal
-pub fn create_shell_tab(
-    _shell_id: usize,
-    notebook: Notebook,
-    shell_counter: Option<Rc<RefCell<usize>>>,
-    toast_overlay: Option<adw::ToastOverlay>,
-    enable_logging: bool,
-) -> GtkBox {
-    let outer_container = GtkBox::new(Orientation::Vertical, 0);
+        let menu_model = gtk::gio::Menu::new();
+        menu_model.append(Some("Detach to Window"), Some("tab.detach"));
+        menu_model.append(Some("Close Tab"), Some("tab.close"));
+
+        let menu = gtk::PopoverMenu::from_model(Some(&menu_model));
+        menu.set_parent(&tab_box_clone);
+        menu.set_pointing_to(Some(&gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+
+        let actions = gtk::gio::SimpleActionGroup::new();
+
+        let detach_action = gtk::gio::SimpleAction::new("detach", None);
+        let notebook_detach = notebook_clone2.clone();
+        let page_detach = page.clone();
+        detach_action.connect_activate(move |_, _| {
+            detach_tab(&notebook_detach, &page_detach);
+        });
+        actions.add_action(&detach_action);
+
+        let close_action = gtk::gio::SimpleAction::new("close", None);
+        let notebook_close = notebook_clone2.clone();
+        close_action.connect_activate(move |_, _| {
+            close_tab_with_confirmation(&notebook_close, &page);
+        });
+        actions.add_action(&close_action);
+
+        tab_box_clone.insert_action_group("tab", Some(&actions));
+        menu.popup();
+    });
+    tab_box.add_controller(right_click);
+
+    tab_box
+}
+
+/// Creates a shell tab with terminal
+pub fn create_shell_tab(
+    _shell_id: usize,
+    notebook: Notebook,
+    shell_counter: Option<Rc<RefCell<usize>>>,
+    toast_overlay: Option<adw::ToastOverlay>,
+    enable_logging: bool,
+    command: Option<Vec<String>>,
+    start_dir: Option<PathBuf>,
+) -> GtkBox {
+    let outer_container = GtkBox::new(Orientation::Vertical, 0);
     outer_container.set_margin_top(6);
     outer_container.set_margin_bottom(6);
     outer_container.set_margin_start(6);
     outer_container.set_margin_end(6);
+    // Page kind, used to reopen the tab after it is closed
+    outer_container.set_widget_name(if enable_logging { "shell" } else { "shell_nolog" });
 
     // Target selector bar
     let target_box = GtkBox::new(Orientation::Horizontal, 6);
@@ -210,13 +1091,13 @@ pub fn create_shell_tab(
 
     let insert_target_btn = Button::builder()
         .icon_name("list-add-symbolic")
-        .tooltip_text("Insert Target (Ctrl+T)")
+        .tooltip_text(&shortcut_hint("Insert Target", "insert_target"))
         .build();
     insert_target_btn.add_css_class("flat");
 
     let drawer_toggle = gtk::ToggleButton::builder()
         .icon_name("view-list-symbolic")
-        .tooltip_text("Commands (Ctrl+`)")
+        .tooltip_text(&shortcut_hint("Commands", "toggle_drawer"))
         .build();
     drawer_toggle.add_css_class("flat");
 
@@ -228,8 +1109,10 @@ pub fn create_shell_tab(
 
     let terminal = Terminal::new();
     terminal.set_vexpand(true);
+    assign_tab_id(&terminal);
 
     add_terminal_scroll_zoom(&terminal);
+    add_terminal_link_matching(&terminal);
 
     // Build environment
     let mut env_vars = vec![
@@ -240,6 +1123,18 @@ pub fn create_shell_tab(
         format!("SHELL={}", std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())),
     ];
 
+    // Variables for the shell itself, which flatpak-spawn has to pass on to the host
+    let mut shell_env: Vec<String> = Vec::new();
+
+    // Report command start and exit status to the tab label (bash only, not override commands)
+    let mut prompt_cmds: Vec<String> = Vec::new();
+    let status_path = if command.is_none() { create_shell_status_file(&terminal) } else { None };
+    if let Some(ref status_path) = status_path {
+        prompt_cmds.push(STATUS_PROMPT_COMMAND.to_string());
+        shell_env.push(format!("PS0={}", STATUS_PS0));
+        shell_env.push(format!("PENENV_STATUS_FILE={}", status_path.display()));
+    }
+
     // Add command logging via PROMPT_COMMAND if enabled (globally and for this shell)
     if enable_logging && is_command_logging_enabled() {
         let log_file = get_file_path("commands.log").to_string_lossy().to_string();
@@ -247,28 +1142,42 @@ pub fn create_shell_tab(
             r#"history -a; __penenv_last_cmd=$(HISTTIMEFORMAT= history 1 | sed 's/^[ ]*[0-9]*[ ]*//'); if [ -z "$__penenv_prev_cmd" ]; then __penenv_prev_cmd="$__penenv_last_cmd"; fi; if [ -n "$__penenv_last_cmd" ] && [ "$__penenv_last_cmd" != "$__penenv_prev_cmd" ]; then echo "[$(date '+%Y-%m-%d %H:%M:%S')] $__penenv_last_cmd" >> '{}'; __penenv_prev_cmd="$__penenv_last_cmd"; fi"#,
             log_file
         );
-        env_vars.insert(0, format!("PROMPT_COMMAND={}", prompt_cmd));
+        prompt_cmds.push(prompt_cmd);
     }
+    if !prompt_cmds.is_empty() {
+        shell_env.push(format!("PROMPT_COMMAND={}", prompt_cmds.join("; ")));
+    }
+    env_vars.extend(shell_env.iter().cloned());
 
     let env_refs: Vec<&str> = env_vars.iter().map(|s| s.as_str()).collect();
 
     // Configure terminal scrollback
     terminal.set_scrollback_lines(crate::config::get_app_settings().terminal_scrollback_lines);
 
-    // Get the base directory to set as working directory
-    let working_dir = get_base_dir();
+    // Start in the requested directory (e.g. a duplicated tab's cwd), else the configured one
+    let working_dir = start_dir.filter(|dir| dir.is_dir()).unwrap_or_else(resolve_shell_start_dir);
     let working_dir_str = working_dir.to_str();
 
     // Detect if running inside Flatpak
     let in_flatpak = is_flatpak();
 
-    let shell_args: Vec<&str> = if in_flatpak {
-        vec!["flatpak-spawn", "--host", "--env=TERM=xterm-256color", "/bin/bash", "-l"]
+    // An override command (e.g. ssh for a connection profile) runs instead of bash
+    let flatpak_env: Vec<String> = shell_env.iter().map(|var| format!("--env={}", var)).collect();
+    let mut shell_args: Vec<&str> = if in_flatpak {
+        let mut args = vec!["flatpak-spawn", "--host", "--env=TERM=xterm-256color"];
+        args.extend(flatpak_env.iter().map(|arg| arg.as_str()));
+        args
     } else {
-        vec!["/bin/bash"]
+        Vec::new()
     };
+    match command.as_ref() {
+        Some(command) => shell_args.extend(command.iter().map(|arg| arg.as_str())),
+        None if in_flatpak => shell_args.extend(["/bin/bash", "-l"]),
+        None => shell_args.push("/bin/bash"),
+    }
 
 
+    let terminal_spawn = terminal.clone();
     let _ = terminal.spawn_async(
         vte4::PtyFlags::DEFAULT,
         working_dir_str,
@@ -278,24 +1187,36 @@ pub fn create_shell_tab(
         || {},
         -1,
         None::<&gtk::gio::Cancellable>,
-        |result| {
-            if let Err(e) = result {
-                log::error!("Failed to spawn shell: {:?}", e);
+        move |result| {
+            match result {
+                Ok(pid) => track_shell_pid(&terminal_spawn, pid.0),
+                Err(e) => show_error_toast(&format!("Failed to spawn shell: {}", e)),
             }
         },
     );
 
+    setup_exit_status_indicator(&terminal, &notebook);
+    if let Some(status_path) = status_path {
+        watch_shell_status(&terminal, &notebook, status_path);
+    }
+    setup_activity_indicator(&terminal, &notebook);
+
     terminal_container.append(&terminal);
 
+    // Scrollback search bar below the terminal, hidden until Ctrl+Shift+F
+    let find_bar = create_terminal_find_bar(&terminal);
+    terminal_container.append(&find_bar.bar);
+
     // Create command drawer
     let (drawer, search_entry) = create_command_drawer(&terminal, &drawer_toggle, &paned);
     drawer.set_visible(false);
 
     paned.set_start_child(Some(&terminal_container));
     paned.set_end_child(Some(&drawer));
-    paned.set_position(10000);
+    paned.set_position(DRAWER_CLOSED_POSITION);
     paned.set_shrink_start_child(false);
     paned.set_shrink_end_child(false);
+    remember_drawer_width(&paned, &drawer);
 
     // Drawer toggle
     let drawer_clone = drawer.clone();
@@ -304,10 +1225,10 @@ pub fn create_shell_tab(
     drawer_toggle.connect_toggled(move |btn| {
         drawer_clone.set_visible(btn.is_active());
         if btn.is_active() {
-            paned_clone.set_position(600);
+            paned_clone.set_position(drawer_open_position(&paned_clone));
             search_entry_clone.grab_focus();
         } else {
-            paned_clone.set_position(10000);
+            paned_clone.set_position(DRAWER_CLOSED_POSITION);
         }
     });
 
@@ -316,20 +1237,11 @@ pub fn create_shell_tab(
     let target_combo_clone = target_combo.clone();
     insert_target_btn.connect_clicked(move |_| {
         if let Some(target) = target_combo_clone.active_text() {
-            terminal_clone.feed_child(target.as_bytes());
+            terminal_clone.feed_child(shell_quote(&target).as_bytes());
             terminal_clone.grab_focus();
         }
     });
 
-    // Periodic log refresh
-    if is_command_logging_enabled() {
-        let notebook_clone = notebook.clone();
-        glib::timeout_add_seconds_local(2, move || {
-            refresh_log_viewer(&notebook_clone);
-            glib::ControlFlow::Continue
-        });
-    }
-
     target_box.append(&target_combo);
     target_box.append(&insert_target_btn);
     target_box.append(&drawer_toggle);
@@ -341,6 +1253,7 @@ pub fn create_shell_tab(
         shell_counter.clone(),
         &drawer_toggle,
         &search_entry,
+        &find_bar,
         toast_overlay,
     );
 
@@ -357,8 +1270,11 @@ fn setup_terminal_keyboard(
     shell_counter: Option<Rc<RefCell<usize>>>,
     drawer_toggle: &gtk::ToggleButton,
     search_entry: &gtk::SearchEntry,
-    _toast_overlay: Option<adw::ToastOverlay>,
+    find_bar: &TerminalFindBar,
+    toast_overlay: Option<adw::ToastOverlay>,
 ) {
+    setup_target_autocomplete(terminal);
+
     let key_controller = gtk::EventControllerKey::new();
     let terminal_clone = terminal.clone();
     let notebook_clone = notebook.clone();
@@ -367,37 +1283,55 @@ fn setup_terminal_keyboard(
     let shell_counter_clone = shell_counter.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
-
-            // Ctrl+Shift combinations
-            if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) {
-                if let Some(ref new_shell_key) = shortcuts.new_shell {
-                    if &key_name == new_shell_key {
-                        if let Some(ref _counter) = shell_counter_clone {
-                            // Would need toast_overlay to show notification
-                        }
-                        return gtk::glib::Propagation::Stop;
-                    }
-                }
-            }
+        let shortcuts = get_keyboard_shortcuts();
 
-            // Toggle drawer
-            if key_name == shortcuts.toggle_drawer {
-                drawer_toggle_clone.set_active(!drawer_toggle_clone.is_active());
-                if drawer_toggle_clone.is_active() {
-                    search_entry_clone.grab_focus();
-                }
-                return gtk::glib::Propagation::Stop;
+        if shortcuts.matches("new_shell", keyval, modifier) {
+            if let Some(ref _counter) = shell_counter_clone {
+                // Would need toast_overlay to show notification
             }
+            return gtk::glib::Propagation::Stop;
+        }
 
-            // Insert target
-            if key_name == shortcuts.insert_target {
-                show_target_selector_popup(&terminal_clone);
-                return gtk::glib::Propagation::Stop;
+        if shortcuts.matches("clear_terminal", keyval, modifier) {
+            clear_terminal(&terminal_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Toggle drawer
+        if shortcuts.matches("toggle_drawer", keyval, modifier) {
+            drawer_toggle_clone.set_active(!drawer_toggle_clone.is_active());
+            if drawer_toggle_clone.is_active() {
+                search_entry_clone.grab_focus();
             }
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Insert target
+        if shortcuts.matches("insert_target", keyval, modifier) {
+            show_target_selector_popup(&terminal_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Insert a timestamp comment to annotate the shell history
+        if shortcuts.matches("insert_timestamp", keyval, modifier) {
+            let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
+            let text = format!("{}{}", get_terminal_timestamp_prefix(), timestamp);
+            terminal_clone.feed_child(text.as_bytes());
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Close tab
+        if shortcuts.matches("close_tab", keyval, modifier) {
+            close_current_tab(&notebook_clone);
+            return gtk::glib::Propagation::Stop;
+        }
 
+        // Next/previous tab, handled here too since VTE swallows keys it receives
+        if handle_tab_cycle_keys(&notebook_clone, keyval, modifier) {
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
             // Tab switching
             let page_num = match keyval {
                 gtk::gdk::Key::_1 => Some(0),
@@ -423,13 +1357,18 @@ fn setup_terminal_keyboard(
     });
     terminal.add_controller(key_controller);
 
-    // Copy/paste shortcuts
+    // Copy/paste and find shortcuts
     let copy_paste_controller = gtk::EventControllerKey::new();
     let terminal_clone2 = terminal.clone();
+    let find_bar_clone = find_bar.clone();
     copy_paste_controller.connect_key_pressed(move |_, keyval, _, modifier| {
         if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) &&
            modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
             match keyval {
+                gtk::gdk::Key::F | gtk::gdk::Key::f => {
+                    find_bar_clone.open();
+                    return gtk::glib::Propagation::Stop;
+                }
                 gtk::gdk::Key::C | gtk::gdk::Key::c => {
                     terminal_clone2.copy_clipboard_format(vte4::Format::Text);
                     return gtk::glib::Propagation::Stop;
@@ -449,10 +1388,39 @@ fn setup_terminal_keyboard(
     let right_click = gtk::GestureClick::new();
     right_click.set_button(3);
     let terminal_clone3 = terminal.clone();
+    let notebook_clone2 = notebook.clone();
+    let toast_clone = toast_overlay.clone();
     right_click.connect_pressed(move |_, _, x, y| {
         let menu_model = gtk::gio::Menu::new();
         menu_model.append(Some("Copy"), Some("terminal.copy"));
         menu_model.append(Some("Paste"), Some("terminal.paste"));
+        // Links and addresses under the pointer get their own section at the top
+        let matched = terminal_link_at(&terminal_clone3, x, y);
+        let link_section = gtk::gio::Menu::new();
+        match matched.as_deref() {
+            Some(m) if is_terminal_path(m) => {
+                link_section.append(Some("Open File"), Some("terminal.open-link"));
+                link_section.append(Some("Copy Path"), Some("terminal.copy-match"));
+            }
+            Some(m) if is_terminal_url(m) => {
+                link_section.append(Some("Open Link"), Some("terminal.open-link"));
+                link_section.append(Some("Copy Link"), Some("terminal.copy-match"));
+            }
+            Some(_) => {
+                link_section.append(Some("Copy IP"), Some("terminal.copy-match"));
+                link_section.append(Some("Add to targets.txt"), Some("terminal.add-target"));
+            }
+            None => {}
+        }
+        if matched.is_some() {
+            menu_model.append_section(None, &link_section);
+        }
+
+        menu_model.append(Some("Send Selection to Notes"), Some("terminal.send-to-notes"));
+        menu_model.append(Some("Save Output..."), Some("terminal.save-output"));
+        menu_model.append(Some("Save Selection..."), Some("terminal.save-selection"));
+        menu_model.append(Some("Clear"), Some("terminal.clear"));
+        menu_model.append(Some("Reset"), Some("terminal.reset"));
 
         let menu = gtk::PopoverMenu::from_model(Some(&menu_model));
         menu.set_parent(&terminal_clone3);
@@ -474,12 +1442,534 @@ fn setup_terminal_keyboard(
         });
         actions.add_action(&paste_action);
 
+        let send_action = gtk::gio::SimpleAction::new("send-to-notes", None);
+        send_action.set_enabled(terminal_clone3.has_selection());
+        let terminal_send = terminal_clone3.clone();
+        let toast_send = toast_clone.clone();
+        send_action.connect_activate(move |_, _| {
+            send_selection_to_notes(&terminal_send, toast_send.clone());
+        });
+        actions.add_action(&send_action);
+
+        // The scrollback is only read once the item is chosen, since it can be large
+        let save_output_action = gtk::gio::SimpleAction::new("save-output", None);
+        let terminal_save = terminal_clone3.clone();
+        let toast_save = toast_clone.clone();
+        save_output_action.connect_activate(move |_, _| match terminal_contents(&terminal_save) {
+            Some(text) => show_save_output_dialog(&terminal_save, text, toast_save.clone()),
+            None => {
+                if let Some(ref overlay) = toast_save {
+                    let toast = adw::Toast::new("No terminal output to save");
+                    toast.set_timeout(2);
+                    overlay.add_toast(toast);
+                }
+            }
+        });
+        actions.add_action(&save_output_action);
+
+        let save_selection_action = gtk::gio::SimpleAction::new("save-selection", None);
+        save_selection_action.set_enabled(terminal_clone3.has_selection());
+        let terminal_save2 = terminal_clone3.clone();
+        let toast_save2 = toast_clone.clone();
+        save_selection_action.connect_activate(move |_, _| {
+            save_selection_to_file(&terminal_save2, toast_save2.clone());
+        });
+        actions.add_action(&save_selection_action);
+
+        if let Some(matched) = matched {
+            let open_link_action = gtk::gio::SimpleAction::new("open-link", None);
+            let link = matched.clone();
+            let terminal_link = terminal_clone3.clone();
+            open_link_action.connect_activate(move |_, _| {
+                open_terminal_link(&terminal_link, &link);
+            });
+            actions.add_action(&open_link_action);
+
+            let copy_match_action = gtk::gio::SimpleAction::new("copy-match", None);
+            let terminal_copy_match = terminal_clone3.clone();
+            let text = matched.clone();
+            copy_match_action.connect_activate(move |_, _| {
+                terminal_copy_match.clipboard().set_text(&text);
+            });
+            actions.add_action(&copy_match_action);
+
+            let add_target_action = gtk::gio::SimpleAction::new("add-target", None);
+            let notebook_target = notebook_clone2.clone();
+            let toast_target = toast_clone.clone();
+            add_target_action.connect_activate(move |_, _| {
+                add_target_from_terminal(&notebook_target, &matched, toast_target.as_ref());
+            });
+            actions.add_action(&add_target_action);
+        }
+
+        let clear_action = gtk::gio::SimpleAction::new("clear", None);
+        let terminal_clear = terminal_clone3.clone();
+        clear_action.connect_activate(move |_, _| {
+            clear_terminal(&terminal_clear);
+        });
+        actions.add_action(&clear_action);
+
+        let reset_action = gtk::gio::SimpleAction::new("reset", None);
+        let terminal_reset = terminal_clone3.clone();
+        reset_action.connect_activate(move |_, _| {
+            reset_terminal(&terminal_reset);
+        });
+        actions.add_action(&reset_action);
+
         terminal_clone3.insert_action_group("terminal", Some(&actions));
         menu.popup();
     });
     terminal.add_controller(right_click);
 }
 
+/// Patterns VTE highlights as clickable: http(s) URLs, IPv4 (with optional CIDR) and IPv6 addresses,
+/// then file paths; earlier patterns win where they overlap
+const TERMINAL_MATCH_PATTERNS: [&str; 4] = [
+    r#"\bhttps?://[^\s<>"'`]+[^\s<>"'`.,;:!?)\]]"#,
+    r"\b(?:(?:25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9]?[0-9])\.){3}(?:25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9]?[0-9])(?:/(?:3[0-2]|[12]?[0-9]))?\b",
+    r"(?i)\b(?:[0-9a-f]{1,4}:){7}[0-9a-f]{1,4}\b|\b(?:[0-9a-f]{1,4}:){1,6}:(?:[0-9a-f]{1,4}:){0,5}[0-9a-f]{1,4}\b|::1\b",
+    r"(?<![\w/.~:-])(?:file://|~|\.\.?)?/[\w.+@%~-]+(?:/[\w.+@%~-]*)*(?<!\.)",
+];
+
+/// PCRE2 multiline flag, required by VTE for match and search regexes
+const PCRE2_MULTILINE: u32 = 0x0000_0400;
+
+/// Returns true if a matched string is a URL (any scheme, as OSC 8 hyperlinks may use) rather than an IP address
+fn is_terminal_url(matched: &str) -> bool {
+    matched.contains("://")
+}
+
+/// Returns true if a matched string is a file path or file:// URI
+fn is_terminal_path(matched: &str) -> bool {
+    matched.starts_with("file://")
+        || matched.starts_with('/')
+        || matched.starts_with("~/")
+        || matched.starts_with("./")
+        || matched.starts_with("../")
+}
+
+/// Gets the link under the pointer: an OSC 8 hyperlink if the program emitted one, else a pattern match
+fn terminal_link_at(terminal: &Terminal, x: f64, y: f64) -> Option<String> {
+    terminal
+        .check_hyperlink_at(x, y)
+        .or_else(|| terminal.check_match_at(x, y).0)
+        .map(|link| link.to_string())
+}
+
+/// Resolves a matched path to a file, relative paths against the shell's working directory
+fn resolve_terminal_path(terminal: &Terminal, path: &str) -> gtk::gio::File {
+    if path.starts_with("file://") {
+        return gtk::gio::File::for_uri(path);
+    }
+    let resolved = if let Some(rest) = path.strip_prefix("~/") {
+        PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| "/".to_string())).join(rest)
+    } else if path.starts_with('/') {
+        PathBuf::from(path)
+    } else {
+        terminal_working_dir(terminal).unwrap_or_else(get_base_dir).join(path)
+    };
+    gtk::gio::File::for_path(resolved)
+}
+
+/// Opens a URL in the default browser, or a file path in its default application
+fn open_terminal_link(terminal: &Terminal, link: &str) {
+    let uri = if is_terminal_path(link) {
+        let file = resolve_terminal_path(terminal, link);
+        if !file.query_exists(None::<&gtk::gio::Cancellable>) {
+            show_error_toast(&format!("{} does not exist", link));
+            return;
+        }
+        file.uri().to_string()
+    } else {
+        link.to_string()
+    };
+    if let Err(e) = gtk::gio::AppInfo::launch_default_for_uri(&uri, None::<&gtk::gio::AppLaunchContext>) {
+        show_error_toast(&format!("Failed to open {}: {}", link, e));
+    }
+}
+
+/// Highlights URLs, IP addresses and file paths in terminal output; Ctrl+click opens links and paths
+fn add_terminal_link_matching(terminal: &Terminal) {
+    // Programs like ls --hyperlink and gcc emit explicit OSC 8 hyperlinks
+    terminal.set_allow_hyperlink(true);
+
+    for pattern in TERMINAL_MATCH_PATTERNS {
+        match vte4::Regex::for_match(pattern, PCRE2_MULTILINE) {
+            Ok(regex) => {
+                let tag = terminal.match_add_regex(&regex, 0);
+                terminal.match_set_cursor_name(tag, "pointer");
+            }
+            Err(e) => log::warn!("Invalid terminal match pattern {}: {}", pattern, e),
+        }
+    }
+
+    let click = gtk::GestureClick::new();
+    click.set_button(1);
+    let terminal_clone = terminal.clone();
+    click.connect_pressed(move |gesture, _, x, y| {
+        if !gesture.current_event_state().contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+            return;
+        }
+        let link = terminal_link_at(&terminal_clone, x, y)
+            .filter(|link| is_terminal_path(link) || is_terminal_url(link));
+        if let Some(link) = link {
+            gesture.set_state(gtk::EventSequenceState::Claimed);
+            open_terminal_link(&terminal_clone, &link);
+        }
+    });
+    terminal.add_controller(click);
+}
+
+/// Appends an address from terminal output to targets.txt and refreshes target lists
+fn add_target_from_terminal(notebook: &Notebook, target: &str, toast_overlay: Option<&adw::ToastOverlay>) {
+    let message = match append_target(target) {
+        Ok(true) => {
+            append_to_targets_editor(notebook, target);
+            reload_targets_in_shells(notebook);
+            format!("Added {} to targets", target)
+        }
+        Ok(false) => format!("{} is already a target", target),
+        Err(e) => e,
+    };
+    if let Some(overlay) = toast_overlay {
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(2);
+        overlay.add_toast(toast);
+    }
+}
+
+/// Mirrors a new target in the Targets tab without discarding unsaved edits there
+fn append_to_targets_editor(notebook: &Notebook, target: &str) {
+    let Some(targets_page) = notebook.nth_page(Some(tabs::TARGETS)) else { return };
+    let mut child = targets_page.first_child();
+    while let Some(current) = child {
+        if let Some(text_view) = current
+            .downcast_ref::<ScrolledWindow>()
+            .and_then(|scrolled| scrolled.child())
+            .and_downcast::<TextView>()
+        {
+            let buffer = text_view.buffer();
+            let mut end = buffer.end_iter();
+            let text = buffer.text(&buffer.start_iter(), &end, false);
+            let line = if text.is_empty() || text.ends_with('\n') {
+                format!("{}\n", target)
+            } else {
+                format!("\n{}\n", target)
+            };
+            buffer.insert(&mut end, &line);
+            return;
+        }
+        child = current.next_sibling();
+    }
+}
+
+/// PCRE2 compile flags for terminal search: case-insensitive, multiline (required by VTE)
+const TERMINAL_SEARCH_FLAGS: u32 = 0x0000_0008 | PCRE2_MULTILINE;
+
+/// Find bar searching a terminal's scrollback
+#[derive(Clone)]
+struct TerminalFindBar {
+    bar: GtkBox,
+    entry: gtk::SearchEntry,
+}
+
+impl TerminalFindBar {
+    /// Shows the bar and focuses the query, keeping any previous search
+    fn open(&self) {
+        self.bar.set_visible(true);
+        self.entry.grab_focus();
+        self.entry.select_region(0, -1);
+    }
+}
+
+/// Applies the query as the terminal's search regex, returning false if the regex is invalid
+fn set_terminal_search(terminal: &Terminal, query: &str, use_regex: bool) -> bool {
+    if query.is_empty() {
+        terminal.search_set_regex(None, 0);
+        return true;
+    }
+    let pattern = if use_regex { query.to_string() } else { regex::escape(query) };
+    match vte4::Regex::for_search(&pattern, TERMINAL_SEARCH_FLAGS) {
+        Ok(regex) => {
+            terminal.search_set_regex(Some(&regex), 0);
+            true
+        }
+        Err(_) => {
+            terminal.search_set_regex(None, 0);
+            false
+        }
+    }
+}
+
+/// Creates the scrollback find bar. Enter searches upwards from the newest output,
+/// Shift+Enter downwards, and Escape clears the highlight and returns to the shell.
+fn create_terminal_find_bar(terminal: &Terminal) -> TerminalFindBar {
+    terminal.search_set_wrap_around(true);
+
+    let bar = GtkBox::new(Orientation::Horizontal, 6);
+    bar.set_margin_top(6);
+    bar.set_visible(false);
+
+    let entry = gtk::SearchEntry::new();
+    entry.set_placeholder_text(Some("Find in scrollback"));
+    entry.set_hexpand(true);
+
+    let regex_toggle = gtk::ToggleButton::builder()
+        .label(".*")
+        .tooltip_text("Regular Expression")
+        .build();
+    regex_toggle.add_css_class("flat");
+
+    let prev_btn = Button::builder()
+        .icon_name("go-up-symbolic")
+        .tooltip_text("Previous Match (Enter)")
+        .build();
+    prev_btn.add_css_class("flat");
+
+    let next_btn = Button::builder()
+        .icon_name("go-down-symbolic")
+        .tooltip_text("Next Match (Shift+Enter)")
+        .build();
+    next_btn.add_css_class("flat");
+
+    let close_btn = Button::builder()
+        .icon_name("window-close-symbolic")
+        .tooltip_text("Close (Escape)")
+        .build();
+    close_btn.add_css_class("flat");
+
+    bar.append(&entry);
+    bar.append(&regex_toggle);
+    bar.append(&prev_btn);
+    bar.append(&next_btn);
+    bar.append(&close_btn);
+
+    // Re-apply the search when the query or regex mode changes, jumping to the newest match
+    let update_search = {
+        let terminal = terminal.clone();
+        let entry = entry.clone();
+        let regex_toggle = regex_toggle.clone();
+        move || {
+            let query = entry.text().to_string();
+            if set_terminal_search(&terminal, &query, regex_toggle.is_active()) {
+                entry.remove_css_class("error");
+                if !query.is_empty() {
+                    terminal.search_find_previous();
+                }
+            } else {
+                entry.add_css_class("error");
+            }
+        }
+    };
+    let update_search_clone = update_search.clone();
+    entry.connect_search_changed(move |_| update_search_clone());
+    regex_toggle.connect_toggled(move |_| update_search());
+
+    let terminal_clone = terminal.clone();
+    prev_btn.connect_clicked(move |_| {
+        terminal_clone.search_find_previous();
+    });
+    let terminal_clone2 = terminal.clone();
+    next_btn.connect_clicked(move |_| {
+        terminal_clone2.search_find_next();
+    });
+
+    let terminal_clone3 = terminal.clone();
+    entry.connect_activate(move |entry| {
+        if is_shift_held(entry) {
+            terminal_clone3.search_find_next();
+        } else {
+            terminal_clone3.search_find_previous();
+        }
+    });
+
+    let close = {
+        let terminal = terminal.clone();
+        let bar = bar.clone();
+        let entry = entry.clone();
+        move || {
+            entry.set_text("");
+            terminal.search_set_regex(None, 0);
+            bar.set_visible(false);
+            terminal.grab_focus();
+        }
+    };
+    let close_clone = close.clone();
+    entry.connect_stop_search(move |_| close_clone());
+    close_btn.connect_clicked(move |_| close());
+
+    TerminalFindBar { bar, entry }
+}
+
+/// Wraps terminal output in a timestamped fenced code block for notes
+fn format_notes_snippet(text: &str) -> String {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    format!("\n### Output [{}]\n```\n{}\n```\n", timestamp, text.trim_end())
+}
+
+/// Finds the notes view of the split view containing this terminal, if any
+fn find_split_notes_view(terminal: &Terminal) -> Option<TextView> {
+    let mut ancestor = terminal.parent();
+    while let Some(current) = ancestor {
+        if let Some(paned) = current.downcast_ref::<Paned>() {
+            let notes_view = paned
+                .start_child()
+                .and_then(|c| c.downcast::<GtkBox>().ok())
+                .and_then(|b| b.first_child())
+                .and_then(|c| c.downcast::<ScrolledWindow>().ok())
+                .and_then(|sw| sw.child())
+                .and_then(|c| c.downcast::<TextView>().ok());
+            if notes_view.is_some() {
+                return notes_view;
+            }
+        }
+        ancestor = current.parent();
+    }
+    None
+}
+
+/// Plain text of the terminal selection, or None when nothing meaningful is selected
+fn selected_text(terminal: &Terminal) -> Option<String> {
+    terminal
+        .text_selected(vte4::Format::Text)
+        .map(|text| text.to_string())
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// Sends the terminal selection to the adjacent notes pane, or appends it to notes.md.
+///
+/// The snippet goes into an open notes buffer when there is one so its autosave writes
+/// it out; notes.md is only written directly when no editor has it open.
+fn send_selection_to_notes(terminal: &Terminal, toast_overlay: Option<adw::ToastOverlay>) {
+    let Some(text) = selected_text(terminal) else { return };
+    let snippet = format_notes_snippet(&text);
+
+    let notes_path = get_file_path("notes.md");
+    let message = if let Some(notes_view) = find_split_notes_view(terminal) {
+        notes_view.buffer().insert_at_cursor(&snippet);
+        "Selection inserted into notes".to_string()
+    } else if let Some(buffer) = open_notes_buffer(&notes_path) {
+        buffer.insert(&mut buffer.end_iter(), &snippet);
+        "Selection appended to notes.md".to_string()
+    } else {
+        let mut content = fs::read_to_string(&notes_path).unwrap_or_default();
+        content.push_str(&snippet);
+        match atomic_write(&notes_path, content) {
+            Ok(()) => "Selection appended to notes.md".to_string(),
+            Err(e) => format!("Failed to write notes: {}", e),
+        }
+    };
+
+    if let Some(ref overlay) = toast_overlay {
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(2);
+        overlay.add_toast(toast);
+    }
+}
+
+/// Clears the screen and scrollback, then has the shell redraw its prompt.
+///
+/// Unlike typing `clear`, this also drops VTE's scrollback so later saves and searches
+/// only see new output. Only VTE's state is touched, so PROMPT_COMMAND logging keeps working.
+fn clear_terminal(terminal: &Terminal) {
+    terminal.reset(false, true);
+    terminal.feed_child(b"\x0c");
+}
+
+/// Fully resets the terminal, including tab stops and scrollback, then redraws the prompt
+fn reset_terminal(terminal: &Terminal) {
+    terminal.reset(true, true);
+    terminal.feed_child(b"\x0c");
+}
+
+/// Reads the scrollback and visible screen as plain text, or None when there is no output
+fn terminal_contents(terminal: &Terminal) -> Option<String> {
+    let stream = gtk::gio::MemoryOutputStream::new_resizable();
+    if let Err(e) = terminal.write_contents_sync(&stream, vte4::WriteFlags::Default, None::<&gtk::gio::Cancellable>) {
+        log::warn!("Failed to read terminal output: {}", e);
+        return None;
+    }
+    stream.close(None::<&gtk::gio::Cancellable>).ok()?;
+    let bytes = stream.steal_as_bytes();
+    let text = String::from_utf8_lossy(&bytes).trim_end().to_string();
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text + "\n")
+    }
+}
+
+/// Writes the scrollback of the page's shell to a file, masking secrets first
+///
+/// Returns false when the shell has no output worth saving.
+pub fn save_scrollback(page: &gtk::Widget, path: &std::path::Path) -> Result<bool, String> {
+    let Some(text) = find_terminal_in(page).and_then(|terminal| terminal_contents(&terminal)) else {
+        return Ok(false);
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = crate::ui::editor::redact_secrets(&text);
+    fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+/// Replays saved scrollback into the page's shell between dimmed separator lines
+pub fn restore_scrollback(page: &gtk::Widget, path: &std::path::Path) {
+    let (Some(terminal), Ok(text)) = (find_terminal_in(page), fs::read_to_string(path)) else {
+        return;
+    };
+    if text.trim().is_empty() {
+        return;
+    }
+    terminal.feed(b"\x1b[2m---- scrollback from previous session ----\x1b[0m\r\n");
+    // The terminal needs carriage returns to start each line at the left edge
+    terminal.feed(text.trim_end().replace('\n', "\r\n").as_bytes());
+    terminal.feed(b"\r\n\x1b[2m---- end of restored scrollback ----\x1b[0m\r\n");
+}
+
+/// Saves the terminal selection to a file
+fn save_selection_to_file(terminal: &Terminal, toast_overlay: Option<adw::ToastOverlay>) {
+    if let Some(text) = selected_text(terminal) {
+        show_save_output_dialog(terminal, text, toast_overlay);
+    }
+}
+
+/// Asks for a destination, defaulting to the base directory, and writes terminal text there
+fn show_save_output_dialog(terminal: &Terminal, text: String, toast_overlay: Option<adw::ToastOverlay>) {
+    let parent = terminal.root().and_downcast::<gtk::Window>();
+    let chooser = gtk::FileChooserNative::new(
+        Some("Save Terminal Output"),
+        parent.as_ref(),
+        gtk::FileChooserAction::Save,
+        Some("Save"),
+        Some("Cancel"),
+    );
+    let _ = chooser.set_current_folder(Some(&gtk::gio::File::for_path(get_base_dir())));
+    chooser.set_current_name(&format!(
+        "terminal-output-{}.txt",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    chooser.connect_response(move |chooser, response| {
+        if response != gtk::ResponseType::Accept {
+            return;
+        }
+        let Some(path) = chooser.file().and_then(|f| f.path()) else { return };
+        let message = match fs::write(&path, &text) {
+            Ok(()) => format!("Saved output to {}", path.display()),
+            Err(e) => format!("Failed to save output: {}", e),
+        };
+        if let Some(ref overlay) = toast_overlay {
+            let toast = adw::Toast::new(&message);
+            toast.set_timeout(3);
+            overlay.add_toast(toast);
+        }
+    });
+
+    chooser.show();
+}
+
 /// Creates command drawer widget
 fn create_command_drawer(
     terminal: &Terminal,
@@ -500,7 +1990,16 @@ fn create_command_drawer(
     search_entry.set_placeholder_text(Some("Search commands..."));
     search_entry.set_hexpand(true);
 
+    let sort_toggle = gtk::ToggleButton::builder()
+        .icon_name("view-sort-ascending-symbolic")
+        .tooltip_text("Sort commands by name")
+        .active(get_sort_commands_alphabetically())
+        .build();
+    sort_toggle.add_css_class("flat");
+    sort_toggle.set_margin_start(4);
+
     search_box.append(&search_entry);
+    search_box.append(&sort_toggle);
 
     let scrolled = ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Never)
@@ -512,173 +2011,681 @@ fn create_command_drawer(
     list_box.set_selection_mode(gtk::SelectionMode::Single);
     list_box.add_css_class("boxed-list");
 
-    let commands = Rc::new(load_command_templates());
+    // Commands are reloaded in place when custom_commands.yaml changes
+    let commands = Rc::new(RefCell::new(load_command_templates()));
     let commands_clone = Rc::clone(&commands);
 
-    // Populate commands
-    let mut category_widgets: HashMap<String, gtk::ListBoxRow> = HashMap::new();
-
-    for (idx, cmd) in commands.iter().enumerate() {
-        if !category_widgets.contains_key(&cmd.category) {
-            let category_row = gtk::ListBoxRow::new();
-            category_row.set_selectable(false);
-            category_row.set_activatable(false);
-
-            let category_label = Label::new(Some(&cmd.category));
-            category_label.set_halign(gtk::Align::Start);
-            category_label.set_margin_start(12);
-            category_label.set_margin_top(16);
-            category_label.set_margin_bottom(8);
-            category_label.add_css_class("heading");
-            category_label.add_css_class("dim-label");
-
-            category_row.set_child(Some(&category_label));
-            list_box.append(&category_row);
-            category_widgets.insert(cmd.category.clone(), category_row);
-        }
-
-        let row = adw::ActionRow::new();
-        row.set_title(&cmd.name);
-        row.set_subtitle(&cmd.description);
-        row.set_activatable(true);
-        row.set_tooltip_text(Some(&format!("{}\n\nCommand: {}", cmd.description, cmd.command)));
-        row.set_widget_name(&format!("cmd_{}", idx));
-
-        // Use a wrapper ListBoxRow
-        let list_row = gtk::ListBoxRow::new();
-        list_row.set_child(Some(&row));
-        list_row.set_widget_name(&format!("cmd_{}", idx));
-        list_box.append(&list_row);
-    }
+    // Category chips, in the order categories first appear
+    let chips_box = gtk::FlowBox::new();
+    chips_box.set_selection_mode(gtk::SelectionMode::None);
+    chips_box.set_max_children_per_line(10);
+    chips_box.set_row_spacing(4);
+    chips_box.set_column_spacing(4);
+    chips_box.set_margin_start(8);
+    chips_box.set_margin_end(8);
+    chips_box.set_margin_bottom(8);
+
+    // Categories differing only in case share one heading and chip
+    let categories = Rc::new(RefCell::new(command_categories(&commands.borrow())));
+    let selected_categories: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+    let collapsed_categories: Rc<RefCell<HashSet<String>>> =
+        Rc::new(RefCell::new(get_collapsed_categories().into_iter().collect()));
+    let os_filter: Rc<Cell<CommandOs>> = Rc::new(Cell::new(CommandOs::Any));
+
+    // Commands grouped under their category heading; clicking a heading collapses it
+    populate_command_rows(&list_box, &commands.borrow(), &categories.borrow(), get_sort_commands_alphabetically());
+    populate_recent_commands(&list_box, &commands.borrow());
+    filter_command_rows(&list_box, &commands.borrow(), "", &HashSet::new(), &collapsed_categories.borrow(), CommandOs::Any);
 
     scrolled.set_child(Some(&list_box));
 
-    // Handle command selection
+    // Re-applies the search, chips and collapsed headings; weak refs so the drawer can be freed
+    let list_box_weak = list_box.downgrade();
+    let search_entry_weak = search_entry.downgrade();
+    let commands_clone5 = Rc::clone(&commands_clone);
+    let selected_categories_clone5 = Rc::clone(&selected_categories);
+    let collapsed_categories_clone5 = Rc::clone(&collapsed_categories);
+    let os_filter_clone5 = Rc::clone(&os_filter);
+    let refilter: Rc<dyn Fn()> = Rc::new(move || {
+        let (Some(list_box), Some(search_entry)) = (list_box_weak.upgrade(), search_entry_weak.upgrade()) else {
+            return;
+        };
+        filter_command_rows(
+            &list_box,
+            &commands_clone5.borrow(),
+            &search_entry.text().to_lowercase(),
+            &selected_categories_clone5.borrow(),
+            &collapsed_categories_clone5.borrow(),
+            os_filter_clone5.get(),
+        );
+    });
+
+    // Handle command selection and heading clicks
     let terminal_clone = terminal.clone();
     let commands_clone2 = Rc::clone(&commands_clone);
     let drawer_toggle_clone = drawer_toggle.clone();
     let paned_clone = paned.clone();
-    list_box.connect_row_activated(move |_, row| {
+    let collapsed_categories_clone0 = Rc::clone(&collapsed_categories);
+    let refilter_clone0 = Rc::clone(&refilter);
+    list_box.connect_row_activated(move |list_box, row| {
         let name = row.widget_name();
-        if let Some(idx_str) = name.strip_prefix("cmd_") {
+        if let Some(key) = name.strip_prefix("category_") {
+            toggle_category_collapsed(&collapsed_categories_clone0, key);
+            refilter_clone0();
+            return;
+        }
+        if let Some(idx_str) = name.trim_start_matches("recent_").strip_prefix("cmd_") {
             if let Ok(idx) = idx_str.parse::<usize>() {
-                if let Some(cmd) = commands_clone2.get(idx) {
-                    if cmd.command.contains("{target}") {
-                        show_target_selector_for_command(&terminal_clone, cmd.command.clone());
-                    } else {
-                        terminal_clone.feed_child(cmd.command.as_bytes());
-                        terminal_clone.feed_child(b" ");
-                        terminal_clone.grab_focus();
-                    }
+                let cmd = commands_clone2.borrow().get(idx).cloned();
+                if let Some(cmd) = cmd {
+                    // Shift+activate runs the command regardless of its auto-run flag
+                    let run_now = cmd.autorun || is_shift_held(list_box);
+                    run_command_template(&terminal_clone, &cmd, run_now);
 
                     drawer_toggle_clone.set_active(false);
-                    paned_clone.set_position(10000);
+                    paned_clone.set_position(DRAWER_CLOSED_POSITION);
+                    populate_recent_commands(list_box, &commands_clone2.borrow());
                 }
             }
         }
     });
 
     // Search functionality
-    let list_box_clone = list_box.clone();
-    let commands_clone3 = Rc::clone(&commands_clone);
-    search_entry.connect_search_changed(move |entry| {
-        let search_text = entry.text().to_lowercase();
-        let is_searching = !search_text.is_empty();
-
-        let mut visible_categories: HashSet<String> = HashSet::new();
-
-        if is_searching {
-            for cmd in commands_clone3.iter() {
-                let matches = cmd.name.to_lowercase().contains(&search_text)
-                    || cmd.description.to_lowercase().contains(&search_text)
-                    || cmd.command.to_lowercase().contains(&search_text)
-                    || cmd.category.to_lowercase().contains(&search_text);
-                if matches {
-                    visible_categories.insert(cmd.category.clone());
+    let refilter_clone = Rc::clone(&refilter);
+    search_entry.connect_search_changed(move |_| {
+        refilter_clone();
+    });
+
+    // Switch between file order and alphabetical order within each category
+    let list_box_clone4 = list_box.clone();
+    let commands_clone4 = Rc::clone(&commands_clone);
+    let categories_clone = Rc::clone(&categories);
+    let refilter_clone4 = Rc::clone(&refilter);
+    sort_toggle.connect_toggled(move |toggle| {
+        let mut settings = get_app_settings();
+        settings.sort_commands_alphabetically = toggle.is_active();
+        report_error(save_app_settings(&settings));
+
+        populate_command_rows(&list_box_clone4, &commands_clone4.borrow(), &categories_clone.borrow(), toggle.is_active());
+        refilter_clone4();
+    });
+
+    // OS chips show only commands for that OS (plus untagged ones); at most one is active
+    let os_chips: Rc<RefCell<Vec<gtk::ToggleButton>>> = Rc::new(RefCell::new(Vec::new()));
+    for os in [CommandOs::Linux, CommandOs::Windows] {
+        let chip = gtk::ToggleButton::with_label(os.label());
+        chip.add_css_class("caption");
+        chip.set_tooltip_text(Some(&format!("Only show {} commands", os.label())));
+        let os_filter_clone = Rc::clone(&os_filter);
+        let os_chips_clone = Rc::clone(&os_chips);
+        let refilter_clone = Rc::clone(&refilter);
+        chip.connect_toggled(move |chip| {
+            if chip.is_active() {
+                os_filter_clone.set(os);
+                // Deactivating the other chip re-enters this handler, so release the borrow first
+                let others: Vec<gtk::ToggleButton> =
+                    os_chips_clone.borrow().iter().filter(|other| *other != chip).cloned().collect();
+                for other in others {
+                    other.set_active(false);
                 }
+            } else if os_filter_clone.get() == os {
+                os_filter_clone.set(CommandOs::Any);
             }
-        }
+            refilter_clone();
+        });
+        os_chips.borrow_mut().push(chip.clone());
+        chips_box.insert(&chip, -1);
+    }
 
-        let mut child = list_box_clone.first_child();
-        while let Some(row) = child {
-            if let Some(list_row) = row.downcast_ref::<gtk::ListBoxRow>() {
-                let name = list_row.widget_name();
-
-                if let Some(idx_str) = name.strip_prefix("cmd_") {
-                    if let Ok(idx) = idx_str.parse::<usize>() {
-                        if let Some(cmd) = commands_clone3.get(idx) {
-                            if is_searching {
-                                let matches = cmd.name.to_lowercase().contains(&search_text)
-                                    || cmd.description.to_lowercase().contains(&search_text)
-                                    || cmd.command.to_lowercase().contains(&search_text)
-                                    || cmd.category.to_lowercase().contains(&search_text);
-                                list_row.set_visible(matches);
-                            } else {
-                                list_row.set_visible(true);
-                            }
-                        }
-                    }
-                } else if !list_row.is_selectable() {
-                    if is_searching {
-                        if let Some(child_widget) = list_row.child() {
-                            if let Some(label) = child_widget.downcast_ref::<Label>() {
-                                let category_text = label.text();
-                                list_row.set_visible(visible_categories.contains(category_text.as_str()));
+    // Category chips narrow the list, composing with the text search
+    append_category_chips(&chips_box, &categories.borrow(), &selected_categories, &refilter);
+
+    // Rebuild rows and category chips from the files; row names are re-indexed from scratch
+    let list_box_weak = list_box.downgrade();
+    let chips_box_weak = chips_box.downgrade();
+    let sort_toggle_weak = sort_toggle.downgrade();
+    register_command_drawer(&list_box, move || {
+        let (Some(list_box), Some(chips_box), Some(sort_toggle)) =
+            (list_box_weak.upgrade(), chips_box_weak.upgrade(), sort_toggle_weak.upgrade())
+        else {
+            return;
+        };
+        *commands.borrow_mut() = load_command_templates();
+        *categories.borrow_mut() = command_categories(&commands.borrow());
+        let keys: HashSet<String> = categories.borrow().iter().map(|c| category_key(c)).collect();
+        selected_categories.borrow_mut().retain(|key| keys.contains(key));
+
+        populate_command_rows(&list_box, &commands.borrow(), &categories.borrow(), sort_toggle.is_active());
+        populate_recent_commands(&list_box, &commands.borrow());
+        append_category_chips(&chips_box, &categories.borrow(), &selected_categories, &refilter);
+        refilter();
+    });
+
+    // Keyboard navigation in search
+    let search_key_controller = gtk::EventControllerKey::new();
+    let list_box_clone2 = list_box.clone();
+    let drawer_toggle_clone2 = drawer_toggle.clone();
+    search_key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        // Ctrl+1-9 runs the Nth visible command instead of switching tabs
+        if activate_numbered_command(&list_box_clone2, keyval, modifier) {
+            return gtk::glib::Propagation::Stop;
+        }
+        match keyval {
+            gtk::gdk::Key::Down => {
+                list_box_clone2.grab_focus();
+                if let Some(first_row) = list_box_clone2.first_child() {
+                    let mut current = Some(first_row);
+                    while let Some(row) = current {
+                        if let Some(list_row) = row.downcast_ref::<gtk::ListBoxRow>() {
+                            if list_row.is_visible() && list_row.is_selectable() {
+                                list_box_clone2.select_row(Some(list_row));
+                                break;
                             }
                         }
-                    } else {
-                        list_row.set_visible(true);
+                        current = row.next_sibling();
                     }
                 }
+                return gtk::glib::Propagation::Stop;
+            }
+            _ if get_keyboard_shortcuts().matches("toggle_drawer", keyval, modifier) => {
+                drawer_toggle_clone2.set_active(false);
+                return gtk::glib::Propagation::Stop;
+            }
+            gtk::gdk::Key::Escape => {
+                drawer_toggle_clone2.set_active(false);
+                return gtk::glib::Propagation::Stop;
+            }
+            _ => {}
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    search_entry.add_controller(search_key_controller);
+
+    // The same digits work after arrowing down into the list
+    let list_key_controller = gtk::EventControllerKey::new();
+    let list_box_clone3 = list_box.clone();
+    list_key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        if activate_numbered_command(&list_box_clone3, keyval, modifier) {
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    list_box.add_controller(list_key_controller);
+
+    drawer.append(&search_box);
+    drawer.append(&chips_box);
+    drawer.append(&scrolled);
+
+    (drawer, search_entry)
+}
+
+/// Replaces the category chips after the OS chips, keeping any that are still selected active
+fn append_category_chips(
+    chips_box: &gtk::FlowBox,
+    categories: &[String],
+    selected_categories: &Rc<RefCell<HashSet<String>>>,
+    refilter: &Rc<dyn Fn()>,
+) {
+    let mut child = chips_box.first_child();
+    while let Some(current) = child {
+        child = current.next_sibling();
+        let is_category_chip = current
+            .downcast_ref::<gtk::FlowBoxChild>()
+            .and_then(|flow_child| flow_child.child())
+            .is_some_and(|chip| chip.widget_name() == "category_chip");
+        if is_category_chip {
+            chips_box.remove(&current);
+        }
+    }
+
+    for category in categories {
+        let chip = gtk::ToggleButton::with_label(category);
+        chip.add_css_class("caption");
+        chip.set_widget_name("category_chip");
+        chip.set_active(selected_categories.borrow().contains(&category_key(category)));
+        let key = category_key(category);
+        let selected_categories_clone = Rc::clone(selected_categories);
+        let refilter_clone = Rc::clone(refilter);
+        chip.connect_toggled(move |chip| {
+            if chip.is_active() {
+                selected_categories_clone.borrow_mut().insert(key.clone());
+            } else {
+                selected_categories_clone.borrow_mut().remove(&key);
+            }
+            refilter_clone();
+        });
+        chips_box.insert(&chip, -1);
+    }
+}
+
+/// Registers a drawer's reload callback; dropped once the drawer's list is gone
+pub fn register_command_drawer<F: Fn() + 'static>(list_box: &gtk::ListBox, reload: F) {
+    COMMAND_DRAWERS.with(|drawers| {
+        drawers.borrow_mut().push((list_box.downgrade(), Rc::new(reload)));
+    });
+}
+
+/// Reloads the commands in every open drawer, e.g. after custom_commands.yaml changed
+pub fn reload_command_drawers() {
+    let reloads: Vec<Rc<dyn Fn()>> = COMMAND_DRAWERS.with(|drawers| {
+        let mut drawers = drawers.borrow_mut();
+        drawers.retain(|(list_box, _)| list_box.upgrade().is_some());
+        drawers.iter().map(|(_, reload)| Rc::clone(reload)).collect()
+    });
+    for reload in reloads {
+        reload();
+    }
+}
+
+/// Watches custom_commands.yaml so external edits show up in open drawers without a restart
+pub fn watch_custom_commands() {
+    let file = gtk::gio::File::for_path(get_custom_commands_path());
+    let monitor = match file.monitor_file(gtk::gio::FileMonitorFlags::NONE, gtk::gio::Cancellable::NONE) {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            log::warn!("Failed to watch custom commands: {}", e);
+            return;
+        }
+    };
+    monitor.connect_changed(|_, _, _, event| {
+        if matches!(event, gtk::gio::FileMonitorEvent::ChangesDoneHint | gtk::gio::FileMonitorEvent::Deleted) {
+            reload_command_drawers();
+            if let Err(message) = read_custom_commands() {
+                show_config_error(&message, get_custom_commands_path());
+            }
+        }
+    });
+    CUSTOM_COMMANDS_MONITOR.with(|m| *m.borrow_mut() = Some(monitor));
+}
+
+/// Activates the Nth visible drawer command for Ctrl+N (1-9), returning true if one ran
+fn activate_numbered_command(list_box: &gtk::ListBox, keyval: gtk::gdk::Key, modifier: gtk::gdk::ModifierType) -> bool {
+    if !modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+        return false;
+    }
+    let Some(n) = keyval.to_unicode().and_then(|c| c.to_digit(10)).filter(|n| *n >= 1) else {
+        return false;
+    };
+
+    let mut seen = 0;
+    let mut child = list_box.first_child();
+    while let Some(current) = child {
+        child = current.next_sibling();
+        let Some(row) = current.downcast_ref::<gtk::ListBoxRow>() else { continue };
+        if row.is_visible() && row.is_selectable() && row.widget_name().contains("cmd_") {
+            seen += 1;
+            if seen == n {
+                // Goes through row-activated, so it behaves exactly like a click
+                row.activate();
+                return true;
             }
-            child = row.next_sibling();
         }
+    }
+    false
+}
+
+/// Rebuilds the category headings and command rows below the Recent section
+fn populate_command_rows(
+    list_box: &gtk::ListBox,
+    commands: &[crate::commands::CommandTemplate],
+    categories: &[String],
+    alphabetical: bool,
+) {
+    let mut child = list_box.first_child();
+    while let Some(row) = child {
+        child = row.next_sibling();
+        if !row.widget_name().starts_with("recent_") {
+            list_box.remove(&row);
+        }
+    }
+
+    for category in categories {
+        let key = category_key(category);
+        let category_row = gtk::ListBoxRow::new();
+        category_row.set_selectable(false);
+        category_row.set_activatable(true);
+        category_row.set_widget_name(&format!("category_{}", key));
+
+        let heading_box = GtkBox::new(Orientation::Horizontal, 6);
+        heading_box.set_margin_start(12);
+        heading_box.set_margin_top(16);
+        heading_box.set_margin_bottom(8);
+
+        let arrow = gtk::Image::from_icon_name("pan-down-symbolic");
+        arrow.add_css_class("dim-label");
+
+        let category_label = Label::new(Some(category));
+        category_label.set_halign(gtk::Align::Start);
+        category_label.add_css_class("heading");
+        category_label.add_css_class("dim-label");
+
+        heading_box.append(&arrow);
+        heading_box.append(&category_label);
+        category_row.set_child(Some(&heading_box));
+        list_box.append(&category_row);
+
+        for idx in category_command_indices(commands, &key, alphabetical) {
+            let row = create_command_row(&commands[idx]);
+            row.set_widget_name(&format!("cmd_{}", idx));
+
+            // Use a wrapper ListBoxRow
+            let list_row = gtk::ListBoxRow::new();
+            list_row.set_child(Some(&row));
+            list_row.set_widget_name(&format!("cmd_{}", idx));
+            list_box.append(&list_row);
+        }
+    }
+}
+
+/// Shows drawer rows matching the search text, selected categories (lowercased keys) and OS.
+///
+/// Commands in collapsed categories are hidden unless a search is active, which
+/// temporarily expands every category with a match. Category headings stay visible
+/// while one of their commands matches, even when collapsed.
+fn filter_command_rows(
+    list_box: &gtk::ListBox,
+    commands: &[crate::commands::CommandTemplate],
+    search_text: &str,
+    categories: &HashSet<String>,
+    collapsed: &HashSet<String>,
+    os: CommandOs,
+) {
+    let mut rows: Vec<gtk::ListBoxRow> = Vec::new();
+    let mut child = list_box.first_child();
+    while let Some(row) = child {
+        child = row.next_sibling();
+        if let Ok(list_row) = row.downcast::<gtk::ListBoxRow>() {
+            rows.push(list_row);
+        }
+    }
+
+    // Command rows first; `matching` ignores collapse so headings know whether they have matches
+    let mut matching: HashSet<String> = HashSet::new();
+    for list_row in &rows {
+        let name = list_row.widget_name();
+        let Some(idx_str) = name.trim_start_matches("recent_").strip_prefix("cmd_") else { continue };
+        if let Some(cmd) = idx_str.parse::<usize>().ok().and_then(|idx| commands.get(idx)) {
+            let key = category_key(&cmd.category);
+            let matches_search = search_text.is_empty()
+                || cmd.name.to_lowercase().contains(search_text)
+                || cmd.description.to_lowercase().contains(search_text)
+                || cmd.command.to_lowercase().contains(search_text)
+                || cmd.category.to_lowercase().contains(search_text);
+            let matches_category = categories.is_empty() || categories.contains(&key);
+            let is_match = matches_search && matches_category && cmd.os.matches(os);
+            if is_match {
+                matching.insert(name.to_string());
+            }
+            // The Recent section has no collapse of its own
+            let is_collapsed = search_text.is_empty() && !name.starts_with("recent_") && collapsed.contains(&key);
+            list_row.set_visible(is_match && !is_collapsed);
+        }
+    }
+
+    // Then headings, which precede their commands
+    for (i, list_row) in rows.iter().enumerate() {
+        if list_row.is_selectable() {
+            continue;
+        }
+        let has_matching_command = rows[i + 1..]
+            .iter()
+            .take_while(|row| row.is_selectable())
+            .any(|row| matching.contains(row.widget_name().as_str()));
+        list_row.set_visible(has_matching_command);
+
+        if let Some(key) = list_row.widget_name().strip_prefix("category_") {
+            let expanded = !search_text.is_empty() || !collapsed.contains(key);
+            let arrow = list_row
+                .child()
+                .and_then(|heading| heading.first_child())
+                .and_downcast::<gtk::Image>();
+            if let Some(arrow) = arrow {
+                arrow.set_icon_name(Some(if expanded { "pan-down-symbolic" } else { "pan-end-symbolic" }));
+            }
+        }
+    }
+}
+
+/// Flips a drawer category between collapsed and expanded and remembers it in settings
+fn toggle_category_collapsed(collapsed: &Rc<RefCell<HashSet<String>>>, key: &str) {
+    {
+        let mut collapsed = collapsed.borrow_mut();
+        if !collapsed.remove(key) {
+            collapsed.insert(key.to_string());
+        }
+    }
+    let mut settings = get_app_settings();
+    let mut keys: Vec<String> = collapsed.borrow().iter().cloned().collect();
+    keys.sort();
+    settings.collapsed_categories = keys;
+    report_error(save_app_settings(&settings));
+}
+
+/// Heading of the drawer section listing recently used commands
+const RECENT_CATEGORY: &str = "Recent";
+
+/// Rebuilds the Recent section at the top of the command drawer list
+fn populate_recent_commands(list_box: &gtk::ListBox, commands: &[crate::commands::CommandTemplate]) {
+    // Drop the previous Recent rows, which are always at the top
+    while let Some(row) = list_box.row_at_index(0) {
+        if !row.widget_name().starts_with("recent_") {
+            break;
+        }
+        list_box.remove(&row);
+    }
+
+    let indices: Vec<usize> = load_recent_commands()
+        .iter()
+        .filter_map(|recent_cmd| commands.iter().position(|c| c.command.replace('\n', " ") == *recent_cmd))
+        .collect();
+    if indices.is_empty() {
+        return;
+    }
+
+    let header_row = gtk::ListBoxRow::new();
+    header_row.set_selectable(false);
+    header_row.set_activatable(false);
+    header_row.set_widget_name("recent_header");
+
+    let header_label = Label::new(Some(RECENT_CATEGORY));
+    header_label.set_halign(gtk::Align::Start);
+    header_label.set_margin_start(12);
+    header_label.set_margin_top(16);
+    header_label.set_margin_bottom(8);
+    header_label.add_css_class("heading");
+    header_label.add_css_class("dim-label");
+    header_row.set_child(Some(&header_label));
+    list_box.insert(&header_row, 0);
+
+    for (position, idx) in indices.iter().enumerate() {
+        let cmd = &commands[*idx];
+        let row = create_command_row(cmd);
+
+        let list_row = gtk::ListBoxRow::new();
+        list_row.set_child(Some(&row));
+        list_row.set_widget_name(&format!("recent_cmd_{}", idx));
+        list_box.insert(&list_row, position as i32 + 1);
+    }
+}
+
+/// Builds the drawer row for a command, with a button opening its reference link if it has one
+fn create_command_row(cmd: &crate::commands::CommandTemplate) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(&cmd.name);
+    row.set_subtitle(&cmd.description);
+    row.set_activatable(true);
+
+    let mut tooltip = format!("{}\n\nCommand: {}", cmd.description, cmd.command);
+    let reference = cmd.reference.trim().to_string();
+    if !reference.is_empty() {
+        tooltip.push_str(&format!("\nReference: {}", reference));
+
+        let reference_btn = Button::builder()
+            .icon_name("help-browser-symbolic")
+            .tooltip_text(&format!("Open reference: {}", reference))
+            .valign(gtk::Align::Center)
+            .build();
+        reference_btn.add_css_class("flat");
+        reference_btn.connect_clicked(move |_| {
+            open_command_reference(&reference);
+        });
+        row.add_suffix(&reference_btn);
+    }
+    if cmd.os != CommandOs::Any {
+        let os_label = Label::new(Some(cmd.os.label()));
+        os_label.add_css_class("caption");
+        os_label.add_css_class("dim-label");
+        row.add_suffix(&os_label);
+    }
+    row.set_tooltip_text(Some(&tooltip));
+    row
+}
+
+/// Opens a command's reference link in the default browser
+pub fn open_command_reference(reference: &str) {
+    if let Err(e) = gtk::gio::AppInfo::launch_default_for_uri(reference, None::<&gtk::gio::AppLaunchContext>) {
+        show_error_toast(&format!("Failed to open {}: {}", reference, e));
+    }
+}
+
+/// Checks whether Shift is currently held on the keyboard of the widget's display
+fn is_shift_held(widget: &impl IsA<gtk::Widget>) -> bool {
+    widget
+        .display()
+        .default_seat()
+        .and_then(|seat| seat.keyboard())
+        .map(|keyboard| keyboard.modifier_state().contains(gtk::gdk::ModifierType::SHIFT_MASK))
+        .unwrap_or(false)
+}
+
+/// Records a template as recently used and sends it to the terminal,
+/// prompting for any placeholders that saved or detected variables don't fill
+pub fn run_command_template(terminal: &Terminal, cmd: &crate::commands::CommandTemplate, run_now: bool) {
+    if let Err(e) = record_recent_command(&cmd.command) {
+        log::warn!("{}", e);
+    }
+
+    let command = fill_placeholders(&cmd.command, &resolve_command_variables());
+    let placeholders = extract_placeholders(&command);
+    if placeholders.is_empty() {
+        send_command(terminal, &command, run_now);
+        terminal.grab_focus();
+    } else if placeholders == ["target"] {
+        show_target_selector_for_command(terminal, command, run_now);
+    } else {
+        show_placeholder_form(terminal, command, placeholders, run_now);
+    }
+}
+
+/// Feeds a command to the terminal, either pressing Enter or leaving it for editing
+fn send_command(terminal: &Terminal, command: &str, run_now: bool) {
+    if is_command_preview_enabled() {
+        show_command_preview(terminal, command, run_now);
+        return;
+    }
+    feed_command(terminal, command, run_now);
+}
+
+/// Feeds a final command to the terminal, confirming it first if it looks dangerous
+fn feed_command(terminal: &Terminal, command: &str, run_now: bool) {
+    if is_dangerous_command(command) {
+        confirm_dangerous_command(terminal, command, run_now);
+        return;
+    }
+    terminal.feed_child(command.as_bytes());
+    if run_now {
+        terminal.feed_child(b"\n");
+    } else {
+        terminal.feed_child(b" ");
+    }
+}
+
+/// Shows the fully substituted command in an editable entry before it is fed
+fn show_command_preview(terminal: &Terminal, command: &str, run_now: bool) {
+    let mut builder = gtk::MessageDialog::builder()
+        .modal(true)
+        .buttons(gtk::ButtonsType::None)
+        .text(if run_now { "Run Command?" } else { "Insert Command?" })
+        .secondary_text("All placeholders have been filled in. Edit the command if needed.");
+    if let Some(window) = terminal.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
+
+    let entry = gtk::Entry::new();
+    entry.set_text(command);
+    entry.add_css_class("monospace");
+    entry.set_width_chars(60);
+    if let Ok(area) = dialog.message_area().downcast::<gtk::Box>() {
+        area.append(&entry);
+    }
+
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    let accept_btn = dialog.add_button(if run_now { "Run" } else { "Insert" }, gtk::ResponseType::Accept);
+    accept_btn.add_css_class("suggested-action");
+    dialog.set_default_response(gtk::ResponseType::Accept);
+
+    let dialog_clone = dialog.clone();
+    entry.connect_activate(move |_| {
+        dialog_clone.response(gtk::ResponseType::Accept);
+    });
+
+    let terminal_clone = terminal.clone();
+    dialog.connect_response(move |dialog, response| {
+        let command = entry.text().trim().to_string();
+        dialog.close();
+        if response == gtk::ResponseType::Accept && !command.is_empty() {
+            feed_command(&terminal_clone, &command, run_now);
+        }
+        terminal_clone.grab_focus();
     });
 
-    // Keyboard navigation in search
-    let search_key_controller = gtk::EventControllerKey::new();
-    let list_box_clone2 = list_box.clone();
-    let drawer_toggle_clone2 = drawer_toggle.clone();
-    search_key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        match keyval {
-            gtk::gdk::Key::Down => {
-                list_box_clone2.grab_focus();
-                if let Some(first_row) = list_box_clone2.first_child() {
-                    let mut current = Some(first_row);
-                    while let Some(row) = current {
-                        if let Some(list_row) = row.downcast_ref::<gtk::ListBoxRow>() {
-                            if list_row.is_visible() && list_row.is_selectable() {
-                                list_box_clone2.select_row(Some(list_row));
-                                break;
-                            }
-                        }
-                        current = row.next_sibling();
-                    }
-                }
-                return gtk::glib::Propagation::Stop;
-            }
-            _ if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) => {
-                let shortcuts = get_keyboard_shortcuts();
-                let key_name = keyval.name().unwrap_or_default().to_string();
-                if key_name == shortcuts.toggle_drawer {
-                    drawer_toggle_clone2.set_active(false);
-                    return gtk::glib::Propagation::Stop;
-                }
+    dialog.show();
+}
+
+/// Shows a destructive-looking command and asks before inserting or running it
+pub fn confirm_dangerous_command(terminal: &Terminal, command: &str, run_now: bool) {
+    let mut builder = gtk::MessageDialog::builder()
+        .modal(true)
+        .buttons(gtk::ButtonsType::None)
+        .text(if run_now { "Run Dangerous Command?" } else { "Insert Dangerous Command?" })
+        .secondary_text(&format!(
+            "This command matches a dangerous pattern:\n\n{}\n\nCheck it carefully before continuing.",
+            command
+        ));
+    if let Some(window) = terminal.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+        builder = builder.transient_for(&window);
+    }
+    let dialog = builder.build();
+
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    let insert_btn = dialog.add_button("Insert Only", gtk::ResponseType::Other(1));
+    if run_now {
+        let run_btn = dialog.add_button("Run", gtk::ResponseType::Accept);
+        run_btn.add_css_class("destructive-action");
+    } else {
+        insert_btn.add_css_class("destructive-action");
+    }
+
+    let terminal_clone = terminal.clone();
+    let command = command.to_string();
+    dialog.connect_response(move |dialog, response| {
+        match response {
+            gtk::ResponseType::Accept => {
+                terminal_clone.feed_child(command.as_bytes());
+                terminal_clone.feed_child(b"\n");
             }
-            gtk::gdk::Key::Escape => {
-                drawer_toggle_clone2.set_active(false);
-                return gtk::glib::Propagation::Stop;
+            gtk::ResponseType::Other(1) => {
+                terminal_clone.feed_child(command.as_bytes());
+                terminal_clone.feed_child(b" ");
             }
             _ => {}
         }
-        gtk::glib::Propagation::Proceed
+        terminal_clone.grab_focus();
+        dialog.close();
     });
-    search_entry.add_controller(search_key_controller);
 
-    drawer.append(&search_box);
-    drawer.append(&scrolled);
-
-    (drawer, search_entry)
+    dialog.show();
 }
 
 /// Creates a split view tab
@@ -689,6 +2696,7 @@ pub fn create_split_view_tab(
     toast_overlay: Option<adw::ToastOverlay>,
 ) -> Paned {
     let paned = Paned::new(Orientation::Horizontal);
+    paned.set_widget_name("split_view");
     paned.set_margin_top(6);
     paned.set_margin_bottom(6);
     paned.set_margin_start(6);
@@ -719,34 +2727,36 @@ pub fn create_split_view_tab(
     apply_markdown_highlighting(&notes_view);
 
     // Track notes view for wrap mode updates
-    track_notes_view(&notes_view);
+    track_notes_view(&notes_view, &notes_path);
 
     // Add text view to zoom tracking
     crate::ui::editor::add_textview_scroll_zoom(&notes_view);
 
     // Auto-save notes
+    let save_state_label = create_save_state_label();
     let notes_path_clone = notes_path.clone();
-    let notes_view_clone = notes_view.clone();
+    let save_state_clone = save_state_label.clone();
     let save_timeout_id: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
     let save_timeout_clone = Rc::clone(&save_timeout_id);
 
     notes_view.buffer().connect_changed(move |buffer| {
         let file_path = notes_path_clone.clone();
-        let notes_view_ref = notes_view_clone.clone();
 
         if let Some(id) = save_timeout_clone.borrow_mut().take() {
             id.remove();
         }
 
-        apply_markdown_highlighting(&notes_view_ref);
+        set_save_state(&save_state_clone, false);
 
         let save_timeout_inner = Rc::clone(&save_timeout_clone);
         let buffer_clone = buffer.clone();
-        let source_id = glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+        let save_state_ref = save_state_clone.clone();
+        let delay = std::time::Duration::from_millis(get_autosave_debounce_ms() as u64);
+        let source_id = glib::timeout_add_local(delay, move || {
             let start = buffer_clone.start_iter();
             let end = buffer_clone.end_iter();
             let text = buffer_clone.text(&start, &end, false);
-            let _ = fs::write(&file_path, text.as_str());
+            save_editor_text_async(&file_path, &text, &save_state_ref);
             *save_timeout_inner.borrow_mut() = None;
             glib::ControlFlow::Break
         });
@@ -759,37 +2769,41 @@ pub fn create_split_view_tab(
     let key_controller = gtk::EventControllerKey::new();
     let notes_path_clone3 = notes_path.clone();
     let notes_view_clone3 = notes_view.clone();
+    let save_state_clone3 = save_state_label.clone();
     let notes_view_clone4 = notes_view.clone();
     let notes_view_clone5 = notes_view.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
-            // Ctrl+S to save
-            if keyval == gtk::gdk::Key::s {
-                let buffer = notes_view_clone3.buffer();
-                let start = buffer.start_iter();
-                let end = buffer.end_iter();
-                let text = buffer.text(&start, &end, false);
-                let _ = fs::write(&notes_path_clone3, text.as_str());
-                return gtk::glib::Propagation::Stop;
-            }
+        // Ctrl+S to save
+        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) && keyval == gtk::gdk::Key::s {
+            let buffer = notes_view_clone3.buffer();
+            let start = buffer.start_iter();
+            let end = buffer.end_iter();
+            let text = buffer.text(&start, &end, false);
+            save_editor_text(&notes_path_clone3, &text, &save_state_clone3);
+            return gtk::glib::Propagation::Stop;
+        }
 
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
+        let shortcuts = get_keyboard_shortcuts();
 
-            // Ctrl+T (or custom key) for target insertion
-            if key_name == shortcuts.insert_target {
-                crate::ui::editor::show_target_selector_for_textview(&notes_view_clone4);
-                return gtk::glib::Propagation::Stop;
-            }
+        // Ctrl+T (or custom key) for target insertion
+        if shortcuts.matches("insert_target", keyval, modifier) {
+            crate::ui::editor::show_target_selector_for_textview(&notes_view_clone4);
+            return gtk::glib::Propagation::Stop;
+        }
 
-            // Ctrl+Shift+T (or custom key) for timestamp insertion
-            if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) && key_name == shortcuts.insert_timestamp {
-                let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
-                let buffer = notes_view_clone5.buffer();
-                buffer.insert_at_cursor(&timestamp);
-                return gtk::glib::Propagation::Stop;
-            }
+        // Ctrl+Shift+T (or custom key) for timestamp insertion
+        if shortcuts.matches("insert_timestamp", keyval, modifier) {
+            let timestamp = chrono::Local::now().format("[%Y-%m-%d %H:%M:%S] ").to_string();
+            let buffer = notes_view_clone5.buffer();
+            buffer.insert_at_cursor(&timestamp);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Ctrl+Shift+L (or custom key) for a table scaffold
+        if shortcuts.matches("insert_table", keyval, modifier) {
+            crate::ui::editor::show_insert_table_prompt(&notes_view_clone5);
+            return gtk::glib::Propagation::Stop;
         }
         gtk::glib::Propagation::Proceed
     });
@@ -807,12 +2821,13 @@ pub fn create_split_view_tab(
 
     let notes_path_clone2 = notes_path.clone();
     let notes_view_clone2 = notes_view.clone();
+    let save_state_clone2 = save_state_label.clone();
     save_btn.connect_clicked(move |_| {
         let buffer = notes_view_clone2.buffer();
         let start = buffer.start_iter();
         let end = buffer.end_iter();
         let text = buffer.text(&start, &end, false);
-        let _ = fs::write(&notes_path_clone2, text.as_str());
+        save_editor_text(&notes_path_clone2, &text, &save_state_clone2);
     });
 
     let file_label = Label::new(Some("notes.md"));
@@ -820,18 +2835,56 @@ pub fn create_split_view_tab(
     file_label.set_hexpand(true);
     file_label.set_halign(gtk::Align::Start);
 
+    let insert_table_btn = Button::builder()
+        .icon_name("view-grid-symbolic")
+        .tooltip_text("Insert Table")
+        .build();
+    insert_table_btn.add_css_class("flat");
+    let notes_view_clone6 = notes_view.clone();
+    insert_table_btn.connect_clicked(move |_| {
+        crate::ui::editor::show_insert_table_prompt(&notes_view_clone6);
+    });
+
+    let add_terminal_btn = Button::builder()
+        .icon_name("view-dual-symbolic")
+        .tooltip_text("Split: add terminal")
+        .build();
+    add_terminal_btn.add_css_class("flat");
+
     notes_bar.append(&save_btn);
+    notes_bar.append(&insert_table_btn);
+    notes_bar.append(&add_terminal_btn);
     notes_bar.append(&file_label);
+    notes_bar.append(&save_state_label);
 
     notes_container.append(&notes_scrolled);
     notes_container.append(&notes_bar);
 
+    let paned_clone = paned.clone();
+    let notebook_clone = notebook.clone();
+    let shell_counter_clone = shell_counter.clone();
+    let toast_clone = toast_overlay.clone();
+    add_terminal_btn.connect_clicked(move |btn| {
+        let added = add_split_terminal(
+            &paned_clone,
+            _shell_id,
+            notebook_clone.clone(),
+            shell_counter_clone.clone(),
+            toast_clone.clone(),
+        );
+        if added {
+            // Only one extra shell fits comfortably next to the notes
+            btn.set_sensitive(false);
+        }
+    });
+
     // Right side: Shell
-    let shell_container = create_shell_tab(_shell_id, notebook, shell_counter, toast_overlay, true);
+    let shell_container = create_shell_tab(_shell_id, notebook, shell_counter, toast_overlay, true, None, None);
 
     paned.set_start_child(Some(&notes_container));
     paned.set_end_child(Some(&shell_container));
-    paned.set_position(500);
+    paned.set_position(get_split_position());
+    remember_split_position(&paned);
     paned.set_shrink_start_child(false);
     paned.set_shrink_end_child(false);
     paned.set_resize_start_child(true);
@@ -840,10 +2893,95 @@ pub fn create_split_view_tab(
     paned
 }
 
+/// Divider position that opens the command drawer at its remembered width
+pub fn drawer_open_position(paned: &Paned) -> i32 {
+    (paned.width() - get_drawer_width()).max(0)
+}
+
+/// Remembers the drawer width whenever the divider of an open drawer is dragged
+pub fn remember_drawer_width(paned: &Paned, drawer: &GtkBox) {
+    let drawer_clone = drawer.clone();
+    paned.connect_position_notify(move |paned| {
+        // Closing the drawer moves the divider too; only open drawers have a width
+        if !drawer_clone.is_visible() || !paned.is_mapped() {
+            return;
+        }
+        let width = paned.width() - paned.position();
+        if width > 0 {
+            schedule_layout_save(move |settings| settings.drawer_width = width);
+        }
+    });
+}
+
+/// Remembers the notes pane width whenever a split view divider is dragged
+pub fn remember_split_position(paned: &Paned) {
+    paned.connect_position_notify(|paned| {
+        if !paned.is_mapped() {
+            return;
+        }
+        let position = paned.position();
+        schedule_layout_save(move |settings| settings.split_position = position);
+    });
+}
+
+/// Saves a layout change once the divider has stopped moving
+fn schedule_layout_save(update: impl FnOnce(&mut AppSettings) + 'static) {
+    LAYOUT_SAVE.with(|pending| {
+        if let Some(id) = pending.borrow_mut().take() {
+            id.remove();
+        }
+        let id = glib::timeout_add_local_once(std::time::Duration::from_millis(500), move || {
+            LAYOUT_SAVE.with(|pending| pending.borrow_mut().take());
+            let mut settings = get_app_settings();
+            update(&mut settings);
+            report_error(save_app_settings(&settings));
+        });
+        *pending.borrow_mut() = Some(id);
+    });
+}
+
+/// Nests a second shell next to the first in a split view, giving notes | shell | shell
+///
+/// Returns false if the split view already holds two shells.
+fn add_split_terminal(
+    paned: &Paned,
+    shell_id: usize,
+    notebook: Notebook,
+    shell_counter: Option<Rc<RefCell<usize>>>,
+    toast_overlay: Option<adw::ToastOverlay>,
+) -> bool {
+    let Some(first_shell) = paned.end_child() else {
+        return false;
+    };
+    if first_shell.is::<Paned>() {
+        return false;
+    }
+
+    let width = first_shell.width();
+    let second_shell = create_shell_tab(shell_id, notebook, shell_counter, toast_overlay, true, None, None);
+
+    let terminals_paned = Paned::new(Orientation::Horizontal);
+    paned.set_end_child(None::<&gtk::Widget>);
+    terminals_paned.set_start_child(Some(&first_shell));
+    terminals_paned.set_end_child(Some(&second_shell));
+    if width > 0 {
+        terminals_paned.set_position(width / 2);
+    }
+    terminals_paned.set_shrink_start_child(false);
+    terminals_paned.set_shrink_end_child(false);
+    terminals_paned.set_resize_start_child(true);
+    terminals_paned.set_resize_end_child(true);
+    paned.set_end_child(Some(&terminals_paned));
+
+    focus_terminal_in_page(second_shell.upcast_ref());
+    true
+}
+
 /// Shows a target selector popup for terminal
 fn show_target_selector_popup(terminal: &Terminal) {
     let targets = load_targets();
     if targets.is_empty() {
+        show_no_targets_toast();
         return;
     }
 
@@ -896,13 +3034,25 @@ fn show_target_selector_popup(terminal: &Terminal) {
         if let Some(row) = list_box_clone.selected_row() {
             let index = row.index() as usize;
             if index < targets_clone.len() {
-                terminal_clone.feed_child(targets_clone[index].as_bytes());
+                terminal_clone.feed_child(shell_quote(&targets_clone[index]).as_bytes());
                 terminal_clone.grab_focus();
             }
         }
         popup_clone.close();
     });
 
+    let copy_btn = Button::with_label("Copy");
+    copy_btn.set_tooltip_text(Some("Copy the selected target to the clipboard (Ctrl+C)"));
+    let popup_clone5 = popup.clone();
+    let list_box_clone3 = list_box.clone();
+    let targets_clone4 = targets.clone();
+    copy_btn.connect_clicked(move |_| {
+        if let Some(target) = list_box_clone3.selected_row().and_then(|row| targets_clone4.get(row.index() as usize)) {
+            copy_target_to_clipboard(target);
+        }
+        popup_clone5.close();
+    });
+
     let popup_clone2 = popup.clone();
     cancel_btn.connect_clicked(move |_| {
         popup_clone2.close();
@@ -915,7 +3065,7 @@ fn show_target_selector_popup(terminal: &Terminal) {
     list_box.connect_row_activated(move |_list_box, row| {
         let index = row.index() as usize;
         if index < targets_clone2.len() {
-            terminal_clone2.feed_child(targets_clone2[index].as_bytes());
+            terminal_clone2.feed_child(shell_quote(&targets_clone2[index]).as_bytes());
             terminal_clone2.grab_focus();
         }
         popup_clone3.close();
@@ -927,15 +3077,23 @@ fn show_target_selector_popup(terminal: &Terminal) {
     let terminal_clone3 = terminal.clone();
     let list_box_clone2 = list_box.clone();
     let targets_clone3 = targets.clone();
-    key_controller.connect_key_pressed(move |_, keyval, _, _| {
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
         if keyval == gtk::gdk::Key::Escape {
             popup_clone4.close();
             return gtk::glib::Propagation::Stop;
+        } else if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK)
+            && (keyval == gtk::gdk::Key::c || keyval == gtk::gdk::Key::C)
+        {
+            if let Some(target) = list_box_clone2.selected_row().and_then(|row| targets_clone3.get(row.index() as usize)) {
+                copy_target_to_clipboard(target);
+            }
+            popup_clone4.close();
+            return gtk::glib::Propagation::Stop;
         } else if keyval == gtk::gdk::Key::Return || keyval == gtk::gdk::Key::KP_Enter {
             if let Some(row) = list_box_clone2.selected_row() {
                 let index = row.index() as usize;
                 if index < targets_clone3.len() {
-                    terminal_clone3.feed_child(targets_clone3[index].as_bytes());
+                    terminal_clone3.feed_child(shell_quote(&targets_clone3[index]).as_bytes());
                     terminal_clone3.grab_focus();
                 }
             }
@@ -947,6 +3105,7 @@ fn show_target_selector_popup(terminal: &Terminal) {
     popup.add_controller(key_controller);
 
     button_box.append(&cancel_btn);
+    button_box.append(&copy_btn);
     button_box.append(&insert_btn);
 
     popup_box.append(&scrolled);
@@ -957,12 +3116,361 @@ fn show_target_selector_popup(terminal: &Terminal) {
     popup.present();
 }
 
+/// Networks and ranges up to this size offer their individual addresses as suggestions
+const COMPLETION_EXPAND_LIMIT: usize = 1024;
+
+/// Most suggestions shown at once
+const MAX_COMPLETIONS: usize = 10;
+
+/// The expanded targets from targets.txt, parsed once until `reload_targets_in_shells` drops them
+fn completion_targets() -> Rc<Vec<String>> {
+    COMPLETION_TARGETS.with(|cache| {
+        Rc::clone(cache.borrow_mut().get_or_insert_with(|| {
+            Rc::new(expand_targets(&load_target_entries(), COMPLETION_EXPAND_LIMIT))
+        }))
+    })
+}
+
+/// Targets that extend the typed word, excluding an exact match
+fn target_completions(word: &str, targets: &[String]) -> Vec<String> {
+    if word.is_empty() {
+        return Vec::new();
+    }
+    targets
+        .iter()
+        .filter(|target| target.len() > word.len() && target.starts_with(word))
+        .take(MAX_COMPLETIONS)
+        .cloned()
+        .collect()
+}
+
+/// Inline target suggestions for a terminal.
+///
+/// VTE doesn't expose the input line, so the current word is tracked from keystrokes
+/// on a best-effort basis and cleared whenever the cursor may have moved elsewhere.
+struct TargetCompletion {
+    terminal: Terminal,
+    popover: gtk::Popover,
+    list_box: gtk::ListBox,
+    word: RefCell<String>,
+    candidates: RefCell<Vec<String>>,
+}
+
+impl TargetCompletion {
+    fn reset(&self) {
+        self.word.borrow_mut().clear();
+        self.popover.popdown();
+    }
+
+    /// Shows the targets matching the current word, or hides the popover when there are none
+    fn refresh(&self) {
+        let candidates = target_completions(&self.word.borrow(), &completion_targets());
+        while let Some(child) = self.list_box.first_child() {
+            self.list_box.remove(&child);
+        }
+        if candidates.is_empty() {
+            self.candidates.borrow_mut().clear();
+            self.popover.popdown();
+            return;
+        }
+
+        for candidate in &candidates {
+            let label = Label::new(Some(candidate));
+            label.set_xalign(0.0);
+            label.add_css_class("monospace");
+            self.list_box.append(&label);
+        }
+        self.list_box.select_row(self.list_box.row_at_index(0).as_ref());
+        *self.candidates.borrow_mut() = candidates;
+
+        // Point at the cursor cell; the cursor row is counted from the top of the scrollback
+        let (column, row) = self.terminal.cursor_position();
+        let top = self.terminal.vadjustment().map(|adj| adj.value() as i64).unwrap_or(0);
+        let x = column * self.terminal.char_width();
+        let y = (row - top) * self.terminal.char_height();
+        self.popover.set_pointing_to(Some(&gtk::gdk::Rectangle::new(
+            x as i32,
+            y as i32,
+            self.terminal.char_width() as i32,
+            self.terminal.char_height() as i32,
+        )));
+        self.popover.popup();
+    }
+
+    fn move_selection(&self, step: i32) {
+        let current = self.list_box.selected_row().map(|row| row.index()).unwrap_or(0);
+        if let Some(row) = self.list_box.row_at_index(current + step) {
+            self.list_box.select_row(Some(&row));
+        }
+    }
+
+    /// Types the rest of the selected target into the shell
+    fn accept(&self) {
+        let index = self.list_box.selected_row().map(|row| row.index()).unwrap_or(0) as usize;
+        let Some(candidate) = self.candidates.borrow().get(index).cloned() else {
+            return;
+        };
+        let typed = self.word.borrow().len();
+        self.terminal.feed_child(candidate[typed..].as_bytes());
+        *self.word.borrow_mut() = candidate;
+        self.popover.popdown();
+    }
+}
+
+/// Suggests targets from targets.txt as the current word is typed (opt-in in settings)
+fn setup_target_autocomplete(terminal: &Terminal) {
+    let list_box = gtk::ListBox::new();
+    list_box.set_selection_mode(gtk::SelectionMode::Single);
+    list_box.set_can_focus(false);
+
+    let popover = gtk::Popover::new();
+    popover.set_autohide(false);
+    popover.set_has_arrow(false);
+    popover.set_can_focus(false);
+    popover.set_position(gtk::PositionType::Bottom);
+    popover.set_child(Some(&list_box));
+    popover.set_parent(terminal);
+
+    let completion = Rc::new(TargetCompletion {
+        terminal: terminal.clone(),
+        popover: popover.clone(),
+        list_box: list_box.clone(),
+        word: RefCell::new(String::new()),
+        candidates: RefCell::new(Vec::new()),
+    });
+
+    // Clicking a suggestion completes it
+    let completion_clone = Rc::clone(&completion);
+    list_box.connect_row_activated(move |list_box, row| {
+        list_box.select_row(Some(row));
+        completion_clone.accept();
+        completion_clone.terminal.grab_focus();
+    });
+
+    // Runs before VTE so Tab and the arrow keys can be taken while suggestions are shown
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let completion_clone2 = Rc::clone(&completion);
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        let completion = &completion_clone2;
+        if !is_target_autocomplete_enabled() {
+            return glib::Propagation::Proceed;
+        }
+
+        if completion.popover.is_visible() {
+            match keyval {
+                gtk::gdk::Key::Tab => {
+                    completion.accept();
+                    return glib::Propagation::Stop;
+                }
+                gtk::gdk::Key::Down => {
+                    completion.move_selection(1);
+                    return glib::Propagation::Stop;
+                }
+                gtk::gdk::Key::Up => {
+                    completion.move_selection(-1);
+                    return glib::Propagation::Stop;
+                }
+                gtk::gdk::Key::Escape => {
+                    completion.popover.popdown();
+                    return glib::Propagation::Stop;
+                }
+                _ => {}
+            }
+        }
+
+        let ctrl = modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK);
+        if ctrl && keyval == gtk::gdk::Key::space {
+            completion.refresh();
+            return glib::Propagation::Stop;
+        }
+        if ctrl || modifier.contains(gtk::gdk::ModifierType::ALT_MASK) {
+            completion.reset();
+            return glib::Propagation::Proceed;
+        }
+
+        match keyval {
+            gtk::gdk::Key::BackSpace => {
+                completion.word.borrow_mut().pop();
+                completion.refresh();
+            }
+            gtk::gdk::Key::Shift_L | gtk::gdk::Key::Shift_R => {}
+            _ => match keyval.to_unicode() {
+                Some(c) if !c.is_whitespace() && !c.is_control() => {
+                    completion.word.borrow_mut().push(c);
+                    completion.refresh();
+                }
+                // Enter, space, Tab, arrows and the like end or move away from the word
+                _ => completion.reset(),
+            },
+        }
+        glib::Propagation::Proceed
+    });
+    terminal.add_controller(key_controller);
+
+    // Clicks may move the cursor and focus loss leaves the word behind
+    let click = gtk::GestureClick::new();
+    let completion_clone3 = Rc::clone(&completion);
+    click.connect_pressed(move |_, _, _, _| {
+        completion_clone3.reset();
+    });
+    terminal.add_controller(click);
+
+    let focus_controller = gtk::EventControllerFocus::new();
+    let completion_clone4 = Rc::clone(&completion);
+    focus_controller.connect_leave(move |_| {
+        completion_clone4.reset();
+    });
+    terminal.add_controller(focus_controller);
+
+    terminal.connect_destroy(move |_| {
+        popover.unparent();
+    });
+}
+
+/// Finds the target currently selected in the shell tab containing this terminal
+fn find_active_target(terminal: &Terminal) -> Option<String> {
+    let mut ancestor = terminal.parent();
+    while let Some(current) = ancestor {
+        if let Some(outer_box) = current.downcast_ref::<GtkBox>() {
+            let combo = outer_box
+                .first_child()
+                .and_then(|c| c.downcast::<GtkBox>().ok())
+                .and_then(|b| b.first_child())
+                .and_then(|c| c.downcast::<gtk::ComboBoxText>().ok());
+            if let Some(combo) = combo {
+                return combo.active_text().map(|t| t.to_string());
+            }
+        }
+        ancestor = current.parent();
+    }
+    None
+}
+
+/// Shows a form with one entry per `{placeholder}` and feeds the filled command
+pub fn show_placeholder_form(terminal: &Terminal, command_template: String, placeholders: Vec<String>, run_now: bool) {
+    let popup = adw::Window::builder()
+        .title("Fill Command Placeholders")
+        .modal(true)
+        .default_width(420)
+        .build();
+    popup.set_transient_for(terminal.root().and_downcast::<gtk::Window>().as_ref());
+
+    let content = adw::Clamp::new();
+    content.set_maximum_size(400);
+
+    let popup_box = GtkBox::new(Orientation::Vertical, 12);
+    popup_box.set_margin_top(16);
+    popup_box.set_margin_bottom(16);
+    popup_box.set_margin_start(16);
+    popup_box.set_margin_end(16);
+
+    let command_label = Label::new(Some(&command_template));
+    command_label.add_css_class("dim-label");
+    command_label.add_css_class("monospace");
+    command_label.set_wrap(true);
+    command_label.set_xalign(0.0);
+    popup_box.append(&command_label);
+
+    let grid = gtk::Grid::new();
+    grid.set_row_spacing(8);
+    grid.set_column_spacing(12);
+
+    let targets = load_targets();
+    let mut fields: Vec<(String, gtk::Entry)> = Vec::new();
+    for (row, name) in placeholders.iter().enumerate() {
+        let label = Label::new(Some(name));
+        label.set_xalign(0.0);
+        grid.attach(&label, 0, row as i32, 1, 1);
+
+        // {target} gets a dropdown of known targets, everything else a plain entry
+        let entry = if name == "target" {
+            let combo = gtk::ComboBoxText::with_entry();
+            for target in &targets {
+                combo.append_text(target);
+            }
+            combo.set_hexpand(true);
+            grid.attach(&combo, 1, row as i32, 1, 1);
+            combo.child().and_then(|c| c.downcast::<gtk::Entry>().ok())
+        } else {
+            None
+        };
+        let entry = entry.unwrap_or_else(|| {
+            let entry = gtk::Entry::new();
+            entry.set_hexpand(true);
+            grid.attach(&entry, 1, row as i32, 1, 1);
+            entry
+        });
+
+        let default_value = match name.as_str() {
+            "target" => find_active_target(terminal).or_else(|| targets.first().cloned()),
+            "lhost" => detect_default_interface().map(|(_, addr)| addr),
+            _ => None,
+        };
+        if let Some(value) = default_value {
+            entry.set_text(&value);
+        }
+        entry.set_activates_default(true);
+        fields.push((name.clone(), entry));
+    }
+    popup_box.append(&grid);
+
+    let button_box = GtkBox::new(Orientation::Horizontal, 8);
+    button_box.set_halign(gtk::Align::End);
+
+    let cancel_btn = Button::with_label("Cancel");
+    let insert_btn = Button::with_label(if run_now { "Run" } else { "Insert" });
+    insert_btn.add_css_class("suggested-action");
+
+    let popup_clone = popup.clone();
+    cancel_btn.connect_clicked(move |_| {
+        popup_clone.close();
+    });
+
+    let popup_clone2 = popup.clone();
+    let terminal_clone = terminal.clone();
+    insert_btn.connect_clicked(move |_| {
+        let values: HashMap<String, String> = fields
+            .iter()
+            .map(|(name, entry)| {
+                let value = entry.text().to_string();
+                // Targets may be URLs or paths with characters the shell would interpret
+                let value = if name == "target" { shell_quote(&value) } else { value };
+                (name.clone(), value)
+            })
+            .collect();
+        let filled_command = fill_placeholders(&command_template, &values);
+        send_command(&terminal_clone, &filled_command, run_now);
+        terminal_clone.grab_focus();
+        popup_clone2.close();
+    });
+
+    let key_controller = gtk::EventControllerKey::new();
+    let popup_clone3 = popup.clone();
+    key_controller.connect_key_pressed(move |_, keyval, _, _| {
+        if keyval == gtk::gdk::Key::Escape {
+            popup_clone3.close();
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    popup.add_controller(key_controller);
+
+    button_box.append(&cancel_btn);
+    button_box.append(&insert_btn);
+    popup_box.append(&button_box);
+
+    content.set_child(Some(&popup_box));
+    popup.set_content(Some(&content));
+    popup.set_default_widget(Some(&insert_btn));
+    popup.present();
+}
+
 /// Shows target selector for command with {target} placeholder
-fn show_target_selector_for_command(terminal: &Terminal, command_template: String) {
+fn show_target_selector_for_command(terminal: &Terminal, command_template: String, run_now: bool) {
     let targets = load_targets();
     if targets.is_empty() {
-        terminal.feed_child(command_template.as_bytes());
-        terminal.feed_child(b" ");
+        send_command(terminal, &command_template, run_now);
         return;
     }
 
@@ -1017,10 +3525,9 @@ fn show_target_selector_for_command(terminal: &Terminal, command_template: Strin
             let index = row.index() as usize;
             if index < targets_clone.len() {
                 let filled_command = command_clone
-                    .replace("{target}", &targets_clone[index])
+                    .replace("{target}", &shell_quote(&targets_clone[index]))
                     .replace("{port}", "");
-                terminal_clone.feed_child(filled_command.as_bytes());
-                terminal_clone.feed_child(b" ");
+                send_command(&terminal_clone, &filled_command, run_now);
                 terminal_clone.grab_focus();
             }
         }
@@ -1040,10 +3547,9 @@ fn show_target_selector_for_command(terminal: &Terminal, command_template: Strin
         let index = row.index() as usize;
         if index < targets_clone2.len() {
             let filled_command = command_clone2
-                .replace("{target}", &targets_clone2[index])
+                .replace("{target}", &shell_quote(&targets_clone2[index]))
                 .replace("{port}", "");
-            terminal_clone2.feed_child(filled_command.as_bytes());
-            terminal_clone2.feed_child(b" ");
+            send_command(&terminal_clone2, &filled_command, run_now);
             terminal_clone2.grab_focus();
         }
         popup_clone3.close();
@@ -1064,10 +3570,9 @@ fn show_target_selector_for_command(terminal: &Terminal, command_template: Strin
                 let index = row.index() as usize;
                 if index < targets_clone3.len() {
                     let filled_command = command_clone3
-                        .replace("{target}", &targets_clone3[index])
+                        .replace("{target}", &shell_quote(&targets_clone3[index]))
                         .replace("{port}", "");
-                    terminal_clone3.feed_child(filled_command.as_bytes());
-                    terminal_clone3.feed_child(b" ");
+                    send_command(&terminal_clone3, &filled_command, run_now);
                     terminal_clone3.grab_focus();
                 }
             }
@@ -1113,14 +3618,54 @@ pub fn focus_terminal_in_page(page: &gtk::Widget) {
 pub fn focus_terminal_in_split_view(page: &gtk::Widget) {
     if let Some(paned) = page.downcast_ref::<Paned>() {
         if let Some(end_child) = paned.end_child() {
-            focus_terminal_in_page(&end_child);
+            // With two shells the end child is another Paned; focus the first shell
+            match end_child.downcast_ref::<Paned>().and_then(|nested| nested.start_child()) {
+                Some(first_shell) => focus_terminal_in_page(&first_shell),
+                None => focus_terminal_in_page(&end_child),
+            }
         }
     }
 }
 
+/// Shows the number of targets on the Targets tab label
+pub fn update_target_count(notebook: &Notebook) {
+    let targets = load_target_entries();
+    let count = targets.len();
+    let Some(label) = notebook
+        .nth_page(Some(tabs::TARGETS))
+        .and_then(|page| notebook.tab_label(&page))
+        .and_then(|tab| tab.last_child())
+        .and_downcast::<Label>()
+        .filter(|label| label.widget_name() == "target_count")
+    else {
+        return;
+    };
+    label.set_text(&count.to_string());
+
+    let addresses = targets.iter().fold(0u128, |sum, target| sum.saturating_add(target.address_count()));
+    let mut tooltip = match count {
+        0 => "No targets yet — add one per line and save".to_string(),
+        1 => "1 target".to_string(),
+        n => format!("{} targets", n),
+    };
+    if addresses > count as u128 {
+        tooltip.push_str(&format!(" ({} addresses)", addresses));
+    }
+    // Malformed entries are still offered as written; point at the first one
+    if let Err(error) = validate_targets() {
+        tooltip.push_str(&format!("\nUnrecognized entry on {}", error));
+        label.add_css_class("warning");
+    } else {
+        label.remove_css_class("warning");
+    }
+    label.set_tooltip_text(Some(&tooltip));
+}
+
 /// Reload targets in all shell tabs
 pub fn reload_targets_in_shells(notebook: &Notebook) {
     let targets = load_targets();
+    COMPLETION_TARGETS.with(|cache| cache.borrow_mut().take());
+    update_target_count(notebook);
 
     // Update notes tab
     if let Some(notes_page) = notebook.nth_page(Some(tabs::NOTES)) {
@@ -1153,7 +3698,7 @@ pub fn reload_targets_in_shells(notebook: &Notebook) {
     }
 
     // Update shell tabs
-    for i in tabs::FIRST_SHELL..notebook.n_pages() {
+    for i in first_closable_tab()..notebook.n_pages() {
         if let Some(page) = notebook.nth_page(Some(i)) {
             if let Some(shell_box) = page.downcast_ref::<GtkBox>() {
                 if let Some(target_box) = shell_box.first_child() {
@@ -1185,24 +3730,22 @@ pub fn reload_targets_in_shells(notebook: &Notebook) {
     }
 }
 
+/// Seconds between Log viewer refreshes
+pub const LOG_REFRESH_SECS: u32 = 2;
+
 /// Refresh the log viewer tab
 pub fn refresh_log_viewer(notebook: &Notebook) {
+    // Checked here since this runs periodically while logging is enabled
+    if let Err(e) = rotate_command_log() {
+        log::warn!("{}", e);
+    }
+    let log_path = get_file_path("commands.log").to_string_lossy().to_string();
+    if !log_file_changed(&log_path) {
+        return;
+    }
     if let Some(log_page) = notebook.nth_page(Some(tabs::LOG)) {
         if let Some(log_box) = log_page.downcast_ref::<GtkBox>() {
-            if let Some(scrolled) = log_box.first_child() {
-                if let Some(scrolled) = scrolled.downcast_ref::<ScrolledWindow>() {
-                    if let Some(text_view) = scrolled.child() {
-                        if let Some(text_view) = text_view.downcast_ref::<TextView>() {
-                            if let Ok(content) = fs::read_to_string(get_file_path("commands.log")) {
-                                text_view.buffer().set_text(&content);
-                                let buffer = text_view.buffer();
-                                let mut end_iter = buffer.end_iter();
-                                text_view.scroll_to_iter(&mut end_iter, 0.0, false, 0.0, 0.0);
-                            }
-                        }
-                    }
-                }
-            }
+            reload_log_viewer(log_box, &log_path);
         }
     }
 }
@@ -7,24 +7,50 @@ use gtk4::{self as gtk, Application, Box as GtkBox, Button, Label, Notebook,
           Orientation, Frame};
 use gtk4::glib;
 use libadwaita::{self as adw, prelude::*};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
-use sysinfo::{System, Networks};
+use std::time::{Duration, Instant};
+use sysinfo::{System, Networks, Disks};
 
 use crate::config::{
-    load_app_settings, get_keyboard_shortcuts,
+    load_app_settings, get_keyboard_shortcuts, get_base_dir,
     is_command_logging_enabled, get_file_path, set_base_dir, tabs,
-    is_browser_enabled, is_containers_enabled,
+    is_browser_enabled, is_containers_enabled, get_color_scheme, ColorScheme,
+    is_restore_session_enabled, load_session, save_session, Session, SessionTab,
+    get_vpn_interface, get_monitor_interval_ms, get_monitored_interfaces,
+    get_window_geometry, WindowGeometry, get_app_settings, save_app_settings, is_protected_tab, set_fixed_tab_count,
+    shortcut_hint, next_shell_number, shell_tab_label, is_scrollback_persistence_enabled, scrollback_file_name, SCROLLBACK_DIR,
+    take_settings_load_error, get_invalid_settings_path, get_custom_commands_path,
+    take_project_settings_error, get_project_settings_path,
 };
-use crate::ui::dialogs::{show_base_dir_dialog, show_settings_dialog};
-use crate::ui::editor::{create_text_editor, create_readonly_viewer};
+use crate::commands::{read_custom_commands, update_default_interface};
+use crate::ui::dialogs::{show_base_dir_dialog, show_settings_dialog, show_settings_dialog_at_tab, settings_tabs, show_new_note_dialog};
+use crate::ui::editor::{create_text_editor, create_readonly_viewer, refresh_notes_colors};
 use crate::ui::terminal::{create_shell_tab, create_split_view_tab, create_editable_tab_label,
-                          focus_terminal_in_page, focus_terminal_in_split_view};
+                          focus_terminal_in_page, focus_terminal_in_split_view, refresh_terminal_colors,
+                          pop_closed_tab, ClosedTabKind, terminal_count, broadcast_to_terminals,
+                          close_current_tab, find_terminal_in, terminal_working_dir, handle_tab_cycle_keys,
+                          save_scrollback, restore_scrollback, watch_custom_commands, update_target_count,
+                          refresh_log_viewer, LOG_REFRESH_SECS};
 use crate::ui::browser::{create_browser_tab, focus_url_entry_in_page};
 use crate::ui::container::create_container_tab;
+use crate::ui::palette::{show_command_palette, PaletteAction};
+use crate::ui::findings::create_findings_tab;
 #[cfg(feature = "webkit")]
 use crate::ui::desktop::create_desktop_tab;
 use crate::container::{ContainerManager, load_container_config};
+use crate::export::export_project;
+use crate::report::write_report;
+use crate::ssh::{load_ssh_profiles, find_ssh_profile, SshProfile};
+
+// System monitor timer, restarted when the refresh interval changes
+thread_local! {
+    static MONITOR_TICK: RefCell<Option<Rc<dyn Fn()>>> = RefCell::new(None);
+    static MONITOR_SOURCE: RefCell<Option<glib::SourceId>> = RefCell::new(None);
+    static MAIN_TOAST: RefCell<Option<adw::ToastOverlay>> = RefCell::new(None);
+}
 
 /// Builds and initializes the main application UI
 pub fn build_ui(app: &Application) {
@@ -41,21 +67,104 @@ pub fn build_ui(app: &Application) {
     });
 }
 
+/// Shows an error message as a toast in the main window
+pub fn show_error_toast(message: &str) {
+    log::error!("{}", message);
+    MAIN_TOAST.with(|toast| {
+        if let Some(toast) = toast.borrow().as_ref() {
+            let toast_msg = adw::Toast::new(message);
+            toast_msg.set_timeout(4);
+            toast.add_toast(toast_msg);
+        }
+    });
+}
+
+/// Tells the user where to add targets when a target selector has nothing to offer
+pub fn show_no_targets_toast() {
+    MAIN_TOAST.with(|toast| {
+        if let Some(toast) = toast.borrow().as_ref() {
+            let toast_msg = adw::Toast::new("No targets yet — add some in the Targets tab");
+            toast_msg.set_timeout(3);
+            toast.add_toast(toast_msg);
+        }
+    });
+}
+
+/// Shows a config file parse error as a toast that can open the file
+pub fn show_config_error(message: &str, path: PathBuf) {
+    log::error!("{}", message);
+    MAIN_TOAST.with(|toast| {
+        if let Some(toast) = toast.borrow().as_ref() {
+            let toast_msg = adw::Toast::new(message);
+            toast_msg.set_timeout(10);
+            toast_msg.set_button_label(Some("Open File"));
+            toast_msg.connect_button_clicked(move |_| {
+                let uri = gtk::gio::File::for_path(&path).uri();
+                if let Err(e) = gtk::gio::AppInfo::launch_default_for_uri(&uri, None::<&gtk::gio::AppLaunchContext>) {
+                    show_error_toast(&format!("Failed to open {}: {}", path.display(), e));
+                }
+            });
+            toast.add_toast(toast_msg);
+        }
+    });
+}
+
+/// Shows the error of a failed operation as a toast
+pub fn report_error(result: Result<(), String>) {
+    if let Err(e) = result {
+        show_error_toast(&e);
+    }
+}
+
+/// Applies the color scheme preference to libadwaita
+pub fn apply_color_scheme(scheme: ColorScheme) {
+    let adw_scheme = match scheme {
+        ColorScheme::System => adw::ColorScheme::Default,
+        ColorScheme::Light => adw::ColorScheme::ForceLight,
+        ColorScheme::Dark => adw::ColorScheme::ForceDark,
+    };
+    adw::StyleManager::default().set_color_scheme(adw_scheme);
+}
+
 /// Creates the main application window with modern AdwHeaderBar
 fn create_main_window(app: &Application) {
     // Load app settings at startup
     let settings = load_app_settings();
 
-    // Create AdwApplicationWindow for modern styling
+    // Apply the preferred color scheme and recolor views when it changes
+    apply_color_scheme(get_color_scheme());
+    adw::StyleManager::default().connect_dark_notify(|_| {
+        refresh_terminal_colors();
+        refresh_notes_colors();
+    });
+
+    // Command drawers follow edits to custom_commands.yaml
+    watch_custom_commands();
+
+    // Create AdwApplicationWindow for modern styling, sized as it was last closed
+    let geometry = get_window_geometry();
     let window = adw::ApplicationWindow::builder()
         .application(app)
         .title("PenEnv")
-        .default_width(1200)
-        .default_height(800)
+        .default_width(geometry.width)
+        .default_height(geometry.height)
+        .maximized(geometry.maximized)
         .build();
 
     // Main container with toast overlay for notifications
     let toast_overlay = adw::ToastOverlay::new();
+    MAIN_TOAST.with(|toast| *toast.borrow_mut() = Some(toast_overlay.clone()));
+
+    // Malformed config files fall back to defaults; say so instead of failing silently
+    if let Some(message) = take_settings_load_error() {
+        show_config_error(&message, get_invalid_settings_path());
+    }
+    if let Some(message) = take_project_settings_error() {
+        show_config_error(&message, get_project_settings_path());
+    }
+    if let Err(message) = read_custom_commands() {
+        show_config_error(&message, get_custom_commands_path());
+    }
 
     // Content box
     let content_box = GtkBox::new(Orientation::Vertical, 0);
@@ -81,7 +190,7 @@ fn create_main_window(app: &Application) {
     // Left side buttons
     let new_shell_btn = Button::builder()
         .icon_name("utilities-terminal-symbolic")
-        .tooltip_text("New Shell Tab (Ctrl+Shift+N)")
+        .tooltip_text(&shortcut_hint("New Shell Tab", "new_shell"))
         .build();
     new_shell_btn.add_css_class("flat");
 
@@ -111,10 +220,16 @@ fn create_main_window(app: &Application) {
 
     let split_mode_btn = Button::builder()
         .icon_name("view-dual-symbolic")
-        .tooltip_text("Split View Mode (Ctrl+Shift+S)")
+        .tooltip_text(&shortcut_hint("Split View Mode", "new_split"))
         .build();
     split_mode_btn.add_css_class("flat");
 
+    let new_note_btn = Button::builder()
+        .icon_name("document-new-symbolic")
+        .tooltip_text("New Note Tab")
+        .build();
+    new_note_btn.add_css_class("flat");
+
     // Container split view button (only if containers enabled)
     let container_split_btn = if is_containers_enabled() {
         let btn = Button::builder()
@@ -154,11 +269,19 @@ fn create_main_window(app: &Application) {
 
 
 
+    // Broadcast mode toggle: sends input from the broadcast bar to every shell
+    let broadcast_btn = gtk::ToggleButton::builder()
+        .icon_name("mail-send-symbolic")
+        .tooltip_text(&broadcast_tooltip())
+        .build();
+    broadcast_btn.add_css_class("flat");
+
     header_bar.pack_start(&new_shell_btn);
     if let Some(ref nolog_btn) = new_shell_nolog_btn {
         header_bar.pack_start(nolog_btn);
     }
     header_bar.pack_start(&split_mode_btn);
+    header_bar.pack_start(&new_note_btn);
     if let Some(ref btn) = container_shell_btn {
         header_bar.pack_start(btn);
     }
@@ -172,6 +295,35 @@ fn create_main_window(app: &Application) {
     if let Some(ref btn) = browser_btn {
         header_bar.pack_start(btn);
     }
+    header_bar.pack_start(&broadcast_btn);
+
+    // SSH profiles dropdown: each profile opens a shell tab running ssh
+    let ssh_list = gtk::ListBox::new();
+    ssh_list.set_selection_mode(gtk::SelectionMode::None);
+    ssh_list.add_css_class("boxed-list");
+
+    let ssh_manage_btn = Button::with_label("Manage Profiles…");
+    ssh_manage_btn.add_css_class("flat");
+
+    let ssh_menu_box = GtkBox::new(Orientation::Vertical, 6);
+    ssh_menu_box.set_margin_top(6);
+    ssh_menu_box.set_margin_bottom(6);
+    ssh_menu_box.set_margin_start(6);
+    ssh_menu_box.set_margin_end(6);
+    ssh_menu_box.set_width_request(260);
+    ssh_menu_box.append(&ssh_list);
+    ssh_menu_box.append(&ssh_manage_btn);
+
+    let ssh_popover = gtk::Popover::new();
+    ssh_popover.set_child(Some(&ssh_menu_box));
+
+    let ssh_btn = gtk::MenuButton::builder()
+        .icon_name("network-server-symbolic")
+        .tooltip_text("SSH Profiles")
+        .popover(&ssh_popover)
+        .build();
+    ssh_btn.add_css_class("flat");
+    header_bar.pack_start(&ssh_btn);
 
     // Right side: System monitors and settings
     let monitors_box = GtkBox::new(Orientation::Horizontal, 8);
@@ -185,9 +337,18 @@ fn create_main_window(app: &Application) {
     // Network Monitor - line graph
     let (net_frame, net_drawing, net_history) = create_network_monitor(settings.monitor_visibility.show_network);
 
+    // Disk Monitor - vertical bar for the filesystem holding the base dir
+    let (disk_frame, disk_drawing) = create_vertical_bar_monitor("Disk", settings.monitor_visibility.show_disk);
+
     monitors_box.append(&cpu_frame);
     monitors_box.append(&ram_frame);
+    monitors_box.append(&disk_frame);
     monitors_box.append(&net_frame);
+    let monitor_frames = MonitorFrames { cpu: cpu_frame, ram: ram_frame, net: net_frame, disk: disk_frame };
+
+    // VPN indicator next to the network monitor
+    let vpn_indicator = create_vpn_indicator();
+    monitors_box.append(&vpn_indicator.frame);
 
     // Settings button with menu styling
     let settings_btn = Button::builder()
@@ -196,7 +357,23 @@ fn create_main_window(app: &Application) {
         .build();
     settings_btn.add_css_class("flat");
 
+    // Export project button
+    let export_btn = Button::builder()
+        .icon_name("package-x-generic-symbolic")
+        .tooltip_text("Export Project (zip)")
+        .build();
+    export_btn.add_css_class("flat");
+
+    // Generate report button
+    let report_btn = Button::builder()
+        .icon_name("x-office-document-symbolic")
+        .tooltip_text("Generate Report (report.md)")
+        .build();
+    report_btn.add_css_class("flat");
+
     header_bar.pack_end(&settings_btn);
+    header_bar.pack_end(&export_btn);
+    header_bar.pack_end(&report_btn);
     header_bar.pack_end(&monitors_box);
 
     // Create notebook for tabs with modern styling
@@ -205,25 +382,43 @@ fn create_main_window(app: &Application) {
         .build();
     notebook.add_css_class("background");
 
-    // Shell counter for tracking shell tab numbers
-    // Start at 4, will be incremented as needed based on which tabs are enabled
-    let shell_counter: Rc<RefCell<usize>> = Rc::new(RefCell::new(4));
+    // Next number for a shell tab label, independent of which fixed tabs are enabled
+    let shell_counter: Rc<RefCell<usize>> = Rc::new(RefCell::new(tabs::FIRST_SHELL_NUMBER));
 
     // Browser counter for tracking browser tab numbers
     let browser_counter: Rc<RefCell<usize>> = Rc::new(RefCell::new(1));
 
     // Tab 1: Targets
     let targets_page = create_text_editor(&get_file_path("targets.txt").to_string_lossy().to_string(), Some(notebook.clone()));
-    notebook.append_page(&targets_page, Some(&create_tab_label("📋", "Targets")));
+    let targets_label = create_tab_label("📋", "Targets");
+    let target_count = Label::new(None);
+    target_count.set_widget_name("target_count");
+    target_count.add_css_class("caption");
+    target_count.add_css_class("numeric");
+    target_count.add_css_class("dim-label");
+    targets_label.append(&target_count);
+    notebook.append_page(&targets_page, Some(&targets_label));
+    update_target_count(&notebook);
 
     // Tab 2: Notes
     let notes_page = create_text_editor(&get_file_path("notes.md").to_string_lossy().to_string(), None);
     notebook.append_page(&notes_page, Some(&create_tab_label("📝", "Notes")));
 
-    // Tab 3: Command Log (only if logging is enabled)
+    // Tab 3: Findings
+    let findings_page = create_findings_tab();
+    notebook.append_page(&findings_page, Some(&create_tab_label("🐞", "Findings")));
+
+    // Tab 4: Command Log (only if logging is enabled)
     if is_command_logging_enabled() {
         let log_page = create_readonly_viewer(&get_file_path("commands.log").to_string_lossy().to_string());
         notebook.append_page(&log_page, Some(&create_tab_label("📜", "Log")));
+
+        // One timer for the whole window, however many shells are logging into the file
+        let notebook_clone = notebook.clone();
+        glib::timeout_add_seconds_local(LOG_REFRESH_SECS, move || {
+            refresh_log_viewer(&notebook_clone);
+            glib::ControlFlow::Continue
+        });
     }
 
     // Tab: Containers (only if enabled)
@@ -233,19 +428,41 @@ fn create_main_window(app: &Application) {
             shell_counter.clone(),
             Some(toast_overlay.clone()),
             &window,
-            &cpu_frame,
-            &ram_frame,
-            &net_frame,
+            &monitor_frames,
         );
         notebook.append_page(&container_page, Some(&create_tab_label("📦", "Containers")));
     }
 
-    // First Shell tab
-    let first_shell_id = notebook.n_pages() + 1;
-    *shell_counter.borrow_mut() = first_shell_id as usize;
-    let shell_page = create_shell_tab(first_shell_id as usize, notebook.clone(), Some(shell_counter.clone()), Some(toast_overlay.clone()), true);
-    let shell_label = create_editable_tab_label(&format!("💻 Shell {}", first_shell_id), &notebook);
-    notebook.append_page(&shell_page, Some(&shell_label));
+    // First Shell tab, unless the previous session's tabs are restored
+    let fixed_pages = notebook.n_pages();
+    set_fixed_tab_count(fixed_pages);
+    let restored = if is_restore_session_enabled() {
+        restore_session_tabs(&notebook, &shell_counter, &toast_overlay)
+    } else {
+        0
+    };
+    if restored == 0 {
+        append_shell_tab(&notebook, &shell_counter, &toast_overlay, true, None);
+    } else {
+        let toast_msg = adw::Toast::new(&format!("Restored {} tab(s) from the previous session", restored));
+        toast_msg.set_timeout(2);
+        toast_overlay.add_toast(toast_msg);
+    }
+
+    // Save the tab layout for the next session when the window closes
+    let notebook_session = notebook.clone();
+    window.connect_close_request(move |window| {
+        crate::ui::editor::flush_notes();
+        if let Err(e) = save_window_geometry(window) {
+            log::warn!("{}", e);
+        }
+        if is_restore_session_enabled() {
+            if let Err(e) = save_session_tabs(&notebook_session, fixed_pages) {
+                log::warn!("{}", e);
+            }
+        }
+        glib::Propagation::Proceed
+    });
 
     // Connect button handlers
     let notebook_clone = notebook.clone();
@@ -272,6 +489,18 @@ fn create_main_window(app: &Application) {
         create_new_split_view_tab(&notebook_clone2, &shell_counter_clone2, &toast_clone2);
     });
 
+    // New note button handler - prompt for a file name in the base directory
+    let notebook_note = notebook.clone();
+    let toast_note = toast_overlay.clone();
+    let window_note = window.clone();
+    new_note_btn.connect_clicked(move |_| {
+        let notebook_note_inner = notebook_note.clone();
+        let toast_note_inner = toast_note.clone();
+        show_new_note_dialog(&window_note, move |name| {
+            create_new_note_tab(&notebook_note_inner, &toast_note_inner, &name);
+        });
+    });
+
     // Container shell button handler - show container selector dialog
     if let Some(ref btn) = container_shell_btn {
         let notebook_container = notebook.clone();
@@ -335,42 +564,59 @@ fn create_main_window(app: &Application) {
 
     // Settings button handler
     let window_clone = window.clone();
-    let cpu_frame_clone = cpu_frame.clone();
-    let ram_frame_clone = ram_frame.clone();
-    let net_frame_clone = net_frame.clone();
+    let monitor_frames_clone = monitor_frames.clone();
     settings_btn.connect_clicked(move |_| {
-        show_settings_dialog(&window_clone, &cpu_frame_clone, &ram_frame_clone, &net_frame_clone);
+        show_settings_dialog(&window_clone, &monitor_frames_clone);
+    });
+
+    // SSH profiles: reload on open so edits from settings show up immediately
+    let ssh_list_clone = ssh_list.clone();
+    ssh_popover.connect_show(move |_| {
+        populate_ssh_menu(&ssh_list_clone);
+    });
+
+    let notebook_ssh = notebook.clone();
+    let shell_counter_ssh = Rc::clone(&shell_counter);
+    let toast_ssh = toast_overlay.clone();
+    let ssh_popover_clone = ssh_popover.clone();
+    ssh_list.connect_row_activated(move |_, row| {
+        if let Some(profile) = find_ssh_profile(&row.widget_name()) {
+            ssh_popover_clone.popdown();
+            open_ssh_profile_tab(&notebook_ssh, &shell_counter_ssh, &toast_ssh, &profile);
+        }
+    });
+
+    let window_ssh = window.clone();
+    let monitor_frames_ssh = monitor_frames.clone();
+    let ssh_popover_clone2 = ssh_popover.clone();
+    ssh_manage_btn.connect_clicked(move |_| {
+        ssh_popover_clone2.popdown();
+        show_settings_dialog_at_tab(&window_ssh, &monitor_frames_ssh, settings_tabs::SSH);
+    });
+
+    // Export project button handler - zip the project files to a chosen location
+    let window_export = window.clone();
+    let toast_export = toast_overlay.clone();
+    export_btn.connect_clicked(move |_| {
+        show_export_project_dialog(&window_export, &toast_export);
+    });
+
+    // Generate report button handler - write report.md and offer to open it
+    let toast_report = toast_overlay.clone();
+    report_btn.connect_clicked(move |_| {
+        generate_report_with_toast(&toast_report);
     });
 
     // Initialize system monitoring
-    setup_system_monitoring(&cpu_drawing, &ram_drawing, &net_drawing, &net_history);
+    setup_system_monitoring(&cpu_drawing, &ram_drawing, &disk_drawing, &net_drawing, &net_history, vpn_indicator);
 
     // Add handler to refresh notes tab when switched to
     notebook.connect_switch_page(move |notebook, page, page_num| {
-        // Reload notes tab when switched to
-        if page_num == tabs::NOTES {
-            if let Some(notes_page) = notebook.nth_page(Some(tabs::NOTES)) {
-                if let Some(notes_box) = notes_page.downcast_ref::<GtkBox>() {
-                    // Iterate through children to find ScrolledWindow (skip target combo if present)
-                    let mut child = notes_box.first_child();
-                    while let Some(current) = child {
-                        if let Some(scrolled) = current.downcast_ref::<gtk::ScrolledWindow>() {
-                            if let Some(text_view) = scrolled.child() {
-                                if let Some(text_view) = text_view.downcast_ref::<gtk::TextView>() {
-                                    let notes_path = get_file_path("notes.md");
-                                    if let Ok(content) = std::fs::read_to_string(notes_path) {
-                                        text_view.buffer().set_text(&content);
-                                        crate::ui::editor::apply_markdown_highlighting(text_view);
-                                    }
-                                    text_view.grab_focus();
-                                }
-                            }
-                            break;
-                        }
-                        child = current.next_sibling();
-                    }
-                }
-            }
+        // Reload markdown note tabs (notes.md and extra note files) when switched to
+        let page_name = page.widget_name();
+        if page_name.starts_with("note:") {
+            reload_note_page(page);
+            return;
         }
 
         // Also reload notes in split view tabs when switched to
@@ -385,11 +631,7 @@ fn create_main_window(app: &Application) {
                             if let Some(scrolled) = scrolled_child.downcast_ref::<gtk::ScrolledWindow>() {
                                 if let Some(text_view) = scrolled.child() {
                                     if let Some(text_view) = text_view.downcast_ref::<gtk::TextView>() {
-                                        let notes_path = get_file_path("notes.md");
-                                        if let Ok(content) = std::fs::read_to_string(notes_path) {
-                                            text_view.buffer().set_text(&content);
-                                            crate::ui::editor::apply_markdown_highlighting(text_view);
-                                        }
+                                        crate::ui::editor::reload_notes_view(text_view);
                                     }
                                 }
                             }
@@ -421,14 +663,63 @@ fn create_main_window(app: &Application) {
                     }
                 }
             }
-        } else if page_num >= tabs::FIRST_SHELL {
+        } else if !is_protected_tab(page_num) {
             // Focus terminal in shell tabs
             crate::ui::terminal::focus_terminal_in_page(page);
         }
     });
 
+    // Broadcast bar shown while broadcast mode is on
+    let broadcast_bar = create_broadcast_bar(&broadcast_btn, &toast_overlay);
+    let broadcast_bar_clone = broadcast_bar.clone();
+    broadcast_btn.connect_toggled(move |btn| {
+        broadcast_bar_clone.set_active(btn.is_active());
+    });
+    let broadcast_bar_clone2 = broadcast_bar.clone();
+    notebook.connect_page_added(move |_, _, _| {
+        broadcast_bar_clone2.update_count();
+    });
+    let broadcast_bar_clone3 = broadcast_bar.clone();
+    notebook.connect_page_removed(move |_, _, _| {
+        broadcast_bar_clone3.update_count();
+    });
+
     // Add global keyboard shortcuts
-    setup_keyboard_shortcuts(&window, &notebook, &new_shell_btn, &split_mode_btn, browser_btn.as_ref());
+    setup_keyboard_shortcuts(&window, &notebook, &new_shell_btn, &split_mode_btn, browser_btn.as_ref(), &shell_counter, &toast_overlay);
+    setup_broadcast_shortcut(&window, &broadcast_btn);
+
+    // Command palette actions reuse the header button handlers
+    let mut palette_actions = vec![
+        PaletteAction::new("New Shell Tab", clone_click(&new_shell_btn)),
+        PaletteAction::new("New Split View", clone_click(&split_mode_btn)),
+        PaletteAction::new("New Note Tab", clone_click(&new_note_btn)),
+    ];
+    if let Some(ref btn) = container_shell_btn {
+        palette_actions.push(PaletteAction::new("New Container Shell", clone_click(btn)));
+    }
+    if let Some(ref btn) = browser_btn {
+        palette_actions.push(PaletteAction::new("New Browser Tab", clone_click(btn)));
+    }
+    let notebook_reopen = notebook.clone();
+    let shell_counter_reopen = Rc::clone(&shell_counter);
+    let toast_reopen = toast_overlay.clone();
+    palette_actions.push(PaletteAction::new("Reopen Closed Tab", move || {
+        reopen_closed_tab(&notebook_reopen, &shell_counter_reopen, &toast_reopen);
+    }));
+    let notebook_duplicate = notebook.clone();
+    let shell_counter_duplicate = Rc::clone(&shell_counter);
+    let toast_duplicate = toast_overlay.clone();
+    palette_actions.push(PaletteAction::new("Duplicate Shell Tab", move || {
+        duplicate_current_tab(&notebook_duplicate, &shell_counter_duplicate, &toast_duplicate);
+    }));
+    let broadcast_btn_palette = broadcast_btn.clone();
+    palette_actions.push(PaletteAction::new("Toggle Broadcast Mode", move || {
+        broadcast_btn_palette.set_active(!broadcast_btn_palette.is_active());
+    }));
+    palette_actions.push(PaletteAction::new("Export Project", clone_click(&export_btn)));
+    palette_actions.push(PaletteAction::new("Generate Report", clone_click(&report_btn)));
+    palette_actions.push(PaletteAction::new("Settings", clone_click(&settings_btn)));
+    setup_command_palette_shortcut(&window, &notebook, palette_actions, &toast_overlay);
 
     // Status bar with creator and version (modern footer)
     let status_box = GtkBox::new(Orientation::Horizontal, 10);
@@ -450,6 +741,7 @@ fn create_main_window(app: &Application) {
 
     // Assemble layout
     content_box.append(&header_bar);
+    content_box.append(&broadcast_bar.revealer);
     content_box.append(&notebook);
     content_box.append(&status_box);
 
@@ -458,6 +750,281 @@ fn create_main_window(app: &Application) {
     window.present();
 }
 
+/// Stores the window size and maximized state so the next launch opens the same way.
+///
+/// The default size tracks the unmaximized size, so a maximized window restores to
+/// its previous dimensions when unmaximized.
+fn save_window_geometry(window: &adw::ApplicationWindow) -> Result<(), String> {
+    let (width, height) = window.default_size();
+    let mut settings = get_app_settings();
+    settings.window_geometry = WindowGeometry {
+        width,
+        height,
+        maximized: window.is_maximized(),
+    };
+    save_app_settings(&settings)
+}
+
+/// Tooltip for the broadcast toggle, including its shortcut when one is assigned
+fn broadcast_tooltip() -> String {
+    shortcut_hint("Broadcast to All Shells", "broadcast")
+}
+
+/// Bar with a command entry that sends input to every open shell
+#[derive(Clone)]
+struct BroadcastBar {
+    revealer: gtk::Revealer,
+    count_label: Label,
+    entry: gtk::Entry,
+}
+
+impl BroadcastBar {
+    /// Shows or hides the bar, focusing the entry when it appears
+    fn set_active(&self, active: bool) {
+        self.revealer.set_reveal_child(active);
+        if active {
+            self.update_count();
+            self.entry.grab_focus();
+        }
+    }
+
+    /// Refreshes the number of shells the next command will reach
+    fn update_count(&self) {
+        let count = terminal_count();
+        let text = match count {
+            1 => "Broadcasting to 1 shell".to_string(),
+            n => format!("Broadcasting to {} shells", n),
+        };
+        self.count_label.set_text(&text);
+    }
+}
+
+/// Creates the broadcast bar, hidden until broadcast mode is toggled on
+fn create_broadcast_bar(toggle: &gtk::ToggleButton, toast: &adw::ToastOverlay) -> BroadcastBar {
+    let bar_box = GtkBox::new(Orientation::Horizontal, 8);
+    bar_box.set_margin_top(6);
+    bar_box.set_margin_bottom(6);
+    bar_box.set_margin_start(12);
+    bar_box.set_margin_end(12);
+
+    let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+    icon.add_css_class("warning");
+
+    let count_label = Label::new(None);
+    count_label.add_css_class("warning");
+    count_label.add_css_class("heading");
+
+    let entry = gtk::Entry::builder()
+        .placeholder_text("Command to send to every shell")
+        .hexpand(true)
+        .build();
+
+    let send_btn = Button::with_label("Send");
+    send_btn.add_css_class("suggested-action");
+
+    let close_btn = Button::builder()
+        .icon_name("window-close-symbolic")
+        .tooltip_text("Stop Broadcasting")
+        .build();
+    close_btn.add_css_class("flat");
+
+    bar_box.append(&icon);
+    bar_box.append(&count_label);
+    bar_box.append(&entry);
+    bar_box.append(&send_btn);
+    bar_box.append(&close_btn);
+
+    let revealer = gtk::Revealer::builder()
+        .transition_type(gtk::RevealerTransitionType::SlideDown)
+        .reveal_child(false)
+        .child(&bar_box)
+        .build();
+
+    let bar = BroadcastBar { revealer, count_label, entry: entry.clone() };
+    bar.update_count();
+
+    let send: Rc<dyn Fn()> = {
+        let entry_clone = entry.clone();
+        let bar_clone = bar.clone();
+        let toast_clone = toast.clone();
+        Rc::new(move || {
+            let command = entry_clone.text().to_string();
+            if command.is_empty() {
+                return;
+            }
+            let sent = broadcast_to_terminals(&format!("{}\n", command));
+            bar_clone.update_count();
+            if sent == 0 {
+                toast_clone.add_toast(adw::Toast::new("No shells open to broadcast to"));
+                return;
+            }
+            entry_clone.set_text("");
+        })
+    };
+
+    let send_clone = Rc::clone(&send);
+    entry.connect_activate(move |_| send_clone());
+    send_btn.connect_clicked(move |_| send());
+
+    let toggle_clone = toggle.clone();
+    close_btn.connect_clicked(move |_| {
+        toggle_clone.set_active(false);
+    });
+
+    bar
+}
+
+/// Returns a closure that clicks the button, for dispatching palette actions
+fn clone_click(button: &Button) -> impl Fn() + 'static {
+    let button = button.clone();
+    move || button.emit_clicked()
+}
+
+/// Opens the command palette with the configured shortcut.
+///
+/// The controller runs in the capture phase so the shortcut also works while a
+/// terminal has focus, before VTE turns the key into shell input.
+fn setup_command_palette_shortcut(
+    window: &adw::ApplicationWindow,
+    notebook: &Notebook,
+    actions: Vec<PaletteAction>,
+    toast: &adw::ToastOverlay,
+) {
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let notebook_clone = notebook.clone();
+    let toast_clone = toast.clone();
+
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        if get_keyboard_shortcuts().matches("command_palette", keyval, modifier) {
+            show_command_palette(&notebook_clone, &actions, &toast_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    window.add_controller(key_controller);
+}
+
+/// Toggles broadcast mode with the configured shortcut
+fn setup_broadcast_shortcut(window: &adw::ApplicationWindow, broadcast_btn: &gtk::ToggleButton) {
+    let key_controller = gtk::EventControllerKey::new();
+    let broadcast_btn_clone = broadcast_btn.clone();
+
+    key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
+        if get_keyboard_shortcuts().matches("broadcast", keyval, modifier) {
+            broadcast_btn_clone.set_active(!broadcast_btn_clone.is_active());
+            return gtk::glib::Propagation::Stop;
+        }
+        gtk::glib::Propagation::Proceed
+    });
+    window.add_controller(key_controller);
+}
+
+/// Asks for a destination and exports the project files as a zip archive
+fn show_export_project_dialog(window: &adw::ApplicationWindow, toast: &adw::ToastOverlay) {
+    let chooser = gtk::FileChooserNative::new(
+        Some("Export Project"),
+        Some(window),
+        gtk::FileChooserAction::Save,
+        Some("Export"),
+        Some("Cancel"),
+    );
+    let project_name = get_base_dir()
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    chooser.set_current_name(&format!(
+        "penenv-{}-{}.zip",
+        project_name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let filter = gtk::FileFilter::new();
+    filter.set_name(Some("Zip archives"));
+    filter.add_pattern("*.zip");
+    chooser.add_filter(&filter);
+
+    let toast_clone = toast.clone();
+    chooser.connect_response(move |chooser, response| {
+        if response != gtk::ResponseType::Accept {
+            return;
+        }
+        let Some(path) = chooser.file().and_then(|f| f.path()) else { return };
+        let message = match export_project(&path) {
+            Ok(count) => format!("Exported {} files to {}", count, path.display()),
+            Err(e) => e,
+        };
+        let toast_msg = adw::Toast::new(&message);
+        toast_msg.set_timeout(3);
+        toast_clone.add_toast(toast_msg);
+    });
+
+    chooser.show();
+}
+
+/// Writes report.md to the base directory and shows a toast with an Open button
+fn generate_report_with_toast(toast: &adw::ToastOverlay) {
+    let path = match write_report() {
+        Ok(path) => path,
+        Err(e) => {
+            show_error_toast(&format!("Failed to generate report: {}", e));
+            return;
+        }
+    };
+
+    let toast_msg = adw::Toast::new(&format!("Report written to {}", path.display()));
+    toast_msg.set_timeout(5);
+    toast_msg.set_button_label(Some("Open"));
+    toast_msg.connect_button_clicked(move |_| {
+        let uri = gtk::gio::File::for_path(&path).uri();
+        if let Err(e) = gtk::gio::AppInfo::launch_default_for_uri(&uri, None::<&gtk::gio::AppLaunchContext>) {
+            show_error_toast(&format!("Failed to open report: {}", e));
+        }
+    });
+    toast.add_toast(toast_msg);
+}
+
+/// The header's system monitor frames, shown or hidden from the settings dialog
+#[derive(Clone)]
+pub struct MonitorFrames {
+    pub cpu: Frame,
+    pub ram: Frame,
+    pub net: Frame,
+    pub disk: Frame,
+}
+
+/// Draws a usage bar filling from the bottom, with the percentage on top.
+///
+/// `alert` draws the bar in red, e.g. for a nearly full disk.
+fn draw_usage_bar(cr: &gtk::cairo::Context, width: i32, height: i32, val: f64, alert: bool) {
+    // Background
+    cr.set_source_rgba(0.2, 0.2, 0.2, 0.3);
+    let _ = cr.rectangle(0.0, 0.0, width as f64, height as f64);
+    let _ = cr.fill();
+
+    // Bar (from bottom up)
+    let bar_height = height as f64 * val;
+    let y = height as f64 - bar_height;
+
+    if alert {
+        cr.set_source_rgba(0.9, 0.3, 0.3, 0.8);
+    } else {
+        cr.set_source_rgba(0.3, 0.6, 1.0, 0.8);
+    }
+    let _ = cr.rectangle(0.0, y, width as f64, bar_height);
+    let _ = cr.fill();
+
+    // Percentage text
+    cr.set_source_rgba(1.0, 1.0, 1.0, 0.9);
+    cr.select_font_face("Sans", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Bold);
+    cr.set_font_size(9.0);
+    let text = format!("{:.0}%", val * 100.0);
+    let Ok(extents) = cr.text_extents(&text) else { return };
+    let x = (width as f64 - extents.width()) / 2.0;
+    let y_pos = height as f64 / 2.0 + extents.height() / 2.0;
+    let _ = cr.move_to(x, y_pos);
+    let _ = cr.show_text(&text);
+}
+
 /// Creates a vertical bar monitor widget (CPU/RAM style)
 fn create_vertical_bar_monitor(label_text: &str, visible: bool) -> (Frame, gtk::DrawingArea) {
     let frame = Frame::new(None);
@@ -484,31 +1051,7 @@ fn create_vertical_bar_monitor(label_text: &str, visible: bool) -> (Frame, gtk::
     let value_clone = Rc::clone(&value);
 
     drawing_area.set_draw_func(move |_, cr, width, height| {
-        let val = *value_clone.borrow();
-
-        // Background
-        cr.set_source_rgba(0.2, 0.2, 0.2, 0.3);
-        let _ = cr.rectangle(0.0, 0.0, width as f64, height as f64);
-        let _ = cr.fill();
-
-        // Bar (from bottom up)
-        let bar_height = height as f64 * val;
-        let y = height as f64 - bar_height;
-
-        cr.set_source_rgba(0.3, 0.6, 1.0, 0.8);
-        let _ = cr.rectangle(0.0, y, width as f64, bar_height);
-        let _ = cr.fill();
-
-        // Percentage text
-        cr.set_source_rgba(1.0, 1.0, 1.0, 0.9);
-        cr.select_font_face("Sans", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Bold);
-        cr.set_font_size(9.0);
-        let text = format!("{:.0}", val * 100.0);
-        let extents = cr.text_extents(&text).unwrap();
-        let x = (width as f64 - extents.width()) / 2.0;
-        let y_pos = height as f64 / 2.0 + extents.height() / 2.0;
-        let _ = cr.move_to(x, y_pos);
-        let _ = cr.show_text(&text);
+        draw_usage_bar(cr, width, height, *value_clone.borrow(), false);
     });
 
     container.append(&label);
@@ -600,6 +1143,77 @@ fn create_network_monitor(visible: bool) -> (Frame, gtk::DrawingArea, Rc<RefCell
     (frame, drawing_area, history)
 }
 
+/// Reloads a note tab's text view from disk, unless it has unsaved edits, and focuses it
+fn reload_note_page(page: &gtk::Widget) {
+    if let Some(notes_box) = page.downcast_ref::<GtkBox>() {
+        if let Some(text_view) = crate::ui::editor::editor_text_view(notes_box) {
+            crate::ui::editor::reload_notes_view(&text_view);
+            text_view.grab_focus();
+        }
+    }
+}
+
+/// Normalizes a user-entered note name into a markdown file name in the base directory
+fn note_file_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains('/') || name.contains('\\') {
+        return None;
+    }
+    if name.ends_with(".md") {
+        Some(name.to_string())
+    } else {
+        Some(format!("{}.md", name))
+    }
+}
+
+/// Helper function to open a markdown note file as a new tab
+pub fn create_new_note_tab(notebook: &Notebook, toast: &adw::ToastOverlay, name: &str) {
+    let Some(file_name) = note_file_name(name) else {
+        let toast_msg = adw::Toast::new("Invalid note name");
+        toast_msg.set_timeout(2);
+        toast.add_toast(toast_msg);
+        return;
+    };
+
+    let path = get_file_path(&file_name);
+    let path_str = path.to_string_lossy().to_string();
+
+    // Switch to the tab if this note is already open
+    let page_name = format!("note:{}", path_str);
+    for i in 0..notebook.n_pages() {
+        if let Some(page) = notebook.nth_page(Some(i)) {
+            if page.widget_name() == page_name {
+                notebook.set_current_page(Some(i));
+                return;
+            }
+        }
+    }
+
+    let title = file_name.trim_end_matches(".md");
+    if !path.exists() {
+        if let Err(e) = std::fs::write(&path, format!("# {}\n\n", title)) {
+            let toast_msg = adw::Toast::new(&format!("Failed to create note: {}", e));
+            toast_msg.set_timeout(3);
+            toast.add_toast(toast_msg);
+            return;
+        }
+    }
+
+    let page_num = append_note_tab(notebook, &path_str, title);
+    notebook.set_current_page(Some(page_num));
+
+    let toast_msg = adw::Toast::new(&format!("Opened note {}", file_name));
+    toast_msg.set_timeout(1);
+    toast.add_toast(toast_msg);
+}
+
+/// Appends a notes editor tab for a markdown file, returning its page number
+fn append_note_tab(notebook: &Notebook, path: &str, title: &str) -> u32 {
+    let note_page = create_text_editor(path, None);
+    let note_label = create_editable_tab_label(&format!("📝 {}", title), notebook);
+    notebook.append_page(&note_page, Some(&note_label))
+}
+
 /// Creates a modern tab label with icon and text
 fn create_tab_label(icon: &str, text: &str) -> GtkBox {
     let tab_box = GtkBox::new(Orientation::Horizontal, 6);
@@ -610,20 +1224,21 @@ fn create_tab_label(icon: &str, text: &str) -> GtkBox {
     tab_box
 }
 
+/// Appends a shell tab and advances the shell counter, returning its page number
+fn append_shell_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay, enable_logging: bool, start_dir: Option<PathBuf>) -> u32 {
+    let shell_number = next_shell_number(&mut shell_counter.borrow_mut());
+    let shell_page = create_shell_tab(shell_number, notebook.clone(), Some(Rc::clone(shell_counter)), Some(toast.clone()), enable_logging, None, start_dir);
+    let shell_label = create_editable_tab_label(&shell_tab_label(shell_number, enable_logging), notebook);
+    notebook.append_page(&shell_page, Some(&shell_label))
+}
+
 /// Helper function to create a new shell tab
 pub fn create_new_shell_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay, enable_logging: bool) {
-    let mut counter = shell_counter.borrow_mut();
-    let shell_page = create_shell_tab(*counter, notebook.clone(), Some(Rc::clone(shell_counter)), Some(toast.clone()), enable_logging);
-    let label_text = if enable_logging {
-        format!("💻 Shell {}", *counter)
-    } else {
-        format!("🔇 Shell {}", *counter)
-    };
-    let shell_label = create_editable_tab_label(&label_text, notebook);
-    let page_num = notebook.append_page(&shell_page, Some(&shell_label));
+    let page_num = append_shell_tab(notebook, shell_counter, toast, enable_logging, None);
     notebook.set_current_page(Some(page_num));
-    focus_terminal_in_page(&shell_page.upcast_ref::<gtk::Widget>());
-    *counter += 1;
+    if let Some(shell_page) = notebook.nth_page(Some(page_num)) {
+        focus_terminal_in_page(&shell_page);
+    }
 
     let toast_msg = if enable_logging {
         adw::Toast::new("New shell tab created")
@@ -634,20 +1249,274 @@ pub fn create_new_shell_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usiz
     toast.add_toast(toast_msg);
 }
 
+/// Opens a new shell tab in the current shell's working directory, keeping its logging mode
+///
+/// Falls back to the base directory when the shell's directory can't be determined.
+pub fn duplicate_current_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) {
+    let Some(page) = notebook.current_page().and_then(|num| notebook.nth_page(Some(num))) else {
+        return;
+    };
+    let enable_logging = match page.widget_name().as_str() {
+        "shell" | "split_view" => true,
+        "shell_nolog" => false,
+        _ => {
+            let toast_msg = adw::Toast::new("Only shell tabs can be duplicated");
+            toast_msg.set_timeout(2);
+            toast.add_toast(toast_msg);
+            return;
+        }
+    };
+    let start_dir = find_terminal_in(&page)
+        .and_then(|terminal| terminal_working_dir(&terminal))
+        .unwrap_or_else(get_base_dir);
+
+    let page_num = append_shell_tab(notebook, shell_counter, toast, enable_logging, Some(start_dir.clone()));
+    notebook.set_current_page(Some(page_num));
+    if let Some(shell_page) = notebook.nth_page(Some(page_num)) {
+        focus_terminal_in_page(&shell_page);
+    }
+
+    let toast_msg = adw::Toast::new(&format!("Duplicated shell in {}", start_dir.display()));
+    toast_msg.set_timeout(1);
+    toast.add_toast(toast_msg);
+}
+
+/// Opens a shell tab running ssh for the given profile, named after it
+pub fn open_ssh_profile_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay, profile: &SshProfile) {
+    let page_num = {
+        // Named after the profile, so it doesn't use up a shell number
+        let shell_id = *shell_counter.borrow();
+        let shell_page = create_shell_tab(shell_id, notebook.clone(), Some(Rc::clone(shell_counter)), Some(toast.clone()), true, Some(profile.command_args()), None);
+        shell_page.set_widget_name(&format!("ssh:{}", profile.name));
+        let ssh_label = create_editable_tab_label(&format!("🔐 {}", profile.name), notebook);
+        notebook.append_page(&shell_page, Some(&ssh_label))
+    };
+    notebook.set_current_page(Some(page_num));
+    if let Some(shell_page) = notebook.nth_page(Some(page_num)) {
+        focus_terminal_in_page(&shell_page);
+    }
+}
+
+/// Refills the SSH profiles popover list from ssh_profiles.yaml
+fn populate_ssh_menu(list_box: &gtk::ListBox) {
+    while let Some(child) = list_box.first_child() {
+        list_box.remove(&child);
+    }
+
+    let profiles = load_ssh_profiles();
+    if profiles.is_empty() {
+        let empty_row = gtk::ListBoxRow::new();
+        empty_row.set_activatable(false);
+        let empty_label = Label::new(Some("No SSH profiles yet"));
+        empty_label.add_css_class("dim-label");
+        empty_label.set_margin_top(6);
+        empty_label.set_margin_bottom(6);
+        empty_row.set_child(Some(&empty_label));
+        list_box.append(&empty_row);
+        return;
+    }
+
+    for profile in profiles {
+        let row = adw::ActionRow::builder()
+            .title(gtk::glib::markup_escape_text(&profile.name))
+            .subtitle(gtk::glib::markup_escape_text(&profile.destination()))
+            .activatable(true)
+            .build();
+        row.set_widget_name(&profile.name);
+        list_box.append(&row);
+    }
+}
+
+/// Appends a split view tab, returning its page number
+fn append_split_view_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) -> u32 {
+    // Split views aren't numbered, so they leave the counter alone
+    let shell_id = *shell_counter.borrow();
+    let split_page = create_split_view_tab(shell_id, notebook.clone(), Some(Rc::clone(shell_counter)), Some(toast.clone()));
+    let split_label = create_editable_tab_label("📝💻 Split View", notebook);
+    notebook.append_page(&split_page, Some(&split_label))
+}
+
 /// Helper function to create a new split view tab
 pub fn create_new_split_view_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) {
-    let counter = shell_counter.borrow();
-    let split_page = create_split_view_tab(*counter, notebook.clone(), Some(Rc::clone(shell_counter)), Some(toast.clone()));
-    let split_label = create_editable_tab_label("📝💻 Split View", notebook);
-    let page_num = notebook.append_page(&split_page, Some(&split_label));
+    let page_num = append_split_view_tab(notebook, shell_counter, toast);
     notebook.set_current_page(Some(page_num));
-    focus_terminal_in_split_view(&split_page.upcast_ref::<gtk::Widget>());
+    if let Some(split_page) = notebook.nth_page(Some(page_num)) {
+        focus_terminal_in_split_view(&split_page);
+    }
 
     let toast_msg = adw::Toast::new("Split view tab created");
     toast_msg.set_timeout(1);
     toast.add_toast(toast_msg);
 }
 
+/// Reopens the most recently closed shell or split view tab with its saved label
+pub fn reopen_closed_tab(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) {
+    let Some(closed) = pop_closed_tab() else {
+        let toast_msg = adw::Toast::new("No recently closed tabs");
+        toast_msg.set_timeout(1);
+        toast.add_toast(toast_msg);
+        return;
+    };
+
+    match closed.kind {
+        ClosedTabKind::Shell { logging } => create_new_shell_tab(notebook, shell_counter, toast, logging),
+        ClosedTabKind::SplitView => create_new_split_view_tab(notebook, shell_counter, toast),
+        ClosedTabKind::Ssh { profile } => match find_ssh_profile(&profile) {
+            Some(profile) => open_ssh_profile_tab(notebook, shell_counter, toast, &profile),
+            None => {
+                let toast_msg = adw::Toast::new(&format!("SSH profile \"{}\" no longer exists", profile));
+                toast_msg.set_timeout(2);
+                toast.add_toast(toast_msg);
+                return;
+            }
+        },
+    }
+
+    // Restore the label the tab had when it was closed
+    if let Some(page_num) = notebook.current_page() {
+        set_tab_label_text(notebook, page_num, &closed.label);
+    }
+}
+
+/// Gets the text of an editable tab label
+fn tab_label_text(notebook: &Notebook, page: &gtk::Widget) -> String {
+    notebook
+        .tab_label(page)
+        .and_then(|tab| tab.first_child())
+        .and_then(|child| child.downcast::<Label>().ok())
+        .map(|label| label.text().to_string())
+        .unwrap_or_default()
+}
+
+/// Replaces the text of an editable tab label, ignoring empty text
+fn set_tab_label_text(notebook: &Notebook, page_num: u32, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(label) = notebook
+        .nth_page(Some(page_num))
+        .and_then(|page| notebook.tab_label(&page))
+        .and_then(|tab| tab.first_child())
+        .and_then(|child| child.downcast::<Label>().ok())
+    {
+        label.set_text(text);
+    }
+}
+
+/// Saves the shell, split view and note tabs after the fixed pages to the session file
+fn save_session_tabs(notebook: &Notebook, fixed_pages: u32) -> Result<(), String> {
+    let current = notebook.current_page().unwrap_or(0);
+    let persist_scrollback = is_scrollback_persistence_enabled();
+    let scrollback_dir = get_file_path(SCROLLBACK_DIR);
+    let mut scrollback_files: Vec<String> = Vec::new();
+    if persist_scrollback {
+        clear_saved_scrollback(&scrollback_dir);
+    }
+    let mut session = Session {
+        tabs: Vec::new(),
+        current_page: current.min(fixed_pages),
+    };
+
+    for i in fixed_pages..notebook.n_pages() {
+        let Some(page) = notebook.nth_page(Some(i)) else {
+            continue;
+        };
+        let page_name = page.widget_name();
+        let (kind, file) = match page_name.as_str() {
+            "shell" | "shell_nolog" | "split_view" => (page_name.to_string(), None),
+            name => match name.strip_prefix("note:") {
+                Some(path) => {
+                    let file = std::path::Path::new(path)
+                        .file_name()
+                        .map(|f| f.to_string_lossy().to_string());
+                    ("note".to_string(), file)
+                }
+                None => continue,
+            },
+        };
+        if i == current {
+            session.current_page = fixed_pages + session.tabs.len() as u32;
+        }
+        let label = tab_label_text(notebook, &page);
+        let scrollback = if kind != "note" && persist_scrollback {
+            let name = scrollback_file_name(&label, &scrollback_files);
+            match save_scrollback(&page, &scrollback_dir.join(&name)) {
+                Ok(true) => {
+                    scrollback_files.push(name.clone());
+                    Some(name)
+                }
+                Ok(false) => None,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        session.tabs.push(SessionTab {
+            kind,
+            label,
+            file,
+            scrollback,
+        });
+    }
+
+    save_session(&session)
+}
+
+/// Removes scrollback saved by an earlier session so closed tabs don't leave files behind
+fn clear_saved_scrollback(dir: &std::path::Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Recreates the tabs saved by the previous session, returning how many were restored
+fn restore_session_tabs(notebook: &Notebook, shell_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) -> usize {
+    let Some(session) = load_session() else {
+        return 0;
+    };
+
+    let mut restored = 0;
+    for tab in &session.tabs {
+        let page_num = match (tab.kind.as_str(), &tab.file) {
+            ("shell", _) => append_shell_tab(notebook, shell_counter, toast, true, None),
+            ("shell_nolog", _) => append_shell_tab(notebook, shell_counter, toast, false, None),
+            ("split_view", _) => append_split_view_tab(notebook, shell_counter, toast),
+            ("note", Some(file)) => {
+                // Skip notes that no longer exist in this base directory
+                let path = get_file_path(file);
+                if !path.exists() {
+                    continue;
+                }
+                append_note_tab(notebook, &path.to_string_lossy(), file.trim_end_matches(".md"))
+            }
+            _ => continue,
+        };
+        set_tab_label_text(notebook, page_num, &tab.label);
+        if let (true, Some(file)) = (is_scrollback_persistence_enabled(), &tab.scrollback) {
+            if let Some(page) = notebook.nth_page(Some(page_num)) {
+                restore_scrollback(&page, &get_file_path(SCROLLBACK_DIR).join(file));
+            }
+        }
+        restored += 1;
+    }
+
+    if restored > 0 && session.current_page < notebook.n_pages() {
+        notebook.set_current_page(Some(session.current_page));
+    }
+    restored
+}
+
 /// Helper function to create a new browser tab
 pub fn create_new_browser_tab(notebook: &Notebook, browser_counter: &Rc<RefCell<usize>>, toast: &adw::ToastOverlay) {
     let mut counter = browser_counter.borrow_mut();
@@ -785,11 +1654,7 @@ fn show_container_selector_dialog(
 
                 match mgr.get_connection_command(&container.name) {
                     Ok((cmd, is_exec)) => {
-                        let shell_id = {
-                            let mut counter = shell_counter_connect.borrow_mut();
-                            *counter += 1;
-                            *counter
-                        };
+                        let shell_id = *shell_counter_connect.borrow();
 
                         let tab_icon = if split_view { "📝" } else if is_exec { "📦" } else { "🔗" };
                         let tab_name = format!("{} {}", tab_icon, container.name);
@@ -1037,76 +1902,153 @@ fn show_desktop_selector_dialog(
     popup.present();
 }
 
+/// Creates a popover with one mini bar per CPU core, opened by clicking the CPU monitor
+fn create_cpu_cores_popover(
+    cpu_drawing: &gtk::DrawingArea,
+    core_values: &Rc<RefCell<Vec<f64>>>,
+    core_count: usize,
+) -> (gtk::Popover, gtk::DrawingArea) {
+    let popover = gtk::Popover::new();
+    popover.set_parent(cpu_drawing);
+
+    let container = GtkBox::new(Orientation::Vertical, 6);
+    container.set_margin_top(8);
+    container.set_margin_bottom(8);
+    container.set_margin_start(8);
+    container.set_margin_end(8);
+
+    let title = Label::new(Some(&format!("CPU Cores ({})", core_count)));
+    title.add_css_class("caption-heading");
+    title.set_halign(gtk::Align::Start);
+
+    let drawing_area = gtk::DrawingArea::new();
+    drawing_area.set_content_width((core_count as i32 * 16).max(120));
+    drawing_area.set_content_height(70);
+
+    let values_for_draw = Rc::clone(core_values);
+    drawing_area.set_draw_func(move |_, cr, width, height| {
+        let values = values_for_draw.borrow();
+        if values.is_empty() {
+            return;
+        }
+
+        // Leave room under the bars for core numbers
+        let label_height = 10.0;
+        let bar_area = height as f64 - label_height;
+        let slot = width as f64 / values.len() as f64;
+        let bar_width = (slot - 2.0).max(1.0);
+
+        cr.select_font_face("Sans", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Normal);
+        cr.set_font_size(7.0);
+        for (i, val) in values.iter().enumerate() {
+            let x = i as f64 * slot + 1.0;
+
+            cr.set_source_rgba(0.2, 0.2, 0.2, 0.3);
+            let _ = cr.rectangle(x, 0.0, bar_width, bar_area);
+            let _ = cr.fill();
+
+            let bar_height = bar_area * val.clamp(0.0, 1.0);
+            cr.set_source_rgba(0.3, 0.6, 1.0, 0.8);
+            let _ = cr.rectangle(x, bar_area - bar_height, bar_width, bar_height);
+            let _ = cr.fill();
+
+            let text = i.to_string();
+            if let Ok(extents) = cr.text_extents(&text) {
+                if extents.width() <= slot {
+                    cr.set_source_rgba(0.6, 0.6, 0.6, 0.9);
+                    let _ = cr.move_to(x + (bar_width - extents.width()) / 2.0, height as f64 - 1.0);
+                    let _ = cr.show_text(&text);
+                }
+            }
+        }
+    });
+
+    container.append(&title);
+    container.append(&drawing_area);
+    popover.set_child(Some(&container));
+
+    let gesture = gtk::GestureClick::new();
+    let popover_clone = popover.clone();
+    let drawing_clone = drawing_area.clone();
+    gesture.connect_released(move |_, _, _, _| {
+        drawing_clone.queue_draw();
+        popover_clone.popup();
+    });
+    cpu_drawing.add_controller(gesture);
+    cpu_drawing.set_cursor_from_name(Some("pointer"));
+    cpu_drawing.set_tooltip_text(Some("Click for per-core usage"));
+
+    (popover, drawing_area)
+}
+
+/// Gets the used fraction of the disk whose mount point contains the path
+fn disk_usage_for(disks: &Disks, path: &std::path::Path) -> Option<f64> {
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())?;
+    let total = disk.total_space() as f64;
+    if total <= 0.0 {
+        return None;
+    }
+    Some((total - disk.available_space() as f64) / total)
+}
+
 fn setup_system_monitoring(
     cpu_drawing: &gtk::DrawingArea,
     ram_drawing: &gtk::DrawingArea,
+    disk_drawing: &gtk::DrawingArea,
     net_drawing: &gtk::DrawingArea,
     net_history: &Rc<RefCell<Vec<(f64, f64)>>>,
+    vpn_indicator: VpnIndicator,
 ) {
     let sys = Rc::new(RefCell::new(System::new_all()));
     let networks = Rc::new(RefCell::new(Networks::new_with_refreshed_list()));
-    let prev_rx = Rc::new(RefCell::new(0u64));
-    let prev_tx = Rc::new(RefCell::new(0u64));
+    update_default_interface(&networks.borrow());
+    let disks = Rc::new(RefCell::new(Disks::new_with_refreshed_list()));
+    let base_dir = get_base_dir().canonicalize().unwrap_or_else(|_| get_base_dir());
+    // Seed the previous totals so the first sample isn't the whole uptime's traffic
+    let monitored = Rc::new(RefCell::new(get_monitored_interfaces()));
+    let (initial_rx, initial_tx) = network_totals(&networks.borrow(), &monitored.borrow());
+    let prev_rx = Rc::new(RefCell::new(initial_rx));
+    let prev_tx = Rc::new(RefCell::new(initial_tx));
 
     let cpu_value = Rc::new(RefCell::new(0.0f64));
     let ram_value = Rc::new(RefCell::new(0.0f64));
+    let disk_value = Rc::new(RefCell::new(0.0f64));
 
     let cpu_drawing_clone = cpu_drawing.clone();
     let ram_drawing_clone = ram_drawing.clone();
     let net_drawing_clone = net_drawing.clone();
+    let disk_drawing_clone = disk_drawing.clone();
     let net_history_clone = Rc::clone(net_history);
 
     // Store drawing area value updaters
     let cpu_value_for_draw = Rc::clone(&cpu_value);
     cpu_drawing.set_draw_func(move |_, cr, width, height| {
-        let val = *cpu_value_for_draw.borrow();
+        draw_usage_bar(cr, width, height, *cpu_value_for_draw.borrow(), false);
+    });
 
-        cr.set_source_rgba(0.2, 0.2, 0.2, 0.3);
-        let _ = cr.rectangle(0.0, 0.0, width as f64, height as f64);
-        let _ = cr.fill();
+    // Per-core breakdown shown when the CPU monitor is clicked
+    let core_values: Rc<RefCell<Vec<f64>>> = Rc::new(RefCell::new(Vec::new()));
+    let core_count = sys.borrow().cpus().len();
+    let (cores_popover, cores_drawing) = create_cpu_cores_popover(cpu_drawing, &core_values, core_count);
 
-        let bar_height = height as f64 * val;
-        let y = height as f64 - bar_height;
-
-        cr.set_source_rgba(0.3, 0.6, 1.0, 0.8);
-        let _ = cr.rectangle(0.0, y, width as f64, bar_height);
-        let _ = cr.fill();
-
-        cr.set_source_rgba(1.0, 1.0, 1.0, 0.9);
-        cr.select_font_face("Sans", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Bold);
-        cr.set_font_size(9.0);
-        let text = format!("{:.0}%", val * 100.0);
-        let extents = cr.text_extents(&text).unwrap();
-        let x = (width as f64 - extents.width()) / 2.0;
-        let y_pos = height as f64 / 2.0 + extents.height() / 2.0;
-        let _ = cr.move_to(x, y_pos);
-        let _ = cr.show_text(&text);
-    });
+    // Per-interface breakdown shown when the network monitor is clicked
+    let (interfaces_popover, interfaces_grid) = create_network_interfaces_popover(net_drawing);
+    let interface_prev: Rc<RefCell<HashMap<String, (u64, u64)>>> = Rc::new(RefCell::new(HashMap::new()));
 
     let ram_value_for_draw = Rc::clone(&ram_value);
     ram_drawing.set_draw_func(move |_, cr, width, height| {
-        let val = *ram_value_for_draw.borrow();
-
-        cr.set_source_rgba(0.2, 0.2, 0.2, 0.3);
-        let _ = cr.rectangle(0.0, 0.0, width as f64, height as f64);
-        let _ = cr.fill();
-
-        let bar_height = height as f64 * val;
-        let y = height as f64 - bar_height;
-
-        cr.set_source_rgba(0.3, 0.6, 1.0, 0.8);
-        let _ = cr.rectangle(0.0, y, width as f64, bar_height);
-        let _ = cr.fill();
+        draw_usage_bar(cr, width, height, *ram_value_for_draw.borrow(), false);
+    });
 
-        cr.set_source_rgba(1.0, 1.0, 1.0, 0.9);
-        cr.select_font_face("Sans", gtk::cairo::FontSlant::Normal, gtk::cairo::FontWeight::Bold);
-        cr.set_font_size(9.0);
-        let text = format!("{:.0}%", val * 100.0);
-        let extents = cr.text_extents(&text).unwrap();
-        let x = (width as f64 - extents.width()) / 2.0;
-        let y_pos = height as f64 / 2.0 + extents.height() / 2.0;
-        let _ = cr.move_to(x, y_pos);
-        let _ = cr.show_text(&text);
+    let disk_value_for_draw = Rc::clone(&disk_value);
+    disk_drawing.set_draw_func(move |_, cr, width, height| {
+        // Turn red when the disk is nearly full
+        let val = *disk_value_for_draw.borrow();
+        draw_usage_bar(cr, width, height, val, val >= 0.9);
     });
 
     // Network line graph drawing
@@ -1190,9 +2132,17 @@ fn setup_system_monitoring(
         }
     });
 
-    glib::timeout_add_seconds_local(1, move || {
+    let last_sample = Rc::new(Cell::new(Instant::now()));
+
+    let tick = move || {
+        // Rates are scaled by the real time since the previous sample
+        let elapsed = last_sample.replace(Instant::now()).elapsed().as_secs_f64().max(0.001);
+
         sys.borrow_mut().refresh_all();
-        networks.borrow_mut().refresh();
+        // Refresh the interface list too so VPN interfaces coming and going are noticed
+        networks.borrow_mut().refresh_list();
+        // Commands take lhost and the interface from here instead of rescanning on every run
+        update_default_interface(&networks.borrow());
 
         let sys_ref = sys.borrow();
 
@@ -1201,6 +2151,31 @@ fn setup_system_monitoring(
         *cpu_value.borrow_mut() = (cpu_usage / 100.0) as f64;
         cpu_drawing_clone.queue_draw();
 
+        // Per-core usage, only redrawn while the popover is open
+        *core_values.borrow_mut() = sys_ref
+            .cpus()
+            .iter()
+            .map(|cpu| (cpu.cpu_usage() / 100.0) as f64)
+            .collect();
+        if cores_popover.is_visible() {
+            cores_drawing.queue_draw();
+        }
+
+        // Hovering the CPU monitor names the busiest core, e.g. a single-threaded cracking job
+        let busiest = core_values
+            .borrow()
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((core, usage)) = busiest {
+            cpu_drawing_clone.set_tooltip_text(Some(&format!(
+                "Busiest core: #{} at {:.0}%\nClick for per-core usage",
+                core,
+                usage * 100.0
+            )));
+        }
+
         // RAM usage
         let total_mem = sys_ref.total_memory() as f64;
         let used_mem = sys_ref.used_memory() as f64;
@@ -1208,27 +2183,26 @@ fn setup_system_monitoring(
         *ram_value.borrow_mut() = mem_percent;
         ram_drawing_clone.queue_draw();
 
-        // Network usage
-        let mut total_rx = 0u64;
-        let mut total_tx = 0u64;
-        for (_name, data) in networks.borrow().iter() {
-            total_rx += data.total_received();
-            total_tx += data.total_transmitted();
+        // Disk usage of the filesystem containing the base dir
+        disks.borrow_mut().refresh();
+        if let Some(usage) = disk_usage_for(&disks.borrow(), &base_dir) {
+            *disk_value.borrow_mut() = usage;
+            disk_drawing_clone.queue_draw();
         }
 
-        let prev_rx_val = *prev_rx.borrow();
-        let prev_tx_val = *prev_tx.borrow();
+        // Network usage of the monitored interfaces
+        let selected = get_monitored_interfaces();
+        let (total_rx, total_tx) = network_totals(&networks.borrow(), &selected);
+        if *monitored.borrow() != selected {
+            // Reseed after a selection change so it doesn't show up as traffic
+            *prev_rx.borrow_mut() = total_rx;
+            *prev_tx.borrow_mut() = total_tx;
+            *monitored.borrow_mut() = selected;
+        }
 
-        let rx_speed = if prev_rx_val > 0 {
-            ((total_rx - prev_rx_val) as f64) / 1024.0 // KB/s
-        } else {
-            0.0
-        };
-        let tx_speed = if prev_tx_val > 0 {
-            ((total_tx - prev_tx_val) as f64) / 1024.0 // KB/s
-        } else {
-            0.0
-        };
+        // A counter reset (interface down/up) yields 0 rather than a spike or underflow
+        let rx_speed = (total_rx.saturating_sub(*prev_rx.borrow()) as f64) / 1024.0 / elapsed; // KB/s
+        let tx_speed = (total_tx.saturating_sub(*prev_tx.borrow()) as f64) / 1024.0 / elapsed; // KB/s
 
         *prev_rx.borrow_mut() = total_rx;
         *prev_tx.borrow_mut() = total_tx;
@@ -1243,10 +2217,219 @@ fn setup_system_monitoring(
 
         net_drawing_clone.queue_draw();
 
-        glib::ControlFlow::Continue
+        // Per-interface rates, only computed while the popover is open
+        if interfaces_popover.is_visible() {
+            update_interface_rates(&interfaces_grid, &networks.borrow(), &mut interface_prev.borrow_mut(), elapsed);
+        } else {
+            interface_prev.borrow_mut().clear();
+        }
+
+        // VPN interface status
+        let vpn_interface = get_vpn_interface();
+        let vpn_ip = networks.borrow().get(&vpn_interface).map(|data| {
+            let ips = data.ip_networks();
+            ips.iter()
+                .find(|ip| ip.addr.is_ipv4())
+                .or_else(|| ips.first())
+                .map(|ip| ip.addr.to_string())
+                .unwrap_or_default()
+        });
+        vpn_indicator.update(&vpn_interface, vpn_ip);
+    };
+
+    MONITOR_TICK.with(|t| *t.borrow_mut() = Some(Rc::new(tick)));
+    restart_system_monitoring();
+}
+
+/// (Re)starts the system monitor timer at the configured interval
+pub fn restart_system_monitoring() {
+    let Some(tick) = MONITOR_TICK.with(|t| t.borrow().clone()) else {
+        return;
+    };
+    MONITOR_SOURCE.with(|source| {
+        if let Some(id) = source.borrow_mut().take() {
+            id.remove();
+        }
+        let interval = Duration::from_millis(get_monitor_interval_ms() as u64);
+        let id = glib::timeout_add_local(interval, move || {
+            tick();
+            glib::ControlFlow::Continue
+        });
+        *source.borrow_mut() = Some(id);
     });
 }
 
+/// Sums the received and transmitted byte counters of the selected interfaces (all when empty)
+fn network_totals(networks: &Networks, selected: &[String]) -> (u64, u64) {
+    networks
+        .iter()
+        .filter(|(name, _)| selected.is_empty() || selected.contains(name))
+        .fold((0, 0), |(rx, tx), (_name, data)| {
+            (rx + data.total_received(), tx + data.total_transmitted())
+        })
+}
+
+/// Formats a rate in KB/s the way the network monitor does
+fn format_rate(kb_per_sec: f64) -> String {
+    if kb_per_sec >= 1024.0 {
+        format!("{:.1} MB/s", kb_per_sec / 1024.0)
+    } else {
+        format!("{:.0} KB/s", kb_per_sec)
+    }
+}
+
+/// Creates a popover listing per-interface rates, opened by clicking the network monitor
+fn create_network_interfaces_popover(net_drawing: &gtk::DrawingArea) -> (gtk::Popover, gtk::Grid) {
+    let popover = gtk::Popover::new();
+    popover.set_parent(net_drawing);
+
+    let container = GtkBox::new(Orientation::Vertical, 6);
+    container.set_margin_top(8);
+    container.set_margin_bottom(8);
+    container.set_margin_start(8);
+    container.set_margin_end(8);
+
+    let title = Label::new(Some("Network Interfaces"));
+    title.add_css_class("caption-heading");
+    title.set_halign(gtk::Align::Start);
+
+    let grid = gtk::Grid::new();
+    grid.set_column_spacing(12);
+    grid.set_row_spacing(2);
+
+    container.append(&title);
+    container.append(&grid);
+    popover.set_child(Some(&container));
+
+    let gesture = gtk::GestureClick::new();
+    let popover_clone = popover.clone();
+    gesture.connect_released(move |_, _, _, _| {
+        popover_clone.popup();
+    });
+    net_drawing.add_controller(gesture);
+    net_drawing.set_cursor_from_name(Some("pointer"));
+    net_drawing.set_tooltip_text(Some("Click for per-interface traffic"));
+
+    (popover, grid)
+}
+
+/// Rebuilds the per-interface grid from the byte counters since the previous sample
+fn update_interface_rates(grid: &gtk::Grid, networks: &Networks, prev: &mut HashMap<String, (u64, u64)>, elapsed: f64) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+
+    let mut names: Vec<&String> = networks.keys().collect();
+    names.sort();
+
+    let mut current = HashMap::new();
+    for (row, name) in names.into_iter().enumerate() {
+        let data = &networks[name];
+        let totals = (data.total_received(), data.total_transmitted());
+        let (rx_text, tx_text) = match prev.get(name) {
+            Some(&(prev_rx, prev_tx)) => (
+                format_rate(totals.0.saturating_sub(prev_rx) as f64 / 1024.0 / elapsed),
+                format_rate(totals.1.saturating_sub(prev_tx) as f64 / 1024.0 / elapsed),
+            ),
+            // The first sample has nothing to compare against
+            None => ("…".to_string(), "…".to_string()),
+        };
+        current.insert(name.clone(), totals);
+
+        let name_label = Label::new(Some(name));
+        name_label.set_halign(gtk::Align::Start);
+        let rx_label = Label::new(Some(&format!("▼ {}", rx_text)));
+        rx_label.set_halign(gtk::Align::End);
+        rx_label.add_css_class("monospace");
+        rx_label.add_css_class("success");
+        let tx_label = Label::new(Some(&format!("▲ {}", tx_text)));
+        tx_label.set_halign(gtk::Align::End);
+        tx_label.add_css_class("monospace");
+        tx_label.add_css_class("accent");
+
+        let row = row as i32;
+        grid.attach(&name_label, 0, row, 1, 1);
+        grid.attach(&rx_label, 1, row, 1, 1);
+        grid.attach(&tx_label, 2, row, 1, 1);
+    }
+    *prev = current;
+}
+
+/// Header indicator showing whether the configured VPN interface is up
+struct VpnIndicator {
+    frame: Frame,
+    dot: Label,
+    label: Label,
+    was_up: Rc<Cell<bool>>,
+}
+
+impl VpnIndicator {
+    /// Updates the dot and address, flashing the dot when the interface goes away
+    fn update(&self, interface: &str, ip: Option<String>) {
+        self.frame.set_visible(!interface.is_empty());
+        if interface.is_empty() {
+            return;
+        }
+
+        let is_up = ip.is_some();
+        self.dot.remove_css_class(if is_up { "error" } else { "success" });
+        self.dot.add_css_class(if is_up { "success" } else { "error" });
+        let text = match ip {
+            Some(ip) if !ip.is_empty() => format!("{} {}", interface, ip),
+            Some(_) => interface.to_string(),
+            None => format!("{} down", interface),
+        };
+        self.label.set_text(&text);
+
+        if self.was_up.replace(is_up) && !is_up {
+            let dot = self.dot.clone();
+            let flashes = Rc::new(Cell::new(0));
+            glib::timeout_add_local(std::time::Duration::from_millis(250), move || {
+                flashes.set(flashes.get() + 1);
+                dot.set_opacity(if flashes.get() % 2 == 0 { 1.0 } else { 0.2 });
+                if flashes.get() >= 8 {
+                    dot.set_opacity(1.0);
+                    glib::ControlFlow::Break
+                } else {
+                    glib::ControlFlow::Continue
+                }
+            });
+        }
+    }
+}
+
+/// Creates the VPN indicator with a status dot and interface address
+fn create_vpn_indicator() -> VpnIndicator {
+    let frame = Frame::new(None);
+    frame.add_css_class("card");
+    frame.set_visible(!get_vpn_interface().is_empty());
+    frame.set_tooltip_text(Some("VPN interface status (configure in Settings)"));
+
+    let container = GtkBox::new(Orientation::Horizontal, 4);
+    container.set_margin_top(4);
+    container.set_margin_bottom(4);
+    container.set_margin_start(6);
+    container.set_margin_end(6);
+    container.set_valign(gtk::Align::Center);
+
+    let dot = Label::new(Some("●"));
+    dot.add_css_class("error");
+
+    let label = Label::new(Some(&format!("{} down", get_vpn_interface())));
+    label.add_css_class("caption");
+
+    container.append(&dot);
+    container.append(&label);
+    frame.set_child(Some(&container));
+
+    VpnIndicator {
+        frame,
+        dot,
+        label,
+        was_up: Rc::new(Cell::new(false)),
+    }
+}
+
 /// Sets up global keyboard shortcuts
 fn setup_keyboard_shortcuts(
     window: &adw::ApplicationWindow,
@@ -1254,40 +2437,56 @@ fn setup_keyboard_shortcuts(
     new_shell_btn: &Button,
     split_mode_btn: &Button,
     browser_btn: Option<&Button>,
+    shell_counter: &Rc<RefCell<usize>>,
+    toast: &adw::ToastOverlay,
 ) {
     let key_controller = gtk::EventControllerKey::new();
     let notebook_clone = notebook.clone();
     let new_shell_btn_clone = new_shell_btn.clone();
     let split_mode_btn_clone = split_mode_btn.clone();
     let browser_btn_clone = browser_btn.cloned();
+    let shell_counter_clone = Rc::clone(shell_counter);
+    let toast_clone = toast.clone();
 
     key_controller.connect_key_pressed(move |_, keyval, _, modifier| {
-        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
-            let shortcuts = get_keyboard_shortcuts();
-            let key_name = keyval.name().unwrap_or_default().to_string();
-
-            // Check for Ctrl+Shift combinations
-            if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) {
-                if let Some(ref new_shell_key) = shortcuts.new_shell {
-                    if &key_name == new_shell_key {
-                        new_shell_btn_clone.emit_clicked();
-                        return gtk::glib::Propagation::Stop;
-                    }
-                }
+        let shortcuts = get_keyboard_shortcuts();
 
-                if let Some(ref new_split_key) = shortcuts.new_split {
-                    if &key_name == new_split_key {
-                        split_mode_btn_clone.emit_clicked();
-                        return gtk::glib::Propagation::Stop;
-                    }
-                }
+        if shortcuts.matches("new_shell", keyval, modifier) {
+            new_shell_btn_clone.emit_clicked();
+            return gtk::glib::Propagation::Stop;
+        }
 
-                // Ctrl+Shift+B: New browser tab (only if enabled)
-                if key_name == "B" {
-                    if let Some(ref btn) = browser_btn_clone {
-                        btn.emit_clicked();
-                        return gtk::glib::Propagation::Stop;
-                    }
+        if shortcuts.matches("new_split", keyval, modifier) {
+            split_mode_btn_clone.emit_clicked();
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if shortcuts.matches("reopen_tab", keyval, modifier) {
+            reopen_closed_tab(&notebook_clone, &shell_counter_clone, &toast_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        // Close the current tab; fixed tabs are left alone
+        if shortcuts.matches("close_tab", keyval, modifier) {
+            close_current_tab(&notebook_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if shortcuts.matches("duplicate_tab", keyval, modifier) {
+            duplicate_current_tab(&notebook_clone, &shell_counter_clone, &toast_clone);
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if handle_tab_cycle_keys(&notebook_clone, keyval, modifier) {
+            return gtk::glib::Propagation::Stop;
+        }
+
+        if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) {
+            // Ctrl+Shift+B: New browser tab (only if enabled)
+            if modifier.contains(gtk::gdk::ModifierType::SHIFT_MASK) && keyval == gtk::gdk::Key::B {
+                if let Some(ref btn) = browser_btn_clone {
+                    btn.emit_clicked();
+                    return gtk::glib::Propagation::Stop;
                 }
             }
 